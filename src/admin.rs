@@ -0,0 +1,823 @@
+//! API administrativa (`/admin`)
+//!
+//! Para corrigir partidas travadas sem reiniciar o servidor. Todas as rotas
+//! exigem `Authorization: Bearer <ADMIN_TOKEN>`; as que alteram algo entram no
+//! registro de auditoria como qualquer outra rota.
+
+use axum::{
+    extract::{Path, Query, Request, State},
+    http::header,
+    middleware::{self, Next},
+    response::{IntoResponse, Json, Response},
+    routing::{delete, get, post, put},
+    Extension, Router,
+};
+use serde::{Deserialize, Serialize};
+use tatic_lib::{Action, GameState, PlayerId};
+use tracing::{info, warn};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::arena::{self, ArenaConfig, ArenaReport};
+use crate::audit::{self, AuditDetails, AuditEntry};
+use crate::bots::BotCredentials;
+use crate::error::{ApiError, ApiJson, ErrorResponse};
+use crate::i18n::Lang;
+use crate::openapi::GameStateSchema;
+use crate::players::{Player, ProfileUpdate};
+use crate::routes::SuccessResponse;
+use crate::state::{AppState, MatchId};
+use crate::tournament::{Tournament, TournamentId};
+use crate::webhooks::{DeadLetter, DeliveryAttempt, Webhook, WebhookCredentials, WebhookEvent};
+
+/// Cria as rotas administrativas (o estado é aplicado por quem as junta)
+pub fn admin_routes(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/admin/matches", get(list_matches_handler))
+        .route("/admin/matches/{id}", delete(close_match_handler))
+        .route("/admin/matches/{id}/state", put(set_state_handler))
+        .route("/admin/matches/{id}/end_turn", post(end_turn_handler))
+        .route("/admin/matches/{id}/observers", get(observers_handler))
+        .route("/admin/matches/{id}/kick", post(kick_observers_handler))
+        .route("/admin/notice", post(notice_handler))
+        .route("/admin/arena/run", post(arena_handler))
+        .route("/admin/bots", post(register_bot_handler))
+        .route("/admin/players/{id}", post(update_player_handler))
+        .route("/admin/players/{id}/language", post(set_language_handler))
+        .route("/admin/tournaments/{id}/forfeit", post(forfeit_tournament_handler))
+        .route("/admin/audit", get(audit_handler))
+        .route("/admin/webhooks", get(list_webhooks_handler).post(create_webhook_handler))
+        .route("/admin/webhooks/{id}", delete(delete_webhook_handler))
+        .route("/admin/webhooks/{id}/deliveries", get(webhook_deliveries_handler))
+        .route("/admin/webhooks/dead_letters", get(dead_letters_handler))
+        .route("/admin/webhooks/dead_letters/{id}/retry", post(retry_dead_letter_handler))
+        .route_layer(middleware::from_fn_with_state(state, require_admin))
+}
+
+/// Middleware que exige o token de administrador
+async fn require_admin(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let provided = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+
+    match (&state.admin_token, provided) {
+        (Some(expected), Some(provided)) if constant_time_eq(expected, provided) => {
+            next.run(request).await
+        }
+        _ => {
+            warn!("🔒 Acesso negado a {}", request.uri().path());
+            ApiError::Unauthorized.into_response()
+        }
+    }
+}
+
+/// Compara sem sair cedo, para não vazar o token pelo tempo de resposta
+pub(crate) fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len() && a.bytes().zip(b.bytes()).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn match_not_found(match_id: &str) -> ApiError {
+    ApiError::MatchNotFound {
+        match_id: match_id.to_string(),
+    }
+}
+
+/// Partida vista pelo administrador
+#[derive(Serialize, ToSchema)]
+pub struct AdminMatch {
+    pub id: MatchId,
+    #[schema(value_type = Vec<String>)]
+    pub players: Vec<PlayerId>,
+    #[schema(value_type = String)]
+    pub turn: PlayerId,
+    pub turn_count: u32,
+    pub finished: bool,
+    /// WebSockets conectados em `/ws`
+    pub observers: usize,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// GET /admin/matches - Partidas com observers conectados
+#[utoipa::path(
+    get,
+    path = "/admin/matches",
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "Partidas ativas", body = SuccessResponse<Vec<AdminMatch>>),
+        (status = 401, description = "Token ausente ou inválido (unauthorized)", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn list_matches_handler(
+    State(state): State<AppState>,
+) -> Json<SuccessResponse<Vec<AdminMatch>>> {
+    info!("🛠️ GET /admin/matches");
+
+    let handles: Vec<_> = state.matches.read().await.values().cloned().collect();
+    let mut matches = Vec::with_capacity(handles.len());
+    for handle in handles {
+        let Some(match_data) = handle.snapshot().await else {
+            continue;
+        };
+        matches.push(AdminMatch {
+            observers: handle.observer_count().await,
+            finished: match_data.is_finished(),
+            id: match_data.id,
+            players: match_data.state.players,
+            turn: match_data.state.turn,
+            turn_count: match_data.state.turn_count,
+            created_at: match_data.created_at,
+            updated_at: match_data.updated_at,
+        });
+    }
+    matches.sort_by_key(|m| m.created_at);
+
+    Json(SuccessResponse {
+        success: true,
+        data: matches,
+    })
+}
+
+/// PUT /admin/matches/{id}/state - Substitui o estado do jogo
+///
+/// Observers recebem `state_update` como numa ação normal. `players` tem de
+/// ser o da partida, e partidas terminadas não podem ser alteradas: o
+/// resultado já foi contado.
+#[utoipa::path(
+    put,
+    path = "/admin/matches/{id}/state",
+    params(("id" = String, Path, description = "ID da partida")),
+    request_body = GameStateSchema,
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "Estado aplicado", body = SuccessResponse<GameStateSchema>),
+        (status = 400, description = "`players` diferente dos jogadores da partida (invalid_request)", body = ErrorResponse),
+        (status = 401, description = "Token ausente ou inválido (unauthorized)", body = ErrorResponse),
+        (status = 404, description = "Partida não encontrada (match_not_found)", body = ErrorResponse),
+        (status = 409, description = "Partida já terminada (conflict)", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn set_state_handler(
+    Path(match_id): Path<MatchId>,
+    State(state): State<AppState>,
+    ApiJson(new_state): ApiJson<GameState>,
+) -> Result<Json<SuccessResponse<GameState>>, ApiError> {
+    info!("🛠️ PUT /admin/matches/{}/state", match_id);
+
+    state.update_match(&match_id, new_state.clone()).await?;
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: new_state,
+    }))
+}
+
+/// POST /admin/matches/{id}/end_turn - Encerra o turno de quem está jogando
+#[utoipa::path(
+    post,
+    path = "/admin/matches/{id}/end_turn",
+    params(("id" = String, Path, description = "ID da partida")),
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "Estado após o fim do turno", body = SuccessResponse<GameStateSchema>),
+        (status = 400, description = "A lib recusou o fim do turno (invalid_action)", body = ErrorResponse),
+        (status = 401, description = "Token ausente ou inválido (unauthorized)", body = ErrorResponse),
+        (status = 404, description = "Partida não encontrada (match_not_found)", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn end_turn_handler(
+    Path(match_id): Path<MatchId>,
+    State(state): State<AppState>,
+) -> Result<(Extension<AuditDetails>, Json<SuccessResponse<GameState>>), ApiError> {
+    info!("🛠️ POST /admin/matches/{}/end_turn", match_id);
+
+    let handle = state
+        .get_handle(&match_id)
+        .await
+        .ok_or_else(|| match_not_found(&match_id))?;
+    let current = handle
+        .snapshot()
+        .await
+        .ok_or_else(|| match_not_found(&match_id))?
+        .state
+        .turn;
+    let new_state = handle.apply_action(current.clone(), Action::EndTurn).await?;
+
+    Ok((
+        Extension(AuditDetails(serde_json::json!({ "ended_turn_of": current }))),
+        Json(SuccessResponse {
+            success: true,
+            data: new_state,
+        }),
+    ))
+}
+
+/// GET /admin/matches/{id}/observers - WebSockets conectados na partida
+#[utoipa::path(
+    get,
+    path = "/admin/matches/{id}/observers",
+    params(("id" = String, Path, description = "ID da partida")),
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "Número de observers", body = SuccessResponse<usize>),
+        (status = 401, description = "Token ausente ou inválido (unauthorized)", body = ErrorResponse),
+        (status = 404, description = "Partida não encontrada (match_not_found)", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn observers_handler(
+    Path(match_id): Path<MatchId>,
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<usize>>, ApiError> {
+    info!("🛠️ GET /admin/matches/{}/observers", match_id);
+
+    let handle = state
+        .get_handle(&match_id)
+        .await
+        .ok_or_else(|| match_not_found(&match_id))?;
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: handle.observer_count().await,
+    }))
+}
+
+/// POST /admin/matches/{id}/kick - Desconecta os observers da partida
+#[utoipa::path(
+    post,
+    path = "/admin/matches/{id}/kick",
+    params(("id" = String, Path, description = "ID da partida")),
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "Observers desconectados", body = SuccessResponse<usize>),
+        (status = 401, description = "Token ausente ou inválido (unauthorized)", body = ErrorResponse),
+        (status = 404, description = "Partida não encontrada (match_not_found)", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn kick_observers_handler(
+    Path(match_id): Path<MatchId>,
+    State(state): State<AppState>,
+) -> Result<(Extension<AuditDetails>, Json<SuccessResponse<usize>>), ApiError> {
+    info!("🛠️ POST /admin/matches/{}/kick", match_id);
+
+    let handle = state
+        .get_handle(&match_id)
+        .await
+        .ok_or_else(|| match_not_found(&match_id))?;
+    let kicked = handle.kick_observers().await;
+
+    Ok((
+        Extension(AuditDetails(serde_json::json!({ "kicked": kicked }))),
+        Json(SuccessResponse {
+            success: true,
+            data: kicked,
+        }),
+    ))
+}
+
+/// DELETE /admin/matches/{id} - Encerra e remove a partida
+///
+/// Se for de um torneio e não tiver terminado, o confronto fica empatado
+/// (em eliminação, os jogadores ganham uma nova partida).
+#[utoipa::path(
+    delete,
+    path = "/admin/matches/{id}",
+    params(("id" = String, Path, description = "ID da partida")),
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "Partida removida", body = SuccessResponse<String>),
+        (status = 401, description = "Token ausente ou inválido (unauthorized)", body = ErrorResponse),
+        (status = 404, description = "Partida não encontrada (match_not_found)", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn close_match_handler(
+    Path(match_id): Path<MatchId>,
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<MatchId>>, ApiError> {
+    info!("🛠️ DELETE /admin/matches/{}", match_id);
+
+    state.close_match(&match_id).await?;
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: match_id,
+    }))
+}
+
+/// Request para aviso do servidor
+#[derive(Deserialize, ToSchema)]
+pub struct NoticeRequest {
+    message: String,
+    /// Só esta partida; sem ela, todas
+    match_id: Option<MatchId>,
+}
+
+/// POST /admin/notice - Envia `server_notice` aos observers
+#[utoipa::path(
+    post,
+    path = "/admin/notice",
+    request_body = NoticeRequest,
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "Partidas avisadas", body = SuccessResponse<usize>),
+        (status = 401, description = "Token ausente ou inválido (unauthorized)", body = ErrorResponse),
+        (status = 404, description = "Partida não encontrada (match_not_found)", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn notice_handler(
+    State(state): State<AppState>,
+    ApiJson(request): ApiJson<NoticeRequest>,
+) -> Result<(Extension<AuditDetails>, Json<SuccessResponse<usize>>), ApiError> {
+    info!("🛠️ POST /admin/notice - {:?}", request.match_id);
+
+    let targets: Vec<MatchId> = match &request.match_id {
+        Some(match_id) => {
+            state
+                .get_handle(match_id)
+                .await
+                .ok_or_else(|| match_not_found(match_id))?;
+            vec![match_id.clone()]
+        }
+        None => state.matches.read().await.keys().cloned().collect(),
+    };
+
+    let notice = serde_json::json!({
+        "type": "server_notice",
+        "message": request.message,
+    })
+    .to_string();
+    for match_id in &targets {
+        state.notify_observers(match_id, notice.clone()).await;
+    }
+
+    Ok((
+        Extension(AuditDetails(serde_json::json!({ "notified": targets }))),
+        Json(SuccessResponse {
+            success: true,
+            data: targets.len(),
+        }),
+    ))
+}
+
+/// Formato da exportação da auditoria
+#[derive(Deserialize, Default, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditFormat {
+    #[default]
+    Json,
+    /// JSON Lines, uma entrada por linha
+    Jsonl,
+}
+
+/// Query params para GET /admin/audit
+#[derive(Deserialize, IntoParams)]
+pub struct AuditQuery {
+    match_id: Option<MatchId>,
+    player_id: Option<String>,
+    /// Trecho da rota, ex: `/action`
+    route: Option<String>,
+    /// Só entradas a partir deste instante (RFC 3339)
+    since: Option<chrono::DateTime<chrono::Utc>>,
+    /// Só as N entradas mais recentes
+    limit: Option<usize>,
+    #[serde(default)]
+    #[param(inline)]
+    format: AuditFormat,
+}
+
+impl AuditQuery {
+    fn matches(&self, entry: &AuditEntry) -> bool {
+        let record = &entry.record;
+        self.match_id.as_ref().is_none_or(|id| record.match_id.as_ref() == Some(id))
+            && self.player_id.as_ref().is_none_or(|p| record.who.player_id.as_ref() == Some(p))
+            && self.route.as_ref().is_none_or(|r| record.route.contains(r.as_str()))
+            && self.since.is_none_or(|since| entry.at >= since)
+    }
+}
+
+/// GET /admin/audit - Registro de auditoria
+///
+/// Sem filtros, todas as entradas em memória (as mais recentes; o histórico
+/// completo fica em `AUDIT_LOG_PATH`). `?format=jsonl` exporta em JSON Lines.
+#[utoipa::path(
+    get,
+    path = "/admin/audit",
+    params(AuditQuery),
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "Entradas, da mais antiga para a mais nova (JSON, ou JSON Lines com ?format=jsonl)", body = SuccessResponse<Vec<AuditEntry>>),
+        (status = 400, description = "Query inválida", body = ErrorResponse),
+        (status = 401, description = "Token ausente ou inválido (unauthorized)", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn audit_handler(
+    Query(params): Query<AuditQuery>,
+    State(state): State<AppState>,
+) -> Response {
+    info!("🛠️ GET /admin/audit");
+
+    let mut entries: Vec<AuditEntry> = state
+        .audit
+        .entries()
+        .into_iter()
+        .filter(|entry| params.matches(entry))
+        .collect();
+    if let Some(limit) = params.limit {
+        entries.drain(..entries.len().saturating_sub(limit));
+    }
+
+    match params.format {
+        AuditFormat::Json => Json(SuccessResponse {
+            success: true,
+            data: entries,
+        })
+        .into_response(),
+        AuditFormat::Jsonl => (
+            [(header::CONTENT_TYPE, "application/x-ndjson")],
+            audit::to_jsonl(&entries),
+        )
+            .into_response(),
+    }
+}
+
+/// GET /admin/webhooks - Webhooks cadastrados
+#[utoipa::path(
+    get,
+    path = "/admin/webhooks",
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "Webhooks, sem os segredos", body = SuccessResponse<Vec<Webhook>>),
+        (status = 401, description = "Token ausente ou inválido (unauthorized)", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn list_webhooks_handler(
+    State(state): State<AppState>,
+) -> Json<SuccessResponse<Vec<Webhook>>> {
+    info!("🛠️ GET /admin/webhooks");
+
+    Json(SuccessResponse {
+        success: true,
+        data: state.webhooks.list(),
+    })
+}
+
+/// Request para cadastrar webhook
+#[derive(Deserialize, ToSchema)]
+pub struct CreateWebhookRequest {
+    url: String,
+    /// Chave do HMAC; sem ela, uma é gerada
+    secret: Option<String>,
+    /// Eventos assinados; vazia (padrão) assina todos
+    #[serde(default)]
+    events: Vec<WebhookEvent>,
+    /// Só eventos das partidas deste torneio
+    tournament_id: Option<TournamentId>,
+}
+
+/// POST /admin/webhooks - Cadastra webhook (do servidor ou de um torneio)
+///
+/// O segredo só é devolvido nesta resposta.
+#[utoipa::path(
+    post,
+    path = "/admin/webhooks",
+    request_body = CreateWebhookRequest,
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "Webhook cadastrado", body = SuccessResponse<WebhookCredentials>),
+        (status = 400, description = "URL ou segredo inválido (invalid_request)", body = ErrorResponse),
+        (status = 401, description = "Token ausente ou inválido (unauthorized)", body = ErrorResponse),
+        (status = 404, description = "Torneio não encontrado (tournament_not_found)", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn create_webhook_handler(
+    State(state): State<AppState>,
+    ApiJson(request): ApiJson<CreateWebhookRequest>,
+) -> Result<Json<SuccessResponse<WebhookCredentials>>, ApiError> {
+    info!("🛠️ POST /admin/webhooks - {}", request.url);
+
+    if let Some(tournament_id) = &request.tournament_id {
+        state.get_tournament(tournament_id).await?;
+    }
+    let credentials = state.webhooks.register(
+        request.url,
+        request.secret,
+        request.events,
+        request.tournament_id,
+    )?;
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: credentials,
+    }))
+}
+
+/// DELETE /admin/webhooks/{id} - Remove webhook
+#[utoipa::path(
+    delete,
+    path = "/admin/webhooks/{id}",
+    params(("id" = String, Path, description = "ID do webhook")),
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "Webhook removido", body = SuccessResponse<String>),
+        (status = 401, description = "Token ausente ou inválido (unauthorized)", body = ErrorResponse),
+        (status = 404, description = "Webhook não encontrado (webhook_not_found)", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn delete_webhook_handler(
+    Path(webhook_id): Path<String>,
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<String>>, ApiError> {
+    info!("🛠️ DELETE /admin/webhooks/{}", webhook_id);
+
+    state.webhooks.remove(&webhook_id)?;
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: webhook_id,
+    }))
+}
+
+/// GET /admin/webhooks/{id}/deliveries - Registro de entregas do webhook
+#[utoipa::path(
+    get,
+    path = "/admin/webhooks/{id}/deliveries",
+    params(("id" = String, Path, description = "ID do webhook")),
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "Tentativas, das mais antigas para as mais novas", body = SuccessResponse<Vec<DeliveryAttempt>>),
+        (status = 401, description = "Token ausente ou inválido (unauthorized)", body = ErrorResponse),
+        (status = 404, description = "Webhook não encontrado (webhook_not_found)", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn webhook_deliveries_handler(
+    Path(webhook_id): Path<String>,
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<Vec<DeliveryAttempt>>>, ApiError> {
+    info!("🛠️ GET /admin/webhooks/{}/deliveries", webhook_id);
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: state.webhooks.deliveries(&webhook_id)?,
+    }))
+}
+
+/// GET /admin/webhooks/dead_letters - Entregas que esgotaram as tentativas
+#[utoipa::path(
+    get,
+    path = "/admin/webhooks/dead_letters",
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "Fila de falhas", body = SuccessResponse<Vec<DeadLetter>>),
+        (status = 401, description = "Token ausente ou inválido (unauthorized)", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn dead_letters_handler(
+    State(state): State<AppState>,
+) -> Json<SuccessResponse<Vec<DeadLetter>>> {
+    info!("🛠️ GET /admin/webhooks/dead_letters");
+
+    Json(SuccessResponse {
+        success: true,
+        data: state.webhooks.dead_letters(),
+    })
+}
+
+/// POST /admin/webhooks/dead_letters/{id}/retry - Reenvia uma entrega da fila de falhas
+#[utoipa::path(
+    post,
+    path = "/admin/webhooks/dead_letters/{id}/retry",
+    params(("id" = String, Path, description = "ID da entrega")),
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "Entrega reenfileirada", body = SuccessResponse<String>),
+        (status = 401, description = "Token ausente ou inválido (unauthorized)", body = ErrorResponse),
+        (status = 404, description = "Webhook removido (webhook_not_found)", body = ErrorResponse),
+        (status = 409, description = "Entrega fora da fila de falhas (conflict)", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn retry_dead_letter_handler(
+    Path(delivery_id): Path<String>,
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<String>>, ApiError> {
+    info!("🛠️ POST /admin/webhooks/dead_letters/{}/retry", delivery_id);
+
+    state.webhooks.retry_dead_letter(&delivery_id)?;
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: delivery_id,
+    }))
+}
+
+/// Request para registrar bot externo
+#[derive(Deserialize, ToSchema)]
+pub struct RegisterBotRequest {
+    /// Letras, números, `-` ou `_`; o ID do jogador será `bot-<name>`
+    name: String,
+}
+
+/// POST /admin/bots - Registra bot externo
+///
+/// O bot se conecta em `/ws/bot?token=` e recebe `your_turn` nas partidas em
+/// que seu ID for um dos jogadores.
+#[utoipa::path(
+    post,
+    path = "/admin/bots",
+    request_body = RegisterBotRequest,
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "Bot registrado, com o token", body = SuccessResponse<BotCredentials>),
+        (status = 400, description = "Nome inválido (invalid_request)", body = ErrorResponse),
+        (status = 401, description = "Token ausente ou inválido (unauthorized)", body = ErrorResponse),
+        (status = 409, description = "Nome já registrado (conflict)", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn register_bot_handler(
+    State(state): State<AppState>,
+    ApiJson(request): ApiJson<RegisterBotRequest>,
+) -> Result<Json<SuccessResponse<BotCredentials>>, ApiError> {
+    info!("🛠️ POST /admin/bots - {}", request.name);
+
+    let credentials = state
+        .register_bot(request.name)
+        .await
+        .inspect_err(|e| warn!("❌ Bot não registrado: {}", e))?;
+
+    info!("✅ Bot registrado: {}", credentials.bot.id);
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: credentials,
+    }))
+}
+
+/// POST /admin/players/{id} - Cria ou altera o perfil do jogador
+///
+/// Campos ausentes ficam como estão. As estatísticas não são editáveis.
+#[utoipa::path(
+    post,
+    path = "/admin/players/{id}",
+    params(("id" = String, Path, description = "ID do jogador")),
+    request_body = ProfileUpdate,
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "Perfil atualizado", body = SuccessResponse<Player>),
+        (status = 400, description = "Nome ou avatar inválido (invalid_request)", body = ErrorResponse),
+        (status = 401, description = "Token ausente ou inválido (unauthorized)", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn update_player_handler(
+    Path(player_id): Path<PlayerId>,
+    State(state): State<AppState>,
+    ApiJson(update): ApiJson<ProfileUpdate>,
+) -> Result<Json<SuccessResponse<Player>>, ApiError> {
+    info!("🛠️ POST /admin/players/{} - {:?}", player_id, update);
+
+    let player = state
+        .update_player(&player_id, update)
+        .await
+        .inspect_err(|e| warn!("❌ Perfil não alterado: {}", e))?;
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: player,
+    }))
+}
+
+/// Request para definir idioma do jogador
+#[derive(Deserialize, ToSchema)]
+pub struct LanguageRequest {
+    lang: Lang,
+}
+
+/// POST /admin/players/{id}/language - Define idioma preferido do jogador
+///
+/// Usado quando a requisição traz `X-Player-Id` e não traz `?lang=`.
+#[utoipa::path(
+    post,
+    path = "/admin/players/{id}/language",
+    params(("id" = String, Path, description = "ID do jogador")),
+    request_body = LanguageRequest,
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "Idioma definido", body = SuccessResponse<Lang>),
+        (status = 401, description = "Token ausente ou inválido (unauthorized)", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn set_language_handler(
+    Path(player_id): Path<PlayerId>,
+    State(state): State<AppState>,
+    ApiJson(request): ApiJson<LanguageRequest>,
+) -> Json<SuccessResponse<Lang>> {
+    info!("🛠️ POST /admin/players/{}/language - {:?}", player_id, request.lang);
+
+    state.set_language(player_id, request.lang).await;
+
+    Json(SuccessResponse {
+        success: true,
+        data: request.lang,
+    })
+}
+
+/// Request para desistência em torneio
+#[derive(Deserialize, ToSchema)]
+pub struct ForfeitRequest {
+    #[schema(value_type = String)]
+    player_id: PlayerId,
+}
+
+/// POST /admin/tournaments/{id}/forfeit - Retira jogador (W.O.)
+///
+/// Jogadores não têm credenciais próprias, então só o administrador retira alguém.
+#[utoipa::path(
+    post,
+    path = "/admin/tournaments/{id}/forfeit",
+    params(("id" = String, Path, description = "ID do torneio")),
+    request_body = ForfeitRequest,
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "Torneio atualizado", body = SuccessResponse<Tournament>),
+        (status = 400, description = "Jogador fora do torneio (invalid_request)", body = ErrorResponse),
+        (status = 401, description = "Token ausente ou inválido (unauthorized)", body = ErrorResponse),
+        (status = 404, description = "Torneio não encontrado (tournament_not_found)", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn forfeit_tournament_handler(
+    Path(tournament_id): Path<TournamentId>,
+    State(state): State<AppState>,
+    ApiJson(request): ApiJson<ForfeitRequest>,
+) -> Result<Json<SuccessResponse<Tournament>>, ApiError> {
+    info!("🛠️ POST /admin/tournaments/{}/forfeit - player: {}", tournament_id, request.player_id);
+
+    let tournament = state
+        .forfeit_tournament(&tournament_id, &request.player_id)
+        .await
+        .inspect_err(|e| warn!("❌ Desistência recusada: {}", e))?;
+
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: tournament,
+    }))
+}
+
+/// Formato do relatório da arena
+#[derive(Deserialize, Default, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+/// Query params para POST /admin/arena/run
+#[derive(Deserialize, IntoParams)]
+pub struct ArenaQuery {
+    #[serde(default)]
+    #[param(inline)]
+    format: ReportFormat,
+}
+
+/// POST /admin/arena/run - Roda partidas IA contra IA e devolve o relatório
+///
+/// As partidas não entram em `/matches` e não têm observers.
+#[utoipa::path(
+    post,
+    path = "/admin/arena/run",
+    params(ArenaQuery),
+    request_body = ArenaConfig,
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "Relatório (JSON, ou CSV com ?format=csv)", body = SuccessResponse<ArenaReport>),
+        (status = 400, description = "Parâmetros inválidos (invalid_request)", body = ErrorResponse),
+        (status = 401, description = "Token ausente ou inválido (unauthorized)", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn arena_handler(
+    Query(params): Query<ArenaQuery>,
+    ApiJson(config): ApiJson<ArenaConfig>,
+) -> Result<Response, ApiError> {
+    info!(
+        "🏟️ POST /admin/arena/run - {} partidas, seed: {:?}",
+        config.matches, config.seed
+    );
+
+    config
+        .validate()
+        .map_err(|reason| ApiError::InvalidRequest { reason })?;
+
+    let report = arena::run(config).await;
+
+    info!(
+        "✅ Arena concluída: {:?}, {} empates, média de {:.1} turnos",
+        report.wins, report.draws, report.avg_turn_count
+    );
+
+    Ok(match params.format {
+        ReportFormat::Json => Json(SuccessResponse {
+            success: true,
+            data: report,
+        })
+        .into_response(),
+        ReportFormat::Csv => ([(header::CONTENT_TYPE, "text/csv")], report.to_csv()).into_response(),
+    })
+}
@@ -0,0 +1,346 @@
+//! Estratégias de IA e avaliação de jogadas
+//!
+//! Além da `ai_choose_action` da lib, há estratégias simuladas sobre
+//! `apply_action`: aleatória, gulosa (melhor jogada imediata) e busca com
+//! profundidade e orçamento de tempo. Todas pontuam as candidatas com a mesma
+//! avaliação, usada nas dicas (`top_k`).
+
+use rand::{rngs::StdRng, seq::IndexedRandom, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tatic_lib::{ai_choose_action, Action, GameState, PlayerId};
+use utoipa::ToSchema;
+
+use crate::error::Reason;
+use crate::moves::{self, LegalAction};
+use crate::state::{is_game_over, winner};
+
+/// Limites aceitos na API
+pub const MAX_DEPTH: u32 = 6;
+pub const MAX_TIME_BUDGET_MS: u64 = 5000;
+pub const MAX_TOP_K: usize = 20;
+
+/// Jogadas de cada nó consideradas na busca (as melhores pela avaliação)
+const BEAM_WIDTH: usize = 8;
+
+/// Pontuação de vitória/derrota
+const WIN_SCORE: f64 = 1_000_000.0;
+
+/// Estratégia (nível de dificuldade) da IA
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AiStrategy {
+    /// `ai_choose_action` da lib
+    #[default]
+    Builtin,
+    /// Qualquer ação legal
+    Random { seed: Option<u64> },
+    /// Melhor ação imediata pela avaliação
+    Greedy,
+    /// Minimax com poda por feixe, aprofundando até `depth` ou acabar o tempo
+    Lookahead {
+        #[serde(default = "default_depth")]
+        depth: u32,
+        #[serde(default = "default_time_budget_ms")]
+        time_budget_ms: u64,
+    },
+}
+
+fn default_depth() -> u32 {
+    3
+}
+
+fn default_time_budget_ms() -> u64 {
+    500
+}
+
+impl AiStrategy {
+    pub fn validate(&self) -> Result<(), Reason> {
+        if let AiStrategy::Lookahead {
+            depth,
+            time_budget_ms,
+        } = *self
+        {
+            if depth == 0 || depth > MAX_DEPTH {
+                return Err(Reason::new("reason.ai_depth").arg("max", MAX_DEPTH));
+            }
+            if time_budget_ms == 0 || time_budget_ms > MAX_TIME_BUDGET_MS {
+                return Err(Reason::new("reason.ai_time_budget").arg("max", MAX_TIME_BUDGET_MS));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// O que a jogada muda, do ponto de vista de quem joga
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct Effects {
+    pub own_hp: i64,
+    pub enemy_hp: i64,
+    pub own_units: i64,
+    pub enemy_units: i64,
+    pub ends_turn: bool,
+    pub wins: bool,
+}
+
+/// Candidata com pontuação e efeitos
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ScoredAction {
+    #[schema(value_type = crate::openapi::ActionSchema)]
+    pub action: Action,
+    pub unit_id: Option<u32>,
+    /// Avaliação da estratégia (maior é melhor)
+    pub score: f64,
+    pub effects: Effects,
+}
+
+/// Escolha da IA e, se pedido, as melhores candidatas
+///
+/// A ação vai no nível de cima, como na resposta antiga de `/ai/action`.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AiDecision {
+    #[serde(flatten)]
+    #[schema(value_type = crate::openapi::ActionSchema)]
+    pub action: Action,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub candidates: Vec<ScoredAction>,
+}
+
+/// Escolhe a ação do jogador com a estratégia dada
+///
+/// `rng` é usado pela estratégia `Random` quando ela não tem `seed`.
+pub fn decide(
+    state: &GameState,
+    player: &PlayerId,
+    strategy: AiStrategy,
+    top_k: usize,
+    rng: &mut impl Rng,
+) -> Option<AiDecision> {
+    match strategy {
+        AiStrategy::Builtin => {
+            let action = ai_choose_action(state, player)?;
+            let candidates = if top_k > 0 {
+                greedy_scores(state, player)
+            } else {
+                Vec::new()
+            };
+            Some(AiDecision {
+                action,
+                candidates: top(candidates, top_k),
+            })
+        }
+        AiStrategy::Random { seed } => {
+            let scored = greedy_scores(state, player);
+            let choice = match seed {
+                Some(seed) => scored.choose(&mut StdRng::seed_from_u64(seed)),
+                None => scored.choose(rng),
+            };
+            let action = choice?.action.clone();
+            Some(AiDecision {
+                action,
+                candidates: top(scored, top_k),
+            })
+        }
+        AiStrategy::Greedy => {
+            let scored = top(greedy_scores(state, player), usize::MAX);
+            let action = best_or_end_turn(&scored)?;
+            Some(AiDecision {
+                action,
+                candidates: top(scored, top_k),
+            })
+        }
+        AiStrategy::Lookahead {
+            depth,
+            time_budget_ms,
+        } => {
+            let deadline = Instant::now() + Duration::from_millis(time_budget_ms);
+            let scored = top(lookahead_scores(state, player, depth, deadline), usize::MAX);
+            let action = best_or_end_turn(&scored)?;
+            Some(AiDecision {
+                action,
+                candidates: top(scored, top_k),
+            })
+        }
+    }
+}
+
+/// Só uma ação, para quem não precisa das candidatas (arena, bots)
+pub fn choose_action(
+    state: &GameState,
+    player: &PlayerId,
+    strategy: AiStrategy,
+    rng: &mut impl Rng,
+) -> Option<Action> {
+    decide(state, player, strategy, 0, rng).map(|d| d.action)
+}
+
+/// Melhor candidata; empate com `EndTurn` fica com `EndTurn`
+///
+/// Evita que a IA mova unidades à toa quando nada melhora a posição.
+fn best_or_end_turn(sorted: &[ScoredAction]) -> Option<Action> {
+    let best = sorted.first()?;
+    let end_turn = sorted.iter().find(|c| c.effects.ends_turn);
+    match end_turn {
+        Some(end_turn) if end_turn.score >= best.score => Some(end_turn.action.clone()),
+        _ => Some(best.action.clone()),
+    }
+}
+
+/// Ordena da maior para a menor pontuação e mantém as `k` primeiras
+fn top(mut scored: Vec<ScoredAction>, k: usize) -> Vec<ScoredAction> {
+    scored.sort_by(|a, b| b.score.total_cmp(&a.score));
+    scored.truncate(k);
+    scored
+}
+
+/// Pontua cada ação legal pelo estado imediatamente seguinte
+fn greedy_scores(state: &GameState, player: &PlayerId) -> Vec<ScoredAction> {
+    moves::legal_actions(state, player)
+        .into_iter()
+        .map(|legal| {
+            let score = evaluate(&legal.next, player);
+            scored(state, player, legal, score)
+        })
+        .collect()
+}
+
+/// Pontua cada ação legal pela busca, aprofundando enquanto houver tempo
+///
+/// Fica com o resultado da última profundidade completa.
+fn lookahead_scores(
+    state: &GameState,
+    player: &PlayerId,
+    depth: u32,
+    deadline: Instant,
+) -> Vec<ScoredAction> {
+    let root = moves::legal_actions(state, player);
+    let mut best: Vec<f64> = root.iter().map(|l| evaluate(&l.next, player)).collect();
+
+    for current_depth in 2..=depth {
+        let mut values = Vec::with_capacity(root.len());
+        for legal in &root {
+            match search(&legal.next, player, current_depth - 1, deadline) {
+                Some(value) => values.push(value),
+                None => break,
+            }
+        }
+        if values.len() < root.len() {
+            break;
+        }
+        best = values;
+    }
+
+    root.into_iter()
+        .zip(best)
+        .map(|(legal, score)| scored(state, player, legal, score))
+        .collect()
+}
+
+/// Minimax: `player` maximiza, o adversário minimiza
+///
+/// `None` quando o tempo acaba no meio da busca.
+fn search(state: &GameState, player: &PlayerId, depth: u32, deadline: Instant) -> Option<f64> {
+    if Instant::now() >= deadline {
+        return None;
+    }
+    if depth == 0 || is_game_over(state) {
+        return Some(evaluate(state, player));
+    }
+
+    let maximizing = &state.turn == player;
+    let mut children: Vec<(f64, GameState)> = moves::legal_actions(state, &state.turn)
+        .into_iter()
+        .map(|legal| (evaluate(&legal.next, player), legal.next))
+        .collect();
+    if children.is_empty() {
+        return Some(evaluate(state, player));
+    }
+    children.sort_by(|a, b| {
+        if maximizing {
+            b.0.total_cmp(&a.0)
+        } else {
+            a.0.total_cmp(&b.0)
+        }
+    });
+    children.truncate(BEAM_WIDTH);
+
+    let mut best = if maximizing { f64::MIN } else { f64::MAX };
+    for (_, child) in &children {
+        let value = search(child, player, depth - 1, deadline)?;
+        best = if maximizing {
+            best.max(value)
+        } else {
+            best.min(value)
+        };
+    }
+    Some(best)
+}
+
+fn scored(state: &GameState, player: &PlayerId, legal: LegalAction, score: f64) -> ScoredAction {
+    ScoredAction {
+        effects: effects(state, &legal.next, player),
+        action: legal.action,
+        unit_id: legal.unit_id,
+        score,
+    }
+}
+
+/// Resumo material do estado para um jogador
+struct Material {
+    own_hp: i64,
+    enemy_hp: i64,
+    own_units: i64,
+    enemy_units: i64,
+    /// Soma das distâncias de cada unidade própria ao inimigo mais próximo
+    distance: i64,
+}
+
+fn material(state: &GameState, player: &PlayerId) -> Material {
+    let (own, enemy): (Vec<_>, Vec<_>) = state
+        .units
+        .iter()
+        .filter(|u| u.hp > 0)
+        .partition(|u| &u.owner == player);
+    let distance = own
+        .iter()
+        .filter_map(|u| {
+            enemy
+                .iter()
+                .map(|e| i64::from((u.pos.x - e.pos.x).abs() + (u.pos.y - e.pos.y).abs()))
+                .min()
+        })
+        .sum();
+    Material {
+        own_hp: own.iter().map(|u| i64::from(u.hp)).sum(),
+        enemy_hp: enemy.iter().map(|u| i64::from(u.hp)).sum(),
+        own_units: own.len() as i64,
+        enemy_units: enemy.len() as i64,
+        distance,
+    }
+}
+
+/// Avaliação do estado para `player`: material, aproximação e resultado
+pub fn evaluate(state: &GameState, player: &PlayerId) -> f64 {
+    if is_game_over(state) {
+        return match winner(state) {
+            Some(w) if &w == player => WIN_SCORE,
+            Some(_) => -WIN_SCORE,
+            None => 0.0,
+        };
+    }
+    let m = material(state, player);
+    10.0 * (m.own_units - m.enemy_units) as f64 + (m.own_hp - m.enemy_hp) as f64
+        - 0.01 * m.distance as f64
+}
+
+fn effects(before: &GameState, after: &GameState, player: &PlayerId) -> Effects {
+    let (b, a) = (material(before, player), material(after, player));
+    Effects {
+        own_hp: a.own_hp - b.own_hp,
+        enemy_hp: a.enemy_hp - b.enemy_hp,
+        own_units: a.own_units - b.own_units,
+        enemy_units: a.enemy_units - b.enemy_units,
+        ends_turn: &after.turn != player,
+        wins: winner(after).as_ref() == Some(player),
+    }
+}
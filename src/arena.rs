@@ -0,0 +1,293 @@
+//! Arena de IA contra IA
+//!
+//! Roda partidas sem observers nem `AppState`: o estado fica só na task de
+//! cada partida e nada é publicado. Serve para avaliar `ai_choose_action` em
+//! lote, ou uma estratégia de `ai` contra outra. Com `seed` o resultado só se
+//! repete quando os dois lados usam `greedy` ou `random`: `builtin` chama
+//! `ai_choose_action`, que não recebe a semente, e `lookahead` depende do
+//! tempo disponível.
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Arc;
+use tatic_lib::{apply_action, Action, GameState, PlayerId};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use utoipa::ToSchema;
+
+use crate::ai::{self, AiStrategy};
+use crate::error::Reason;
+use crate::moves;
+use crate::state::{is_game_over, winner};
+
+/// Máximo de partidas por execução
+pub const MAX_ARENA_MATCHES: u32 = 1000;
+
+/// Os dois competidores
+const BOT_A: &str = "bot-a";
+const BOT_B: &str = "bot-b";
+
+/// Parâmetros de uma execução da arena
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct ArenaConfig {
+    /// Número de partidas (os lados alternam quem começa)
+    pub matches: u32,
+    /// Semente para `randomness` e para a estratégia `random`; só torna a
+    /// execução repetível com `greedy` ou `random` nos dois lados
+    pub seed: Option<u64>,
+    /// Chance (0 a 1) de cada jogada ser trocada por `EndTurn`
+    #[serde(default)]
+    pub randomness: f64,
+    /// Partida empata ao chegar neste `turn_count`
+    #[serde(default = "default_max_turns")]
+    pub max_turns: u32,
+    /// Partidas rodando ao mesmo tempo (padrão e máximo: número de CPUs)
+    pub concurrency: Option<usize>,
+    /// Estratégia de `bot-a` (padrão: `builtin`)
+    #[serde(default)]
+    pub bot_a: AiStrategy,
+    /// Estratégia de `bot-b` (padrão: `builtin`)
+    #[serde(default)]
+    pub bot_b: AiStrategy,
+}
+
+fn default_max_turns() -> u32 {
+    200
+}
+
+impl ArenaConfig {
+    pub fn validate(&self) -> Result<(), Reason> {
+        if self.matches == 0 || self.matches > MAX_ARENA_MATCHES {
+            return Err(Reason::new("reason.arena_matches").arg("max", MAX_ARENA_MATCHES));
+        }
+        if !(0.0..=1.0).contains(&self.randomness) {
+            return Err(Reason::new("reason.arena_randomness"));
+        }
+        if self.max_turns == 0 {
+            return Err(Reason::new("reason.arena_max_turns"));
+        }
+        self.bot_a.validate()?;
+        self.bot_b.validate()
+    }
+}
+
+/// Como a partida terminou
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ArenaOutcome {
+    Win,
+    Draw,
+    /// Chegou a `max_turns`
+    TurnLimit,
+    /// Nem `EndTurn` foi aceito; a partida foi abandonada
+    Error,
+}
+
+/// Resultado de uma partida da arena
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ArenaMatch {
+    pub index: u32,
+    pub first_player: String,
+    pub outcome: ArenaOutcome,
+    pub winner: Option<String>,
+    pub turn_count: u32,
+    pub actions: u32,
+    /// Jogadas da IA recusadas por `apply_action`
+    pub illegal_actions: u32,
+    /// Contagem por tipo de ação (`Move`, `Attack`, `EndTurn`...)
+    pub action_counts: BTreeMap<String, u32>,
+}
+
+/// Relatório agregado da arena
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ArenaReport {
+    pub matches: u32,
+    pub seed: Option<u64>,
+    pub wins: BTreeMap<String, u32>,
+    /// Vitórias por jogador sobre o total de partidas
+    pub win_rates: BTreeMap<String, f64>,
+    pub draws: u32,
+    pub turn_limits: u32,
+    pub errors: u32,
+    pub first_player_wins: u32,
+    pub avg_turn_count: f64,
+    pub min_turn_count: u32,
+    pub max_turn_count: u32,
+    pub action_counts: BTreeMap<String, u32>,
+    pub illegal_actions: u32,
+    pub results: Vec<ArenaMatch>,
+}
+
+impl ArenaReport {
+    fn new(config: &ArenaConfig, mut results: Vec<ArenaMatch>) -> Self {
+        results.sort_by_key(|r| r.index);
+
+        let mut wins = BTreeMap::from([(BOT_A.to_string(), 0), (BOT_B.to_string(), 0)]);
+        let mut action_counts = BTreeMap::new();
+        let count = |outcome| results.iter().filter(|r| r.outcome == outcome).count() as u32;
+        let (draws, turn_limits, errors) = (
+            count(ArenaOutcome::Draw),
+            count(ArenaOutcome::TurnLimit),
+            count(ArenaOutcome::Error),
+        );
+        let mut first_player_wins = 0;
+        for result in &results {
+            if let Some(winner) = &result.winner {
+                *wins.entry(winner.clone()).or_insert(0) += 1;
+                if *winner == result.first_player {
+                    first_player_wins += 1;
+                }
+            }
+            for (kind, n) in &result.action_counts {
+                *action_counts.entry(kind.clone()).or_insert(0) += n;
+            }
+        }
+
+        let total = results.len().max(1) as f64;
+        let turn_counts = results.iter().map(|r| r.turn_count);
+        Self {
+            matches: results.len() as u32,
+            seed: config.seed,
+            win_rates: wins
+                .iter()
+                .map(|(bot, &n)| (bot.clone(), n as f64 / total))
+                .collect(),
+            wins,
+            draws,
+            turn_limits,
+            errors,
+            first_player_wins,
+            avg_turn_count: turn_counts.clone().map(f64::from).sum::<f64>() / total,
+            min_turn_count: turn_counts.clone().min().unwrap_or(0),
+            max_turn_count: turn_counts.max().unwrap_or(0),
+            action_counts,
+            illegal_actions: results.iter().map(|r| r.illegal_actions).sum(),
+            results,
+        }
+    }
+
+    /// Uma linha por partida
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "index,first_player,outcome,winner,turn_count,actions,illegal_actions\n",
+        );
+        for r in &self.results {
+            let outcome = serde_json::to_value(r.outcome).unwrap_or_default();
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{},{}",
+                r.index,
+                r.first_player,
+                outcome.as_str().unwrap_or_default(),
+                r.winner.as_deref().unwrap_or_default(),
+                r.turn_count,
+                r.actions,
+                r.illegal_actions,
+            );
+        }
+        csv
+    }
+}
+
+/// Roda as partidas em paralelo e agrega o resultado
+pub async fn run(config: ArenaConfig) -> ArenaReport {
+    let cpus = std::thread::available_parallelism().map_or(4, |n| n.get());
+    let concurrency = config.concurrency.filter(|&n| n > 0).unwrap_or(cpus).min(cpus);
+    let permits = Arc::new(Semaphore::new(concurrency));
+    let base_seed = config.seed.unwrap_or_else(rand::random);
+
+    let mut tasks = JoinSet::new();
+    for index in 0..config.matches {
+        let permits = permits.clone();
+        let config = config.clone();
+        tasks.spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            let seed = base_seed.wrapping_add(index as u64);
+            tokio::task::spawn_blocking(move || play(index, seed, &config)).await
+        });
+    }
+
+    let mut results = Vec::with_capacity(config.matches as usize);
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok(Ok(result)) => results.push(result),
+            _ => tracing::error!("❌ Partida da arena falhou"),
+        }
+    }
+
+    ArenaReport::new(&config, results)
+}
+
+/// Joga uma partida inteira
+fn play(index: u32, seed: u64, config: &ArenaConfig) -> ArenaMatch {
+    let (first, second) = if index.is_multiple_of(2) {
+        (BOT_A, BOT_B)
+    } else {
+        (BOT_B, BOT_A)
+    };
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut state = GameState::new(first.to_string(), second.to_string());
+    let mut result = ArenaMatch {
+        index,
+        first_player: first.to_string(),
+        outcome: ArenaOutcome::TurnLimit,
+        winner: None,
+        turn_count: 0,
+        actions: 0,
+        illegal_actions: 0,
+        action_counts: BTreeMap::new(),
+    };
+    // Limite de segurança para uma IA que nunca passa o turno
+    let max_actions = config.max_turns.saturating_mul(100);
+
+    loop {
+        if is_game_over(&state) {
+            result.winner = winner(&state);
+            result.outcome = if result.winner.is_some() {
+                ArenaOutcome::Win
+            } else {
+                ArenaOutcome::Draw
+            };
+            break;
+        }
+        if state.turn_count >= config.max_turns || result.actions >= max_actions {
+            break;
+        }
+
+        let player: PlayerId = state.turn.clone();
+        let strategy = if player == BOT_A {
+            config.bot_a
+        } else {
+            config.bot_b
+        };
+        let action = if rng.random_bool(config.randomness) {
+            Action::EndTurn
+        } else {
+            ai::choose_action(&state, &player, strategy, &mut rng).unwrap_or(Action::EndTurn)
+        };
+        let mut kind = moves::action_kind(&action);
+
+        let next = match apply_action(&state, &player, action) {
+            Ok(next) => next,
+            Err(_) => {
+                result.illegal_actions += 1;
+                kind = moves::action_kind(&Action::EndTurn);
+                match apply_action(&state, &player, Action::EndTurn) {
+                    Ok(next) => next,
+                    Err(_) => {
+                        result.outcome = ArenaOutcome::Error;
+                        break;
+                    }
+                }
+            }
+        };
+        *result.action_counts.entry(kind).or_insert(0) += 1;
+        result.actions += 1;
+        state = next;
+    }
+
+    result.turn_count = state.turn_count;
+    result
+}
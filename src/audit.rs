@@ -0,0 +1,356 @@
+//! Registro de auditoria
+//!
+//! Lista só de inclusão com toda operação que altera estado (rotas REST que
+//! não são leitura e ações de bots pelo WebSocket): quem pediu, a rota, a
+//! ação, `turn_count`/fase antes e depois e o resultado. Serve para resolver
+//! disputas ("eu não fiz essa jogada"). A memória guarda só as entradas mais
+//! recentes (`AUDIT_MEMORY_ENTRIES`); com `AUDIT_LOG_PATH`, todas são
+//! acrescentadas ao arquivo em JSON Lines por uma task própria.
+
+use axum::{
+    body::Body,
+    extract::{ConnectInfo, Request, State},
+    http::{HeaderMap, Method},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use serde_json::Value;
+use std::{
+    collections::VecDeque,
+    fs::OpenOptions,
+    net::SocketAddr,
+    sync::Mutex,
+};
+use tatic_lib::GameState;
+use tokio::{io::AsyncWriteExt, sync::mpsc};
+use tracing::warn;
+use utoipa::ToSchema;
+
+use crate::error::ApiError;
+use crate::state::{AppState, MatchId};
+
+/// Header opcional que identifica a sessão do cliente
+pub const SESSION_ID: &str = "x-session-id";
+
+/// Maior corpo de requisição lido pelo registro
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// Quem fez a requisição
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct Who {
+    /// `player_id` do corpo, ou o header `X-Player-Id`
+    pub player_id: Option<String>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    /// Header `X-Session-Id`, ou a conexão no caso do WebSocket
+    pub session: Option<String>,
+}
+
+impl Who {
+    /// Lê os headers e o endereço da conexão
+    pub fn from_parts(headers: &HeaderMap, addr: Option<SocketAddr>) -> Self {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        Self {
+            player_id: header("x-player-id"),
+            ip: addr.map(|addr| addr.ip().to_string()),
+            user_agent: header("user-agent"),
+            session: header(SESSION_ID),
+        }
+    }
+}
+
+/// Turno e fase de uma partida num instante
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct StateStamp {
+    pub turn_count: u32,
+    #[schema(value_type = Object)]
+    pub phase: Value,
+}
+
+impl StateStamp {
+    pub fn of(state: &GameState) -> Self {
+        Self {
+            turn_count: state.turn_count,
+            phase: serde_json::to_value(&state.phase).unwrap_or(Value::Null),
+        }
+    }
+}
+
+/// Resultado da operação
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Outcome {
+    /// Status HTTP (ou o equivalente, para comandos WebSocket)
+    pub status: u16,
+    /// Código do erro, ex: `not_your_turn`
+    pub error: Option<String>,
+}
+
+impl Outcome {
+    pub fn of<T>(result: &Result<T, ApiError>) -> Self {
+        match result {
+            Ok(_) => Self {
+                status: 200,
+                error: None,
+            },
+            Err(e) => Self {
+                status: e.status().as_u16(),
+                error: Some(e.code().to_string()),
+            },
+        }
+    }
+}
+
+/// Conteúdo de uma entrada
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AuditRecord {
+    pub who: Who,
+    /// Método e caminho (`POST /action`) ou o comando (`WS /ws/bot action`)
+    pub route: String,
+    pub match_id: Option<MatchId>,
+    /// Ação (ou lista de ações) como enviada
+    #[schema(value_type = Object)]
+    pub action: Option<Value>,
+    pub before: Option<StateStamp>,
+    pub after: Option<StateStamp>,
+    pub outcome: Outcome,
+    /// Informações extras da operação (ex: observers desconectados)
+    #[schema(value_type = Object)]
+    pub details: Value,
+}
+
+/// Uma operação registrada
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AuditEntry {
+    /// Sequencial, a partir de 1
+    pub id: u64,
+    pub at: chrono::DateTime<chrono::Utc>,
+    #[serde(flatten)]
+    pub record: AuditRecord,
+}
+
+/// Detalhes que um handler anexa à resposta para o registro
+#[derive(Debug, Clone)]
+pub struct AuditDetails(pub Value);
+
+/// Entradas mantidas em memória por padrão
+const DEFAULT_MEMORY_ENTRIES: usize = 10_000;
+
+/// Janela das entradas mais recentes
+#[derive(Default)]
+struct Window {
+    /// ID da última entrada registrada (conta também as que saíram da janela)
+    last_id: u64,
+    entries: VecDeque<AuditEntry>,
+}
+
+/// Registro só de inclusão: janela em memória e, opcionalmente, o arquivo
+pub struct AuditLog {
+    window: Mutex<Window>,
+    capacity: usize,
+    /// Linhas para a task que grava o arquivo
+    file: Option<mpsc::UnboundedSender<String>>,
+}
+
+impl Default for AuditLog {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_MEMORY_ENTRIES)
+    }
+}
+
+impl AuditLog {
+    /// Só memória, com as `capacity` entradas mais recentes
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            window: Mutex::new(Window::default()),
+            capacity: capacity.max(1),
+            file: None,
+        }
+    }
+
+    /// Lê `AUDIT_MEMORY_ENTRIES` e `AUDIT_LOG_PATH`; sem o arquivo (ou se ele
+    /// não abrir), só memória
+    pub fn from_env() -> Self {
+        let capacity = std::env::var("AUDIT_MEMORY_ENTRIES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_MEMORY_ENTRIES);
+        let file = std::env::var("AUDIT_LOG_PATH")
+            .ok()
+            .filter(|path| !path.is_empty())
+            .and_then(|path| {
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&path)
+                    .inspect_err(|e| warn!("⚠️ Não foi possível abrir {}: {}", path, e))
+                    .ok()
+            });
+        Self {
+            file: file.map(|file| spawn_writer(tokio::fs::File::from_std(file))),
+            ..Self::with_capacity(capacity)
+        }
+    }
+
+    /// Acrescenta uma entrada e a repete no log do servidor
+    pub fn record(&self, record: AuditRecord) {
+        let mut window = self.window.lock().unwrap();
+        window.last_id += 1;
+        let entry = AuditEntry {
+            id: window.last_id,
+            at: chrono::Utc::now(),
+            record,
+        };
+        tracing::info!(
+            "📝 Auditoria #{}: {} {:?} {:?} -> {}",
+            entry.id,
+            entry.record.route,
+            entry.record.who.player_id,
+            entry.record.match_id,
+            entry.record.outcome.status
+        );
+        if let Some(file) = &self.file {
+            let _ = file.send(to_json_line(&entry));
+        }
+        if window.entries.len() == self.capacity {
+            window.entries.pop_front();
+        }
+        window.entries.push_back(entry);
+    }
+
+    /// Cópia das entradas em memória, da mais antiga para a mais nova
+    pub fn entries(&self) -> Vec<AuditEntry> {
+        self.window.lock().unwrap().entries.iter().cloned().collect()
+    }
+}
+
+/// Grava as linhas no arquivo na ordem em que foram registradas
+fn spawn_writer(mut file: tokio::fs::File) -> mpsc::UnboundedSender<String> {
+    let (tx, mut rx) = mpsc::unbounded_channel::<String>();
+    tokio::spawn(async move {
+        while let Some(line) = rx.recv().await {
+            let written = async {
+                file.write_all(line.as_bytes()).await?;
+                file.write_all(b"\n").await?;
+                file.flush().await
+            };
+            if let Err(e) = written.await {
+                warn!("⚠️ Falha ao gravar auditoria: {}", e);
+            }
+        }
+    });
+    tx
+}
+
+fn to_json_line(entry: &AuditEntry) -> String {
+    serde_json::to_string(entry).unwrap_or_default()
+}
+
+/// Entradas em JSON Lines (uma por linha)
+pub fn to_jsonl(entries: &[AuditEntry]) -> String {
+    entries
+        .iter()
+        .map(|entry| to_json_line(entry) + "\n")
+        .collect()
+}
+
+/// Middleware que registra toda requisição que não é de leitura
+///
+/// A partida vem do `match_id` do corpo, de um segmento do caminho ou, na
+/// criação, do `data` da resposta.
+pub async fn audit(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    if matches!(*request.method(), Method::GET | Method::HEAD | Method::OPTIONS) {
+        return next.run(request).await;
+    }
+
+    let route = format!("{} {}", request.method(), request.uri().path());
+    let addr = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| *addr);
+    let mut who = Who::from_parts(request.headers(), addr);
+
+    let (parts, body) = request.into_parts();
+    let body = match axum::body::to_bytes(body, MAX_BODY_BYTES).await {
+        Ok(body) => body,
+        Err(e) => {
+            return ApiError::InvalidRequest {
+                reason: e.to_string().into(),
+            }
+            .into_response();
+        }
+    };
+    let json: Value = serde_json::from_slice(&body).unwrap_or(Value::Null);
+    if let Some(player_id) = json["player_id"].as_str() {
+        who.player_id = Some(player_id.to_string());
+    }
+    let action = json.get("action").or_else(|| json.get("actions")).cloned();
+
+    let candidates = json["match_id"]
+        .as_str()
+        .into_iter()
+        .chain(parts.uri.path().split('/').filter(|s| !s.is_empty()))
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    let mut match_id = None;
+    let mut before = None;
+    for candidate in candidates {
+        if let Some(match_data) = state.get_match(&candidate).await {
+            before = Some(StateStamp::of(&match_data.state));
+            match_id = Some(candidate);
+            break;
+        }
+    }
+
+    let request = Request::from_parts(parts, Body::from(body));
+    let mut response = next.run(request).await;
+
+    let outcome = Outcome {
+        status: response.status().as_u16(),
+        error: response
+            .extensions()
+            .get::<ApiError>()
+            .map(|e| e.code().to_string()),
+    };
+    let details = response
+        .extensions()
+        .get::<AuditDetails>()
+        .map(|d| d.0.clone())
+        .unwrap_or_else(|| serde_json::json!({}));
+
+    if match_id.is_none() && response.status().is_success() {
+        let (parts, body) = response.into_parts();
+        let body = axum::body::to_bytes(body, usize::MAX).await.unwrap_or_default();
+        let created = serde_json::from_slice::<Value>(&body)
+            .ok()
+            .and_then(|v| v["data"].as_str().map(str::to_string));
+        if let Some(created) = created
+            && state.get_handle(&created).await.is_some()
+        {
+            match_id = Some(created);
+        }
+        response = Response::from_parts(parts, Body::from(body));
+    }
+
+    let after = match &match_id {
+        Some(id) => state.get_match(id).await.map(|m| StateStamp::of(&m.state)),
+        None => None,
+    };
+
+    state.audit.record(AuditRecord {
+        who,
+        route,
+        match_id,
+        action,
+        before,
+        after,
+        outcome,
+        details,
+    });
+    response
+}
@@ -0,0 +1,99 @@
+//! Tabuleiro em texto (`GET /match/{id}/board`)
+//!
+//! Cada unidade aparece com o avatar do dono. A legenda embaixo diz quem é
+//! cada avatar, lista as unidades e de quem é o turno, no idioma do cliente.
+
+use tatic_lib::{GameState, PlayerId};
+
+use crate::i18n::{translate, Lang};
+use crate::state::{is_game_over, winner};
+
+/// Casa sem unidade
+const EMPTY: char = '.';
+
+/// Como um jogador aparece no tabuleiro
+#[derive(Debug, Clone)]
+pub struct Piece {
+    pub player_id: PlayerId,
+    pub display_name: String,
+    pub avatar: char,
+}
+
+/// Desenha o tabuleiro e a legenda
+///
+/// Se os dois jogadores têm o mesmo avatar, o segundo aparece em minúscula.
+pub fn render(state: &GameState, pieces: &[Piece], lang: Lang) -> String {
+    let mut pieces = pieces.to_vec();
+    if let [first, second] = pieces.as_mut_slice()
+        && first.avatar == second.avatar
+    {
+        second.avatar = second.avatar.to_ascii_lowercase();
+    }
+    let avatar = |owner: &PlayerId| {
+        pieces
+            .iter()
+            .find(|p| &p.player_id == owner)
+            .map_or('?', |p| p.avatar)
+    };
+
+    let width = state.width.max(0) as usize;
+    let height = state.height.max(0) as usize;
+    let mut grid = vec![vec![EMPTY; width]; height];
+    for unit in &state.units {
+        if let (Ok(x), Ok(y)) = (usize::try_from(unit.pos.x), usize::try_from(unit.pos.y))
+            && x < width
+            && y < height
+        {
+            grid[y][x] = avatar(&unit.owner);
+        }
+    }
+
+    let header: String = (0..width).map(|x| char::from(b'0' + (x % 10) as u8)).collect();
+    let mut lines = vec![format!("   {}", header)];
+    for (y, row) in grid.iter().enumerate() {
+        lines.push(format!("{:>2} {}", y, row.iter().collect::<String>()));
+    }
+
+    lines.push(String::new());
+    lines.push(translate(lang, "board.legend", &[]));
+    lines.push(translate(lang, "board.empty", &[("empty", &EMPTY.to_string())]));
+    for piece in &pieces {
+        lines.push(translate(
+            lang,
+            "board.player",
+            &[
+                ("avatar", &piece.avatar.to_string()),
+                ("name", &piece.display_name),
+            ],
+        ));
+        for unit in state.units.iter().filter(|u| u.owner == piece.player_id) {
+            lines.push(translate(
+                lang,
+                "board.unit",
+                &[
+                    ("id", &unit.id.to_string()),
+                    ("kind", &unit.kind),
+                    ("x", &unit.pos.x.to_string()),
+                    ("y", &unit.pos.y.to_string()),
+                    ("hp", &unit.hp.to_string()),
+                ],
+            ));
+        }
+    }
+
+    lines.push(String::new());
+    lines.push(match winner(state) {
+        Some(winner) => translate(lang, "board.winner", &[("player", &winner)]),
+        None if is_game_over(state) => translate(lang, "board.draw", &[]),
+        None => translate(
+            lang,
+            "board.turn",
+            &[
+                ("player", &state.turn),
+                ("turn_count", &state.turn_count.to_string()),
+            ],
+        ),
+    });
+    lines.push(String::new());
+    lines.join("\n")
+}
@@ -0,0 +1,378 @@
+//! Bots externos
+//!
+//! Um bot registrado é um `PlayerId` como outro qualquer: basta colocá-lo numa
+//! partida. Quando chega a vez dele, o servidor envia `your_turn` pela conexão
+//! `/ws/bot` e espera a ação até o prazo. Sem resposta a tempo, sem conexão ou
+//! com ação inválida, joga `ai_choose_action` (ou `EndTurn`) no lugar dele.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tatic_lib::{ai_choose_action, Action, GameState, PlayerId};
+use tokio::sync::{mpsc, oneshot};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::audit::{AuditLog, AuditRecord, Outcome, StateStamp, Who};
+use crate::error::{ApiError, Reason};
+use crate::match_actor::MatchHandle;
+use crate::state::MatchId;
+
+/// Pedidos de jogada na fila de cada conexão
+pub const BOT_QUEUE_SIZE: usize = 16;
+
+/// Configuração dos turnos de bots
+#[derive(Debug, Clone, Copy)]
+pub struct BotConfig {
+    /// Prazo para o bot responder cada `your_turn`
+    pub turn_timeout: Duration,
+    /// Ações por turno antes de forçar `EndTurn`
+    pub max_actions_per_turn: u32,
+}
+
+impl Default for BotConfig {
+    fn default() -> Self {
+        Self {
+            turn_timeout: Duration::from_secs(10),
+            max_actions_per_turn: 50,
+        }
+    }
+}
+
+impl BotConfig {
+    /// Lê `BOT_TURN_TIMEOUT_SECS` e `BOT_MAX_ACTIONS_PER_TURN`
+    pub fn from_env() -> Self {
+        let default = Self::default();
+        let env = |name| std::env::var(name).ok().and_then(|v| v.parse::<u64>().ok());
+        Self {
+            turn_timeout: env("BOT_TURN_TIMEOUT_SECS")
+                .filter(|&secs| secs > 0)
+                .map(Duration::from_secs)
+                .unwrap_or(default.turn_timeout),
+            max_actions_per_turn: env("BOT_MAX_ACTIONS_PER_TURN")
+                .and_then(|n| u32::try_from(n).ok())
+                .filter(|&n| n > 0)
+                .unwrap_or(default.max_actions_per_turn),
+        }
+    }
+}
+
+/// Bot registrado
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Bot {
+    /// `PlayerId` usado nas partidas
+    #[schema(value_type = String)]
+    pub id: PlayerId,
+    pub name: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Resposta do registro: o token só é mostrado aqui
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BotCredentials {
+    #[serde(flatten)]
+    pub bot: Bot,
+    /// Usado em `/ws/bot?token=`
+    pub token: String,
+}
+
+/// Pedido de jogada para a conexão do bot
+pub struct TurnRequest {
+    pub match_id: MatchId,
+    pub state: GameState,
+    /// Motivo da recusa da ação anterior neste turno
+    pub last_error: Option<String>,
+    pub reply: oneshot::Sender<BotAction>,
+}
+
+/// Ação respondida pelo bot e quem a enviou
+pub struct BotAction {
+    pub action: Action,
+    pub origin: Who,
+}
+
+/// Rota registrada na auditoria para cada origem da jogada
+const ROUTE_BOT_ACTION: &str = "WS /ws/bot action";
+const ROUTE_FALLBACK: &str = "BOT ai_fallback";
+const ROUTE_FORCED_END_TURN: &str = "BOT max_actions_per_turn";
+
+/// Bots registrados e suas conexões
+#[derive(Default)]
+pub struct BotRegistry {
+    /// Prazos dos turnos; compartilhado com quem joga os turnos
+    pub config: BotConfig,
+    bots: HashMap<PlayerId, Bot>,
+    tokens: HashMap<String, PlayerId>,
+    connections: HashMap<PlayerId, mpsc::Sender<TurnRequest>>,
+    turns: TurnsInFlight,
+}
+
+impl BotRegistry {
+    pub fn new(config: BotConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    /// Registra bot; o ID é `bot-<name>`
+    pub fn register(&mut self, name: String) -> Result<BotCredentials, ApiError> {
+        let valid = !name.is_empty()
+            && name.len() <= 32
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(ApiError::InvalidRequest {
+                reason: Reason::new("reason.bot_name"),
+            });
+        }
+        let id = format!("bot-{}", name);
+        if self.bots.contains_key(&id) {
+            return Err(ApiError::Conflict {
+                reason: Reason::new("reason.bot_registered").arg("bot_id", &id),
+            });
+        }
+
+        let bot = Bot {
+            id: id.clone(),
+            name,
+            created_at: chrono::Utc::now(),
+        };
+        let token = Uuid::new_v4().simple().to_string();
+        self.tokens.insert(token.clone(), id.clone());
+        self.bots.insert(id, bot.clone());
+        Ok(BotCredentials { bot, token })
+    }
+
+    /// Bot dono do token
+    pub fn authenticate(&self, token: &str) -> Option<&Bot> {
+        self.bots.get(self.tokens.get(token)?)
+    }
+
+    pub fn is_bot(&self, player_id: &str) -> bool {
+        self.bots.contains_key(player_id)
+    }
+
+    /// Lista bots, do mais antigo para o mais novo
+    pub fn list(&self) -> Vec<Bot> {
+        let mut bots: Vec<_> = self.bots.values().cloned().collect();
+        bots.sort_by_key(|b| b.created_at);
+        bots
+    }
+
+    /// Registra a conexão do bot, substituindo uma anterior
+    pub fn connect(&mut self, bot_id: PlayerId, tx: mpsc::Sender<TurnRequest>) {
+        self.connections.insert(bot_id, tx);
+    }
+
+    /// Remove a conexão, se ainda for a mesma (o bot pode ter reconectado)
+    pub fn disconnect(&mut self, bot_id: &str, tx: &mpsc::Sender<TurnRequest>) {
+        if self.connections.get(bot_id).is_some_and(|c| c.same_channel(tx)) {
+            self.connections.remove(bot_id);
+        }
+    }
+
+    fn connection(&self, bot_id: &str) -> Option<mpsc::Sender<TurnRequest>> {
+        self.connections.get(bot_id).cloned()
+    }
+}
+
+/// Turnos de bots sendo jogados, por (bot, partida)
+///
+/// `true` pede mais uma volta a quem está jogando: a vez voltou ao bot
+/// enquanto o turno anterior terminava.
+type TurnsInFlight = Arc<Mutex<HashMap<(PlayerId, MatchId), bool>>>;
+
+/// Posse do turno de um bot numa partida: só uma task joga de cada vez, para
+/// o bot não receber o mesmo `your_turn` duas vezes
+struct TurnGuard {
+    turns: TurnsInFlight,
+    key: (PlayerId, MatchId),
+    held: bool,
+}
+
+impl TurnGuard {
+    /// `None` se outra task já joga o turno; ela confere a vez mais uma vez
+    fn acquire(turns: &TurnsInFlight, key: (PlayerId, MatchId)) -> Option<Self> {
+        let mut in_flight = turns.lock().unwrap();
+        if let Some(again) = in_flight.get_mut(&key) {
+            *again = true;
+            return None;
+        }
+        in_flight.insert(key.clone(), false);
+        Some(Self {
+            turns: turns.clone(),
+            key,
+            held: true,
+        })
+    }
+
+    /// Solta o turno, ou `true` (mantendo a posse) se pediram mais uma volta
+    fn release(&mut self) -> bool {
+        let mut in_flight = self.turns.lock().unwrap();
+        if let Some(again) = in_flight.get_mut(&self.key)
+            && *again
+        {
+            *again = false;
+            return true;
+        }
+        in_flight.remove(&self.key);
+        self.held = false;
+        false
+    }
+}
+
+impl Drop for TurnGuard {
+    fn drop(&mut self) {
+        if self.held {
+            self.turns.lock().unwrap().remove(&self.key);
+        }
+    }
+}
+
+/// Joga o turno do bot até a vez passar
+///
+/// Cada ação é pedida ao bot; quando ele não responde a tempo ou a ação é
+/// recusada, a IA embutida joga aquela ação no lugar dele. Se outra task já
+/// joga este bot nesta partida, ela confere a vez de novo e esta não faz nada.
+pub async fn play_turn(
+    registry: &tokio::sync::RwLock<BotRegistry>,
+    audit: &AuditLog,
+    handle: MatchHandle,
+    bot_id: PlayerId,
+) {
+    let turns = registry.read().await.turns.clone();
+    let Some(mut guard) = TurnGuard::acquire(&turns, (bot_id.clone(), handle.id.clone())) else {
+        return;
+    };
+    loop {
+        play(registry, audit, &handle, &bot_id).await;
+        if !guard.release() {
+            return;
+        }
+    }
+}
+
+async fn play(
+    registry: &tokio::sync::RwLock<BotRegistry>,
+    audit: &AuditLog,
+    handle: &MatchHandle,
+    bot_id: &PlayerId,
+) {
+    let config = registry.read().await.config;
+    let mut last_error = None;
+    let mut actions = 0;
+
+    loop {
+        let Some(match_data) = handle.snapshot().await else {
+            return;
+        };
+        if match_data.is_finished() || match_data.state.turn != *bot_id {
+            return;
+        }
+        if actions == config.max_actions_per_turn {
+            warn!("🤖 {} chegou a {} ações em {}, encerrando o turno", bot_id, actions, handle.id);
+            let _ = apply(
+                audit,
+                handle,
+                bot_id,
+                ROUTE_FORCED_END_TURN,
+                Who::default(),
+                Action::EndTurn,
+                &match_data.state,
+            )
+            .await;
+            return;
+        }
+        actions += 1;
+
+        let connection = registry.read().await.connection(bot_id);
+        let reply = match connection {
+            Some(tx) => {
+                let (reply, rx) = oneshot::channel();
+                let request = TurnRequest {
+                    match_id: handle.id.clone(),
+                    state: handle.view(&match_data.state, Some(bot_id)),
+                    last_error: last_error.take(),
+                    reply,
+                };
+                match tx.send(request).await {
+                    Ok(()) => tokio::time::timeout(config.turn_timeout, rx)
+                        .await
+                        .ok()
+                        .and_then(Result::ok),
+                    Err(_) => None,
+                }
+            }
+            None => None,
+        };
+
+        match reply {
+            Some(BotAction { action, origin }) => {
+                let result = apply(
+                    audit,
+                    handle,
+                    bot_id,
+                    ROUTE_BOT_ACTION,
+                    origin,
+                    action,
+                    &match_data.state,
+                )
+                .await;
+                if let Err(e) = result {
+                    warn!("🤖 Ação de {} recusada em {}: {}", bot_id, handle.id, e);
+                    last_error = Some(e.to_string());
+                    fallback(audit, handle, bot_id, &match_data.state).await;
+                }
+            }
+            None => {
+                info!("⏰ {} não respondeu em {}, usando a IA", bot_id, handle.id);
+                fallback(audit, handle, bot_id, &match_data.state).await;
+            }
+        }
+    }
+}
+
+/// Jogada no lugar do bot: `ai_choose_action`, ou `EndTurn` se ela falhar
+///
+/// Com `fog`, a IA só conhece o que o bot vê.
+async fn fallback(audit: &AuditLog, handle: &MatchHandle, bot_id: &PlayerId, state: &GameState) {
+    let play = |action| apply(audit, handle, bot_id, ROUTE_FALLBACK, Who::default(), action, state);
+    if let Some(action) = ai_choose_action(&handle.view(state, Some(bot_id)), bot_id)
+        && play(action).await.is_ok()
+    {
+        return;
+    }
+    let _ = play(Action::EndTurn).await;
+}
+
+/// Aplica uma jogada do bot e a registra na auditoria
+///
+/// `origin` vazio indica jogada feita pelo servidor no lugar do bot.
+async fn apply(
+    audit: &AuditLog,
+    handle: &MatchHandle,
+    bot_id: &PlayerId,
+    route: &str,
+    origin: Who,
+    action: Action,
+    before: &GameState,
+) -> Result<GameState, ApiError> {
+    let recorded = serde_json::to_value(&action).ok();
+    let result = handle.apply_action(bot_id.clone(), action).await;
+    audit.record(AuditRecord {
+        who: Who {
+            player_id: Some(bot_id.clone()),
+            ..origin
+        },
+        route: route.to_string(),
+        match_id: Some(handle.id.clone()),
+        action: recorded,
+        before: Some(StateStamp::of(before)),
+        after: Some(StateStamp::of(result.as_ref().unwrap_or(before))),
+        outcome: Outcome::of(&result),
+        details: serde_json::json!({}),
+    });
+    result
+}
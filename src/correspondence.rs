@@ -0,0 +1,201 @@
+//! Partidas por correspondência
+//!
+//! Turnos com prazo de horas ou dias: quem não joga até o prazo tem a vez
+//! passada (`EndTurn`). Essas partidas são gravadas em disco a cada mudança
+//! (`CORRESPONDENCE_DIR`, um arquivo JSON por partida) e recriadas quando o
+//! servidor reinicia. Partidas terminadas saem do disco.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tatic_lib::PlayerId;
+use tracing::{error, info, warn};
+use utoipa::ToSchema;
+
+use crate::error::{ApiError, Reason};
+use crate::state::{Match, MatchId};
+
+/// Maior prazo de turno aceito (30 dias)
+pub const MAX_TURN_DEADLINE_HOURS: u32 = 30 * 24;
+
+/// Modo da partida
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MatchMode {
+    /// Jogadores conectados, sem prazo de turno
+    #[default]
+    Live,
+    /// Cada turno tem até `turn_deadline_hours` horas
+    Correspondence { turn_deadline_hours: u32 },
+}
+
+impl MatchMode {
+    pub fn is_correspondence(&self) -> bool {
+        matches!(self, Self::Correspondence { .. })
+    }
+
+    /// Prazo de cada turno (`None` em partidas ao vivo)
+    pub fn turn_deadline(&self) -> Option<chrono::Duration> {
+        match self {
+            Self::Live => None,
+            Self::Correspondence { turn_deadline_hours } => {
+                Some(chrono::Duration::hours(i64::from(*turn_deadline_hours)))
+            }
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), ApiError> {
+        match self {
+            Self::Correspondence { turn_deadline_hours }
+                if !(1..=MAX_TURN_DEADLINE_HOURS).contains(turn_deadline_hours) =>
+            {
+                Err(ApiError::InvalidRequest {
+                    reason: Reason::new("reason.turn_deadline_hours")
+                        .arg("max", MAX_TURN_DEADLINE_HOURS),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Turno aguardando o jogador (`GET /players/{id}/my_turn`)
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PendingTurn {
+    pub match_id: MatchId,
+    #[schema(value_type = Vec<String>)]
+    pub opponents: Vec<PlayerId>,
+    pub turn_count: u32,
+    pub mode: MatchMode,
+    pub turn_started_at: chrono::DateTime<chrono::Utc>,
+    /// Quando a vez será passada (ausente em partidas ao vivo sem controle de tempo)
+    pub deadline: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl PendingTurn {
+    /// `None` se a vez não é do jogador
+    pub fn of(match_data: &Match, player_id: &str) -> Option<Self> {
+        if match_data.state.turn != player_id || match_data.is_finished() {
+            return None;
+        }
+        Some(Self {
+            match_id: match_data.id.clone(),
+            opponents: match_data
+                .state
+                .players
+                .iter()
+                .filter(|p| *p != player_id)
+                .cloned()
+                .collect(),
+            turn_count: match_data.state.turn_count,
+            mode: match_data.mode,
+            turn_started_at: match_data.turn_started_at,
+            deadline: match_data.turn_deadline(),
+        })
+    }
+}
+
+/// Pedido à task que grava as partidas em disco (ver `AppState`)
+pub enum StoreCommand {
+    /// Grava o estado atual da partida, se ela for por correspondência
+    Save(MatchId),
+    /// Apaga a partida do disco
+    Remove(MatchId),
+}
+
+/// Partidas por correspondência gravadas em disco
+pub struct MatchStore {
+    /// Sem diretório, nada é gravado
+    dir: Option<PathBuf>,
+}
+
+impl MatchStore {
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        Self { dir }
+    }
+
+    /// Lê `CORRESPONDENCE_DIR`; sem ela, as partidas só ficam em memória
+    pub fn from_env() -> Self {
+        let dir = std::env::var("CORRESPONDENCE_DIR")
+            .ok()
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from);
+        if dir.is_none() {
+            info!("📭 Sem CORRESPONDENCE_DIR: partidas por correspondência só em memória");
+        }
+        Self::new(dir)
+    }
+
+    fn path(&self, match_id: &str) -> Option<PathBuf> {
+        Some(self.dir.as_ref()?.join(format!("{}.json", match_id)))
+    }
+
+    /// Grava a partida (arquivo temporário + rename, para não deixar meio arquivo)
+    pub async fn save(&self, match_data: &Match) {
+        let (Some(dir), Some(path)) = (&self.dir, self.path(&match_data.id)) else {
+            return;
+        };
+        let json = match serde_json::to_vec(match_data) {
+            Ok(json) => json,
+            Err(e) => {
+                error!("❌ Partida {} não serializada: {}", match_data.id, e);
+                return;
+            }
+        };
+        let tmp = path.with_extension("json.tmp");
+        let result = async {
+            tokio::fs::create_dir_all(dir).await?;
+            tokio::fs::write(&tmp, json).await?;
+            tokio::fs::rename(&tmp, &path).await
+        }
+        .await;
+        if let Err(e) = result {
+            error!("❌ Partida {} não gravada em {}: {}", match_data.id, path.display(), e);
+        }
+    }
+
+    /// Apaga a partida do disco
+    pub async fn remove(&self, match_id: &str) {
+        let Some(path) = self.path(match_id) else {
+            return;
+        };
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => info!("🗑️ Partida {} removida de {}", match_id, path.display()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => warn!("⚠️ Não foi possível remover {}: {}", path.display(), e),
+        }
+    }
+
+    /// Lê todas as partidas gravadas; arquivos inválidos são ignorados
+    pub async fn load(&self) -> Vec<Match> {
+        let Some(dir) = &self.dir else {
+            return Vec::new();
+        };
+        let mut entries = match tokio::fs::read_dir(dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Vec::new(),
+            Err(e) => {
+                error!("❌ Não foi possível ler {}: {}", dir.display(), e);
+                return Vec::new();
+            }
+        };
+
+        let mut matches = Vec::new();
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let parsed = tokio::fs::read(&path)
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|bytes| {
+                    serde_json::from_slice::<Match>(&bytes).map_err(|e| e.to_string())
+                });
+            match parsed {
+                Ok(match_data) => matches.push(match_data),
+                Err(e) => warn!("⚠️ Ignorando {}: {}", path.display(), e),
+            }
+        }
+        matches
+    }
+}
@@ -0,0 +1,320 @@
+use axum::{
+    extract::{rejection::JsonRejection, FromRequest},
+    http::{header, StatusCode},
+    response::{IntoResponse, Json, Response},
+};
+use serde::Serialize;
+use std::fmt;
+use tatic_lib::PlayerId;
+use utoipa::ToSchema;
+
+use crate::i18n::{translate, Lang};
+use crate::state::MatchId;
+
+/// Erros da API com código estável para os clientes
+#[derive(Debug, Clone)]
+pub enum ApiError {
+    MatchNotFound { match_id: MatchId },
+    TournamentNotFound { tournament_id: String },
+    WebhookNotFound { webhook_id: String },
+    PlayerNotFound { player_id: PlayerId },
+    InvalidRequest { reason: Reason },
+    InvalidAction { reason: Reason },
+    NotYourTurn { player_id: PlayerId, current: PlayerId },
+    AiFailed,
+    Conflict { reason: Reason },
+    Unauthorized,
+    RateLimited { retry_after_secs: u64 },
+    /// Limite de partidas em andamento do IP
+    TooManyMatches { limit: usize },
+    /// Ação `index` (a partir de 0) de um lote falhou; nada foi aplicado
+    BatchFailed { index: usize, cause: Box<ApiError> },
+    /// `Idempotency-Key` já usada com outra requisição
+    IdempotencyKeyReused { key: String },
+    /// Ação `number` (a partir de 1) de uma partida importada é ilegal
+    ImportFailed { number: usize, player_id: PlayerId, cause: Box<ApiError> },
+    /// Falha do servidor (ex: task que entrou em pânico)
+    Internal,
+}
+
+impl ApiError {
+    /// Código estável, usado pelos clientes para decidir o tratamento
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::MatchNotFound { .. } => "match_not_found",
+            ApiError::TournamentNotFound { .. } => "tournament_not_found",
+            ApiError::WebhookNotFound { .. } => "webhook_not_found",
+            ApiError::PlayerNotFound { .. } => "player_not_found",
+            ApiError::InvalidRequest { .. } => "invalid_request",
+            ApiError::InvalidAction { .. } => "invalid_action",
+            ApiError::NotYourTurn { .. } => "not_your_turn",
+            ApiError::AiFailed => "ai_failed",
+            ApiError::Conflict { .. } => "conflict",
+            ApiError::Unauthorized => "unauthorized",
+            ApiError::RateLimited { .. } => "rate_limited",
+            ApiError::TooManyMatches { .. } => "too_many_matches",
+            ApiError::BatchFailed { .. } => "batch_failed",
+            ApiError::IdempotencyKeyReused { .. } => "idempotency_key_reused",
+            ApiError::ImportFailed { .. } => "import_failed",
+            ApiError::Internal => "internal",
+        }
+    }
+
+    /// Status HTTP correspondente
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ApiError::MatchNotFound { .. }
+            | ApiError::TournamentNotFound { .. }
+            | ApiError::WebhookNotFound { .. }
+            | ApiError::PlayerNotFound { .. } => {
+                StatusCode::NOT_FOUND
+            }
+            ApiError::InvalidRequest { .. } => StatusCode::BAD_REQUEST,
+            ApiError::InvalidAction { .. } => StatusCode::BAD_REQUEST,
+            ApiError::NotYourTurn { .. } => StatusCode::CONFLICT,
+            ApiError::AiFailed | ApiError::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+            ApiError::Conflict { .. } => StatusCode::CONFLICT,
+            ApiError::Unauthorized => StatusCode::UNAUTHORIZED,
+            ApiError::RateLimited { .. } | ApiError::TooManyMatches { .. } => {
+                StatusCode::TOO_MANY_REQUESTS
+            }
+            ApiError::BatchFailed { cause, .. } => cause.status(),
+            ApiError::IdempotencyKeyReused { .. } | ApiError::ImportFailed { .. } => {
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
+        }
+    }
+
+    /// Dados estruturados do erro, com o motivo no idioma pedido
+    pub fn details(&self, lang: Lang) -> serde_json::Value {
+        match self {
+            ApiError::MatchNotFound { match_id } => serde_json::json!({ "match_id": match_id }),
+            ApiError::TournamentNotFound { tournament_id } => {
+                serde_json::json!({ "tournament_id": tournament_id })
+            }
+            ApiError::WebhookNotFound { webhook_id } => {
+                serde_json::json!({ "webhook_id": webhook_id })
+            }
+            ApiError::PlayerNotFound { player_id } => {
+                serde_json::json!({ "player_id": player_id })
+            }
+            ApiError::InvalidAction { reason }
+            | ApiError::InvalidRequest { reason }
+            | ApiError::Conflict { reason } => serde_json::json!({ "reason": reason.message(lang) }),
+            ApiError::NotYourTurn { player_id, current } => {
+                serde_json::json!({ "player_id": player_id, "current_turn": current })
+            }
+            ApiError::RateLimited { retry_after_secs } => {
+                serde_json::json!({ "retry_after_secs": retry_after_secs })
+            }
+            ApiError::TooManyMatches { limit } => serde_json::json!({ "limit": limit }),
+            ApiError::BatchFailed { index, cause } => serde_json::json!({
+                "index": index,
+                "cause": { "code": cause.code(), "details": cause.details(lang) },
+            }),
+            ApiError::IdempotencyKeyReused { key } => serde_json::json!({ "key": key }),
+            ApiError::ImportFailed {
+                number,
+                player_id,
+                cause,
+            } => serde_json::json!({
+                "number": number,
+                "player_id": player_id,
+                "cause": { "code": cause.code(), "details": cause.details(lang) },
+            }),
+            ApiError::AiFailed | ApiError::Unauthorized | ApiError::Internal => {
+                serde_json::json!({})
+            }
+        }
+    }
+
+    /// Mensagem traduzida para o idioma pedido
+    pub fn message(&self, lang: Lang) -> String {
+        let key = format!("error.{}", self.code());
+        match self {
+            ApiError::MatchNotFound { match_id } => {
+                translate(lang, &key, &[("match_id", match_id)])
+            }
+            ApiError::TournamentNotFound { tournament_id } => {
+                translate(lang, &key, &[("tournament_id", tournament_id)])
+            }
+            ApiError::WebhookNotFound { webhook_id } => {
+                translate(lang, &key, &[("webhook_id", webhook_id)])
+            }
+            ApiError::PlayerNotFound { player_id } => {
+                translate(lang, &key, &[("player_id", player_id)])
+            }
+            ApiError::InvalidAction { reason }
+            | ApiError::InvalidRequest { reason }
+            | ApiError::Conflict { reason } => {
+                translate(lang, &key, &[("reason", &reason.message(lang))])
+            }
+            ApiError::NotYourTurn { player_id, .. } => {
+                translate(lang, &key, &[("player_id", player_id)])
+            }
+            ApiError::RateLimited { retry_after_secs } => {
+                translate(lang, &key, &[("retry_after_secs", &retry_after_secs.to_string())])
+            }
+            ApiError::TooManyMatches { limit } => {
+                translate(lang, &key, &[("limit", &limit.to_string())])
+            }
+            ApiError::BatchFailed { index, cause } => translate(
+                lang,
+                &key,
+                &[("index", &index.to_string()), ("cause", &cause.message(lang))],
+            ),
+            ApiError::IdempotencyKeyReused { key: idempotency_key } => {
+                translate(lang, &key, &[("key", idempotency_key)])
+            }
+            ApiError::ImportFailed {
+                number,
+                player_id,
+                cause,
+            } => translate(
+                lang,
+                &key,
+                &[
+                    ("number", &number.to_string()),
+                    ("player_id", player_id),
+                    ("cause", &cause.message(lang)),
+                ],
+            ),
+            ApiError::AiFailed | ApiError::Unauthorized | ApiError::Internal => {
+                translate(lang, &key, &[])
+            }
+        }
+    }
+}
+
+/// Motivo de um erro
+///
+/// Uma chave `reason.*` do catálogo com seus parâmetros, traduzida junto com
+/// a mensagem; ou texto vindo de fora (serde, axum, regras do jogo), que vai
+/// como veio.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Reason {
+    Key {
+        key: &'static str,
+        args: Vec<(&'static str, Reason)>,
+    },
+    Text(String),
+}
+
+impl Reason {
+    pub fn new(key: &'static str) -> Self {
+        Reason::Key {
+            key,
+            args: Vec::new(),
+        }
+    }
+
+    /// Acrescenta o parâmetro `{name}`
+    pub fn arg(self, name: &'static str, value: impl ToString) -> Self {
+        self.with(name, Reason::Text(value.to_string()))
+    }
+
+    /// Acrescenta o parâmetro `{name}` com outro motivo, traduzido junto
+    pub fn with(mut self, name: &'static str, reason: Reason) -> Self {
+        if let Reason::Key { args, .. } = &mut self {
+            args.push((name, reason));
+        }
+        self
+    }
+
+    /// Texto no idioma pedido
+    pub fn message(&self, lang: Lang) -> String {
+        match self {
+            Reason::Key { key, args } => {
+                let args: Vec<_> = args
+                    .iter()
+                    .map(|(name, value)| (*name, value.message(lang)))
+                    .collect();
+                let args: Vec<_> = args
+                    .iter()
+                    .map(|(name, value)| (*name, value.as_str()))
+                    .collect();
+                translate(lang, key, &args)
+            }
+            Reason::Text(text) => text.clone(),
+        }
+    }
+}
+
+impl From<String> for Reason {
+    fn from(text: String) -> Self {
+        Reason::Text(text)
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message(Lang::default()))
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+/// Corpo do erro na resposta
+#[derive(Serialize, ToSchema)]
+pub struct ErrorBody {
+    /// Código estável (ex: `match_not_found`)
+    pub code: String,
+    /// Mensagem legível
+    pub message: String,
+    /// Dados estruturados, dependem do código
+    #[schema(value_type = Object)]
+    pub details: serde_json::Value,
+}
+
+/// Response para erros
+#[derive(Serialize, ToSchema)]
+pub struct ErrorResponse {
+    pub success: bool,
+    pub error: ErrorBody,
+}
+
+impl ErrorResponse {
+    /// Monta a resposta com a mensagem no idioma pedido
+    pub fn localized(err: &ApiError, lang: Lang) -> Self {
+        Self {
+            success: false,
+            error: ErrorBody {
+                code: err.code().to_string(),
+                message: err.message(lang),
+                details: err.details(lang),
+            },
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    /// Responde em pt-BR; o middleware `i18n::localize_errors` traduz se preciso
+    fn into_response(self) -> Response {
+        let mut response =
+            (self.status(), Json(ErrorResponse::localized(&self, Lang::default()))).into_response();
+        if let ApiError::RateLimited { retry_after_secs } = self {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, retry_after_secs.into());
+        }
+        response.extensions_mut().insert(self);
+        response
+    }
+}
+
+impl From<JsonRejection> for ApiError {
+    /// Corpo ausente, malformado ou com campos errados
+    fn from(rejection: JsonRejection) -> Self {
+        ApiError::InvalidRequest {
+            reason: rejection.body_text().into(),
+        }
+    }
+}
+
+/// `Json` que responde com `ErrorResponse` quando o corpo é inválido
+///
+/// O extrator do axum responde texto puro; este converte a rejeição em
+/// `ApiError::InvalidRequest`.
+#[derive(FromRequest)]
+#[from_request(via(Json), rejection(ApiError))]
+pub struct ApiJson<T>(pub T);
@@ -0,0 +1,44 @@
+//! Eventos internos do ciclo de vida das partidas
+//!
+//! Publicados pelos atores das partidas para os subsistemas que reagem a eles
+//! (torneios, estatísticas, ...). Não confundir com o broadcast para observers.
+//!
+//! O canal não tem limite: um ator nunca espera pelo ouvinte e nenhum evento
+//! é descartado, nem em rajadas como um lote de ações.
+
+use tatic_lib::{Action, PlayerId};
+use tokio::sync::mpsc;
+
+use crate::state::{Match, MatchId};
+
+/// Lado dos atores do canal de eventos de partidas
+pub type MatchEvents = mpsc::UnboundedSender<MatchEvent>;
+
+/// Evento de partida
+#[derive(Clone)]
+pub enum MatchEvent {
+    /// O ator da partida foi iniciado
+    Created { match_id: MatchId, players: Vec<PlayerId> },
+    /// Uma ação foi aplicada (uma por ação, também nos lotes)
+    ActionApplied {
+        match_id: MatchId,
+        player: PlayerId,
+        action: Action,
+        turn_count: u32,
+    },
+    /// A vez passou para outro jogador (também enviado quando a partida começa)
+    TurnChanged { match_id: MatchId, player: PlayerId },
+    /// A fase do jogo passou a indicar fim de partida
+    Finished(Box<Match>),
+}
+
+impl MatchEvent {
+    pub fn match_id(&self) -> &str {
+        match self {
+            Self::Created { match_id, .. }
+            | Self::ActionApplied { match_id, .. }
+            | Self::TurnChanged { match_id, .. } => match_id,
+            Self::Finished(match_data) => &match_data.id,
+        }
+    }
+}
@@ -0,0 +1,120 @@
+//! Broadcast de estado para observers
+//!
+//! Cada partida publica num `tokio::sync::broadcast`: o envio nunca espera
+//! observers lentos. Quem fica para trás perde as mensagens mais antigas e é
+//! tratado conforme a `LagPolicy`.
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use utoipa::ToSchema;
+
+/// Mensagens guardadas por partida para observers atrasados
+const DEFAULT_BUFFER: usize = 100;
+
+/// O que fazer com um observer que perdeu mensagens
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LagPolicy {
+    /// Ignora as mensagens perdidas e continua das mais novas
+    DropOldest,
+    /// Envia um snapshot completo do estado (`type: resync`)
+    #[default]
+    Resync,
+    /// Fecha a conexão
+    Disconnect,
+}
+
+impl LagPolicy {
+    fn parse(value: &str) -> Option<LagPolicy> {
+        match value {
+            "drop_oldest" => Some(LagPolicy::DropOldest),
+            "resync" => Some(LagPolicy::Resync),
+            "disconnect" => Some(LagPolicy::Disconnect),
+            _ => None,
+        }
+    }
+}
+
+/// Configuração do broadcast
+#[derive(Debug, Clone, Copy)]
+pub struct FanoutConfig {
+    /// Capacidade do canal de cada partida
+    pub buffer: usize,
+    /// Política para observers atrasados
+    pub lag_policy: LagPolicy,
+}
+
+impl Default for FanoutConfig {
+    fn default() -> Self {
+        Self {
+            buffer: DEFAULT_BUFFER,
+            lag_policy: LagPolicy::default(),
+        }
+    }
+}
+
+impl FanoutConfig {
+    /// Lê `WS_BUFFER` e `WS_LAG_POLICY` (drop_oldest, resync, disconnect)
+    pub fn from_env() -> Self {
+        let default = Self::default();
+        Self {
+            buffer: std::env::var("WS_BUFFER")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|&b| b > 0)
+                .unwrap_or(default.buffer),
+            lag_policy: std::env::var("WS_LAG_POLICY")
+                .ok()
+                .and_then(|v| LagPolicy::parse(&v))
+                .unwrap_or(default.lag_policy),
+        }
+    }
+}
+
+/// Contadores de mensagens perdidas por observers lentos
+#[derive(Debug, Default)]
+pub struct FanoutMetrics {
+    dropped_messages: AtomicU64,
+    lag_events: AtomicU64,
+    resyncs: AtomicU64,
+    disconnects: AtomicU64,
+}
+
+/// Cópia dos contadores para a API
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FanoutMetricsSnapshot {
+    /// Mensagens descartadas para observers atrasados
+    pub dropped_messages: u64,
+    /// Vezes que algum observer ficou para trás
+    pub lag_events: u64,
+    /// Snapshots reenviados (`LagPolicy::Resync`)
+    pub resyncs: u64,
+    /// Conexões fechadas por atraso (`LagPolicy::Disconnect`)
+    pub disconnects: u64,
+}
+
+impl FanoutMetrics {
+    /// Registra que um observer perdeu `skipped` mensagens
+    pub fn record_lag(&self, skipped: u64, policy: LagPolicy) {
+        self.dropped_messages.fetch_add(skipped, Ordering::Relaxed);
+        self.lag_events.fetch_add(1, Ordering::Relaxed);
+        match policy {
+            LagPolicy::DropOldest => {}
+            LagPolicy::Resync => {
+                self.resyncs.fetch_add(1, Ordering::Relaxed);
+            }
+            LagPolicy::Disconnect => {
+                self.disconnects.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    pub fn snapshot(&self) -> FanoutMetricsSnapshot {
+        FanoutMetricsSnapshot {
+            dropped_messages: self.dropped_messages.load(Ordering::Relaxed),
+            lag_events: self.lag_events.load(Ordering::Relaxed),
+            resyncs: self.resyncs.load(Ordering::Relaxed),
+            disconnects: self.disconnects.load(Ordering::Relaxed),
+        }
+    }
+}
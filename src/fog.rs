@@ -0,0 +1,38 @@
+//! Névoa de guerra (`settings.fog`)
+//!
+//! O servidor guarda o estado completo; o que sai para os clientes passa por
+//! `view`. Cada jogador vê as próprias unidades e as inimigas a até
+//! `SIGHT_RANGE` casas de alguma delas. Quem não joga a partida não vê
+//! unidade nenhuma. Terminada a partida, todos veem tudo.
+
+use tatic_lib::{Coord, GameState};
+
+use crate::state::is_game_over;
+
+/// Alcance da visão de cada unidade (distância de Manhattan)
+pub const SIGHT_RANGE: i32 = 3;
+
+/// Estado como `viewer` o vê (`None`: espectador)
+///
+/// Sem `fog`, ou com a partida terminada, é o estado completo.
+pub fn view(state: &GameState, fog: bool, viewer: Option<&str>) -> GameState {
+    let mut view = state.clone();
+    if !fog || is_game_over(state) {
+        return view;
+    }
+    let is_own = |owner: &str| viewer == Some(owner);
+    let sight: Vec<Coord> = state
+        .units
+        .iter()
+        .filter(|u| is_own(&u.owner))
+        .map(|u| u.pos)
+        .collect();
+    view.units.retain(|u| {
+        is_own(&u.owner) || sight.iter().any(|pos| distance(*pos, u.pos) <= SIGHT_RANGE)
+    });
+    view
+}
+
+fn distance(a: Coord, b: Coord) -> i32 {
+    (a.x - b.x).abs() + (a.y - b.y).abs()
+}
@@ -0,0 +1,221 @@
+//! Histórico de partidas por jogador
+//!
+//! Índice mantido pelos eventos de partidas (criação, ações e fim), para que
+//! `GET /players/{id}/matches` não precise consultar os atores. Partidas
+//! removidas pelo admin continuam no histórico como `closed`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tatic_lib::PlayerId;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::state::{winner, Match, MatchId};
+
+/// Itens por página quando `per_page` não é informado
+const DEFAULT_PER_PAGE: usize = 20;
+/// Maior `per_page` aceito
+const MAX_PER_PAGE: usize = 100;
+
+/// Situação da partida no histórico
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryStatus {
+    Active,
+    Finished,
+    /// Removida antes de terminar
+    Closed,
+}
+
+/// Resultado do ponto de vista do jogador
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PlayerOutcome {
+    Won,
+    Lost,
+    Draw,
+}
+
+/// Partida no índice
+#[derive(Debug, Clone)]
+struct HistoryEntry {
+    match_id: MatchId,
+    players: Vec<PlayerId>,
+    status: HistoryStatus,
+    winner: Option<PlayerId>,
+    turn_count: u32,
+    created_at: chrono::DateTime<chrono::Utc>,
+    finished_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Links para acompanhar ou rever a partida
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MatchLinks {
+    /// Ausente em partidas removidas
+    pub export: Option<String>,
+    /// Só para partidas terminadas
+    pub replay: Option<String>,
+    /// Só para partidas em andamento
+    pub observe: Option<String>,
+}
+
+/// Partida no histórico de um jogador
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MatchHistoryItem {
+    pub match_id: MatchId,
+    #[schema(value_type = Vec<String>)]
+    pub opponents: Vec<PlayerId>,
+    pub status: HistoryStatus,
+    /// Ausente enquanto a partida não termina
+    pub outcome: Option<PlayerOutcome>,
+    /// `turn_count` atual, ou o final nas terminadas
+    pub turn_count: u32,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub finished_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Da criação ao fim (ou até agora, se em andamento)
+    pub duration_secs: i64,
+    pub links: MatchLinks,
+}
+
+/// Página do histórico
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MatchHistoryPage {
+    pub items: Vec<MatchHistoryItem>,
+    /// A partir de 1
+    pub page: usize,
+    pub per_page: usize,
+    /// Partidas que atendem ao filtro, em todas as páginas
+    pub total: usize,
+}
+
+/// Query params para GET /players/{id}/matches
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct HistoryQuery {
+    /// A partir de 1 (padrão 1)
+    page: Option<usize>,
+    /// Padrão 20, máximo 100
+    per_page: Option<usize>,
+    /// Só partidas nesta situação
+    #[param(inline)]
+    status: Option<HistoryStatus>,
+}
+
+/// Índice das partidas de cada jogador
+#[derive(Default)]
+pub struct MatchHistory {
+    entries: HashMap<MatchId, HistoryEntry>,
+    /// Partidas de cada jogador, em ordem de criação
+    by_player: HashMap<PlayerId, Vec<MatchId>>,
+}
+
+impl MatchHistory {
+    /// Registra partida nova
+    pub fn created(&mut self, match_id: &str, players: &[PlayerId]) {
+        if self.entries.contains_key(match_id) {
+            return;
+        }
+        self.entries.insert(
+            match_id.to_string(),
+            HistoryEntry {
+                match_id: match_id.to_string(),
+                players: players.to_vec(),
+                status: HistoryStatus::Active,
+                winner: None,
+                turn_count: 0,
+                created_at: chrono::Utc::now(),
+                finished_at: None,
+            },
+        );
+        for player in players {
+            self.by_player
+                .entry(player.clone())
+                .or_default()
+                .push(match_id.to_string());
+        }
+    }
+
+    /// Atualiza o número do turno após uma ação
+    pub fn action_applied(&mut self, match_id: &str, turn_count: u32) {
+        if let Some(entry) = self.entries.get_mut(match_id) {
+            entry.turn_count = turn_count;
+        }
+    }
+
+    /// Guarda o resultado da partida terminada
+    pub fn finished(&mut self, match_data: &Match) {
+        self.created(&match_data.id, &match_data.state.players);
+        let entry = self.entries.get_mut(&match_data.id).expect("criado acima");
+        entry.status = HistoryStatus::Finished;
+        entry.winner = winner(&match_data.state);
+        entry.turn_count = match_data.state.turn_count;
+        entry.created_at = match_data.created_at;
+        entry.finished_at = Some(match_data.updated_at);
+    }
+
+    /// Partida removida: se ainda não tinha terminado, fica como `closed`
+    pub fn closed(&mut self, match_id: &str, players: &[PlayerId]) {
+        self.created(match_id, players);
+        let entry = self.entries.get_mut(match_id).expect("criado acima");
+        if entry.status == HistoryStatus::Active {
+            entry.status = HistoryStatus::Closed;
+            entry.finished_at = Some(chrono::Utc::now());
+        }
+    }
+
+    /// Página do histórico do jogador, das partidas mais novas para as mais antigas
+    pub fn page(&self, player_id: &str, query: &HistoryQuery) -> MatchHistoryPage {
+        let page = query.page.unwrap_or(1).max(1);
+        let per_page = query.per_page.unwrap_or(DEFAULT_PER_PAGE).clamp(1, MAX_PER_PAGE);
+
+        let entries: Vec<_> = self
+            .by_player
+            .get(player_id)
+            .into_iter()
+            .flatten()
+            .rev()
+            .filter_map(|id| self.entries.get(id))
+            .filter(|e| query.status.is_none_or(|status| e.status == status))
+            .collect();
+        let items = entries
+            .iter()
+            .skip((page - 1).saturating_mul(per_page))
+            .take(per_page)
+            .map(|entry| item(entry, player_id))
+            .collect();
+
+        MatchHistoryPage {
+            items,
+            page,
+            per_page,
+            total: entries.len(),
+        }
+    }
+}
+
+fn item(entry: &HistoryEntry, player_id: &str) -> MatchHistoryItem {
+    let outcome = match (entry.status, &entry.winner) {
+        (HistoryStatus::Finished, None) => Some(PlayerOutcome::Draw),
+        (HistoryStatus::Finished, Some(w)) if w == player_id => Some(PlayerOutcome::Won),
+        (HistoryStatus::Finished, Some(_)) => Some(PlayerOutcome::Lost),
+        _ => None,
+    };
+    let end = entry.finished_at.unwrap_or_else(chrono::Utc::now);
+    let id = &entry.match_id;
+    MatchHistoryItem {
+        match_id: id.clone(),
+        opponents: entry.players.iter().filter(|p| *p != player_id).cloned().collect(),
+        status: entry.status,
+        outcome,
+        turn_count: entry.turn_count,
+        created_at: entry.created_at,
+        finished_at: entry.finished_at,
+        duration_secs: (end - entry.created_at).num_seconds(),
+        links: MatchLinks {
+            export: (entry.status != HistoryStatus::Closed)
+                .then(|| format!("/match/{}/export", id)),
+            replay: (entry.status == HistoryStatus::Finished)
+                .then(|| format!("/ws/replay?match_id={}", id)),
+            observe: (entry.status == HistoryStatus::Active)
+                .then(|| format!("/ws?match_id={}", id)),
+        },
+    }
+}
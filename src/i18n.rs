@@ -0,0 +1,293 @@
+//! Catálogo de mensagens (pt-BR e en)
+//!
+//! Para adicionar um idioma: nova variante em `Lang` e uma tabela como `PT_BR`.
+
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{header, HeaderMap},
+    middleware::Next,
+    response::Response,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::error::{ApiError, ErrorResponse};
+use crate::state::AppState;
+
+/// Idiomas suportados
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum Lang {
+    #[default]
+    #[serde(rename = "pt-BR")]
+    PtBr,
+    #[serde(rename = "en")]
+    En,
+}
+
+impl Lang {
+    /// Interpreta uma tag de idioma (ex: `pt`, `pt-BR`, `en-US`)
+    pub fn parse(tag: &str) -> Option<Lang> {
+        let primary = tag.trim().split(['-', '_']).next()?.to_ascii_lowercase();
+        match primary.as_str() {
+            "pt" => Some(Lang::PtBr),
+            "en" => Some(Lang::En),
+            _ => None,
+        }
+    }
+
+    /// Escolhe o idioma suportado de maior peso no header `Accept-Language`
+    ///
+    /// Idiomas com `q=0` foram recusados pelo cliente e são ignorados.
+    pub fn from_accept_language(value: &str) -> Option<Lang> {
+        let mut candidates: Vec<(f32, Lang)> = value
+            .split(',')
+            .filter_map(|part| {
+                let mut pieces = part.split(';');
+                let lang = Lang::parse(pieces.next()?)?;
+                let weight = pieces
+                    .find_map(|p| p.trim().strip_prefix("q="))
+                    .and_then(|q| q.parse().ok())
+                    .unwrap_or(1.0);
+                (weight > 0.0).then_some((weight, lang))
+            })
+            .collect();
+        candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+        candidates.first().map(|(_, lang)| *lang)
+    }
+
+    fn catalog(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Lang::PtBr => PT_BR,
+            Lang::En => EN,
+        }
+    }
+}
+
+const PT_BR: &[(&str, &str)] = &[
+    ("error.match_not_found", "Partida {match_id} não encontrada"),
+    ("error.invalid_action", "Ação inválida: {reason}"),
+    ("error.tournament_not_found", "Torneio {tournament_id} não encontrado"),
+    ("error.webhook_not_found", "Webhook {webhook_id} não encontrado"),
+    ("error.player_not_found", "Jogador {player_id} não encontrado"),
+    ("error.invalid_request", "Requisição inválida: {reason}"),
+    ("error.not_your_turn", "Não é o turno de {player_id}"),
+    ("error.ai_failed", "IA não conseguiu escolher ação"),
+    ("error.conflict", "Conflito: {reason}"),
+    ("error.unauthorized", "Não autorizado"),
+    ("error.internal", "Erro interno do servidor"),
+    ("error.rate_limited", "Muitas requisições, tente novamente em {retry_after_secs}s"),
+    ("error.too_many_matches", "Limite de {limit} partidas em andamento atingido"),
+    ("error.batch_failed", "Ação {index} do lote falhou: {cause}"),
+    ("error.idempotency_key_reused", "A chave {key} já foi usada com outra requisição"),
+    ("error.import_failed", "Ação {number} ({player_id}) é ilegal: {cause}"),
+    ("reason.bot_name", "nome do bot deve ter até 32 letras, números, '-' ou '_'"),
+    ("reason.bot_registered", "bot {bot_id} já registrado"),
+    ("reason.display_name", "display_name deve ter de 1 a {max} caracteres"),
+    ("reason.avatar", "avatar deve ser um caractere ASCII visível"),
+    ("reason.turn_deadline_hours", "turn_deadline_hours deve estar entre 1 e {max}"),
+    ("reason.time_control_correspondence", "time_control não se aplica a partidas por correspondência"),
+    ("reason.already_host", "{player_id} já é o anfitrião"),
+    ("reason.not_a_player", "{player_id} não joga a partida {match_id}"),
+    ("reason.not_in_tournament", "{player_id} não está no torneio"),
+    ("reason.no_pending_turn", "nenhum turno pendente em {match_id}"),
+    ("reason.match_in_progress", "partida {match_id} ainda em andamento"),
+    ("reason.match_finished", "partida {match_id} já terminou"),
+    ("reason.players_changed", "players deve continuar sendo {players}"),
+    ("reason.replay_speed", "speed deve estar entre {min} e {max}"),
+    ("reason.batch_size", "o lote deve ter de 1 a {max} ações"),
+    ("reason.idempotency_key", "Idempotency-Key deve ter de 1 a 255 caracteres"),
+    ("reason.idempotency_in_flight", "requisição com a chave {key} ainda em andamento"),
+    ("reason.webhook_url", "URL inválida: {url}"),
+    ("reason.webhook_secret", "secret não pode ser vazio"),
+    ("reason.dead_letter_missing", "entrega {delivery_id} não está na fila de falhas"),
+    ("reason.unknown_map", "mapa {map} desconhecido (disponíveis: {available})"),
+    ("reason.board_size", "board deve ter lados entre {min} e {max}"),
+    ("reason.roster_empty", "roster precisa de unidades dos dois lados"),
+    ("reason.turn_secs", "time_control.turn_secs deve estar entre {min} e {max}"),
+    ("reason.row_too_narrow", "{count} unidades não cabem numa linha de largura {width}"),
+    ("reason.unknown_unit", "unidade {kind} desconhecida (disponíveis: {available})"),
+    ("reason.duplicate_player", "jogador repetido: {player_id}"),
+    ("reason.tournament_min_players", "torneio precisa de pelo menos 2 jogadores"),
+    ("reason.tournament_max_players", "torneio aceita no máximo {max} jogadores"),
+    ("reason.swiss_rounds", "suíço precisa de pelo menos 1 rodada"),
+    ("reason.arena_matches", "matches deve estar entre 1 e {max}"),
+    ("reason.arena_randomness", "randomness deve estar entre 0 e 1"),
+    ("reason.arena_max_turns", "max_turns deve ser maior que zero"),
+    ("reason.ai_depth", "depth deve estar entre 1 e {max}"),
+    ("reason.ai_time_budget", "time_budget_ms deve estar entre 1 e {max}"),
+    ("reason.import_line", "linha {line}: {reason}"),
+    ("reason.import_empty", "documento vazio"),
+    ("reason.import_header", "esperado \"{magic} <versão>\""),
+    ("reason.import_version", "versão {version} não suportada"),
+    ("reason.import_action_number", "esperada a ação {number}"),
+    ("reason.import_field_syntax", "esperado \"campo: valor\""),
+    ("reason.import_field_repeated", "campo {field} repetido"),
+    ("reason.import_field_missing", "campo {field} ausente"),
+    ("reason.import_field_invalid", "{field}: {error}"),
+    ("reason.import_action_count", "{declared} ações declaradas, {found} encontradas"),
+    ("reason.import_players_mismatch", "players diferente dos jogadores de initial_state"),
+    ("reason.import_player_missing", "jogador ausente"),
+    ("reason.import_action_missing", "ação ausente"),
+    ("reason.import_trailing", "conteúdo extra após a ação"),
+    ("reason.import_players", "players deve ter dois jogadores"),
+    ("reason.import_seed", "settings.seed é obrigatório com starting_player random"),
+    ("reason.import_initial_state", "initial_state não é o estado inicial de uma partida com esses jogadores e settings"),
+    ("reason.import_result", "resultado declarado {declared} difere do obtido {obtained}"),
+    ("ws.observing", "Observando partida {match_id}"),
+    ("ws.replaying", "Replay da partida {match_id}"),
+    ("ws.state_updated", "Turno de {turn} (rodada {turn_count})"),
+    ("board.legend", "Legenda:"),
+    ("board.empty", "  {empty} casa vazia"),
+    ("board.player", "  {avatar} {name}"),
+    ("board.unit", "      #{id} {kind} em ({x}, {y}), {hp} PV"),
+    ("board.turn", "Turno de {player} (rodada {turn_count})"),
+    ("board.winner", "Fim de jogo: vitória de {player}"),
+    ("board.draw", "Fim de jogo: empate"),
+];
+
+const EN: &[(&str, &str)] = &[
+    ("error.match_not_found", "Match {match_id} not found"),
+    ("error.invalid_action", "Invalid action: {reason}"),
+    ("error.tournament_not_found", "Tournament {tournament_id} not found"),
+    ("error.webhook_not_found", "Webhook {webhook_id} not found"),
+    ("error.player_not_found", "Player {player_id} not found"),
+    ("error.invalid_request", "Invalid request: {reason}"),
+    ("error.not_your_turn", "It is not {player_id}'s turn"),
+    ("error.ai_failed", "AI could not choose an action"),
+    ("error.conflict", "Conflict: {reason}"),
+    ("error.unauthorized", "Unauthorized"),
+    ("error.internal", "Internal server error"),
+    ("error.rate_limited", "Too many requests, retry in {retry_after_secs}s"),
+    ("error.too_many_matches", "Limit of {limit} matches in progress reached"),
+    ("error.batch_failed", "Batch action {index} failed: {cause}"),
+    ("error.idempotency_key_reused", "Key {key} was already used with a different request"),
+    ("error.import_failed", "Action {number} ({player_id}) is illegal: {cause}"),
+    ("reason.bot_name", "bot name must have up to 32 letters, digits, '-' or '_'"),
+    ("reason.bot_registered", "bot {bot_id} is already registered"),
+    ("reason.display_name", "display_name must have 1 to {max} characters"),
+    ("reason.avatar", "avatar must be a visible ASCII character"),
+    ("reason.turn_deadline_hours", "turn_deadline_hours must be between 1 and {max}"),
+    ("reason.time_control_correspondence", "time_control does not apply to correspondence matches"),
+    ("reason.already_host", "{player_id} is already the host"),
+    ("reason.not_a_player", "{player_id} does not play match {match_id}"),
+    ("reason.not_in_tournament", "{player_id} is not in the tournament"),
+    ("reason.no_pending_turn", "no pending turn in {match_id}"),
+    ("reason.match_in_progress", "match {match_id} is still in progress"),
+    ("reason.match_finished", "match {match_id} is already over"),
+    ("reason.players_changed", "players must remain {players}"),
+    ("reason.replay_speed", "speed must be between {min} and {max}"),
+    ("reason.batch_size", "the batch must have 1 to {max} actions"),
+    ("reason.idempotency_key", "Idempotency-Key must have 1 to 255 characters"),
+    ("reason.idempotency_in_flight", "request with key {key} is still in progress"),
+    ("reason.webhook_url", "invalid URL: {url}"),
+    ("reason.webhook_secret", "secret must not be empty"),
+    ("reason.dead_letter_missing", "delivery {delivery_id} is not in the dead-letter queue"),
+    ("reason.unknown_map", "unknown map {map} (available: {available})"),
+    ("reason.board_size", "board sides must be between {min} and {max}"),
+    ("reason.roster_empty", "roster needs units on both sides"),
+    ("reason.turn_secs", "time_control.turn_secs must be between {min} and {max}"),
+    ("reason.row_too_narrow", "{count} units do not fit in a row of width {width}"),
+    ("reason.unknown_unit", "unknown unit {kind} (available: {available})"),
+    ("reason.duplicate_player", "duplicate player: {player_id}"),
+    ("reason.tournament_min_players", "a tournament needs at least 2 players"),
+    ("reason.tournament_max_players", "a tournament accepts at most {max} players"),
+    ("reason.swiss_rounds", "swiss needs at least 1 round"),
+    ("reason.arena_matches", "matches must be between 1 and {max}"),
+    ("reason.arena_randomness", "randomness must be between 0 and 1"),
+    ("reason.arena_max_turns", "max_turns must be greater than zero"),
+    ("reason.ai_depth", "depth must be between 1 and {max}"),
+    ("reason.ai_time_budget", "time_budget_ms must be between 1 and {max}"),
+    ("reason.import_line", "line {line}: {reason}"),
+    ("reason.import_empty", "empty document"),
+    ("reason.import_header", "expected \"{magic} <version>\""),
+    ("reason.import_version", "unsupported version {version}"),
+    ("reason.import_action_number", "expected action {number}"),
+    ("reason.import_field_syntax", "expected \"field: value\""),
+    ("reason.import_field_repeated", "repeated field {field}"),
+    ("reason.import_field_missing", "missing field {field}"),
+    ("reason.import_field_invalid", "{field}: {error}"),
+    ("reason.import_action_count", "{declared} actions declared, {found} found"),
+    ("reason.import_players_mismatch", "players differs from the players of initial_state"),
+    ("reason.import_player_missing", "missing player"),
+    ("reason.import_action_missing", "missing action"),
+    ("reason.import_trailing", "extra content after the action"),
+    ("reason.import_players", "players must have two players"),
+    ("reason.import_seed", "settings.seed is required with starting_player random"),
+    ("reason.import_initial_state", "initial_state is not the initial state of a match with these players and settings"),
+    ("reason.import_result", "declared result {declared} differs from the replayed {obtained}"),
+    ("ws.observing", "Observing match {match_id}"),
+    ("ws.replaying", "Replay of match {match_id}"),
+    ("ws.state_updated", "{turn}'s turn (round {turn_count})"),
+    ("board.legend", "Legend:"),
+    ("board.empty", "  {empty} empty square"),
+    ("board.player", "  {avatar} {name}"),
+    ("board.unit", "      #{id} {kind} at ({x}, {y}), {hp} HP"),
+    ("board.turn", "{player}'s turn (round {turn_count})"),
+    ("board.winner", "Game over: {player} wins"),
+    ("board.draw", "Game over: draw"),
+];
+
+/// Traduz uma chave, substituindo `{param}` pelos argumentos
+///
+/// Chaves ausentes no idioma caem para pt-BR e, por fim, para a própria chave.
+pub fn translate(lang: Lang, key: &str, args: &[(&str, &str)]) -> String {
+    let lookup = |lang: Lang| {
+        lang.catalog()
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| *v)
+    };
+    let template = lookup(lang).or_else(|| lookup(Lang::PtBr)).unwrap_or(key);
+
+    args.iter().fold(template.to_string(), |text, (name, value)| {
+        text.replace(&format!("{{{}}}", name), value)
+    })
+}
+
+/// Resolve o idioma da requisição
+///
+/// Ordem: `?lang=`, preferência do jogador (`X-Player-Id` ou `?player_id=`),
+/// `Accept-Language`.
+pub async fn resolve_lang(state: &AppState, query: Option<&str>, headers: &HeaderMap) -> Lang {
+    let query_param =
+        |name: &str| query.and_then(|q| q.split('&').find_map(|p| p.strip_prefix(name)));
+
+    if let Some(lang) = query_param("lang=").and_then(Lang::parse) {
+        return lang;
+    }
+
+    let player = headers
+        .get("x-player-id")
+        .and_then(|v| v.to_str().ok())
+        .or_else(|| query_param("player_id="));
+    if let Some(player_id) = player
+        && let Some(lang) = state.get_language(player_id).await
+    {
+        return lang;
+    }
+
+    headers
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(Lang::from_accept_language)
+        .unwrap_or_default()
+}
+
+/// Middleware que traduz respostas de erro para o idioma da requisição
+pub async fn localize_errors(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let lang = resolve_lang(&state, request.uri().query(), request.headers()).await;
+    let response = next.run(request).await;
+
+    let Some(err) = response.extensions().get::<ApiError>().cloned() else {
+        return response;
+    };
+    if lang == Lang::default() {
+        return response;
+    }
+
+    let (mut parts, _) = response.into_parts();
+    parts.headers.remove(header::CONTENT_LENGTH);
+    let body = serde_json::to_vec(&ErrorResponse::localized(&err, lang)).unwrap_or_default();
+    Response::from_parts(parts, Body::from(body))
+}
@@ -0,0 +1,287 @@
+//! Chaves de idempotência (`Idempotency-Key`)
+//!
+//! Clientes que repetem uma requisição por timeout mandam a mesma chave: a
+//! resposta original é devolvida sem executar de novo. A chave vale por IP e
+//! jogador (`X-Player-Id`) e guarda um resumo da requisição; reusá-la com outra
+//! rota ou outro corpo é recusado com 422.
+
+use axum::{
+    body::{Body, Bytes},
+    extract::{ConnectInfo, Request, State},
+    http::{HeaderMap, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::{
+    collections::HashMap,
+    hash::{DefaultHasher, Hash, Hasher},
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tracing::{info, warn};
+
+use crate::error::{ApiError, Reason};
+
+/// Header com a chave enviada pelo cliente
+pub const IDEMPOTENCY_KEY: &str = "idempotency-key";
+
+/// Header marcando respostas repetidas
+pub const IDEMPOTENCY_REPLAYED: &str = "idempotency-replayed";
+
+/// Validade padrão de uma chave
+const DEFAULT_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Maior corpo de requisição aceito com chave
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// Máximo de chaves guardadas; ao chegar nele as expiradas são descartadas e,
+/// se não bastar, as mais antigas
+const MAX_TRACKED_KEYS: usize = 10_000;
+
+/// Resposta guardada para repetir
+struct StoredResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+    /// Mantido para o middleware de idioma traduzir a resposta repetida
+    error: Option<ApiError>,
+}
+
+struct Entry {
+    fingerprint: u64,
+    expires_at: Instant,
+    /// `None` enquanto a primeira requisição ainda está em andamento
+    response: Option<Arc<StoredResponse>>,
+}
+
+/// O que fazer com uma requisição com chave
+enum Lookup {
+    /// Primeira vez: executar e guardar
+    Run,
+    Replay(Arc<StoredResponse>),
+    InFlight,
+    Mismatch,
+}
+
+/// Respostas guardadas por (jogador, chave)
+pub struct IdempotencyStore {
+    ttl: Duration,
+    entries: Mutex<HashMap<(String, String), Entry>>,
+}
+
+impl IdempotencyStore {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Lê `IDEMPOTENCY_TTL_SECS`
+    pub fn from_env() -> Self {
+        let ttl = std::env::var("IDEMPOTENCY_TTL_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .filter(|&secs| secs > 0)
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_TTL);
+        Self::new(ttl)
+    }
+
+    fn lookup(&self, scope: &(String, String), fingerprint: u64) -> Lookup {
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap();
+
+        if entries.len() >= MAX_TRACKED_KEYS {
+            entries.retain(|_, e| e.expires_at > now);
+        }
+        if entries.len() >= MAX_TRACKED_KEYS {
+            // Um décimo de uma vez, para não ordenar a cada chave nova
+            let mut oldest: Vec<_> = entries
+                .iter()
+                .map(|(scope, e)| (e.expires_at, scope.clone()))
+                .collect();
+            oldest.sort_unstable_by_key(|(expires_at, _)| *expires_at);
+            for (_, scope) in oldest.into_iter().take(MAX_TRACKED_KEYS / 10) {
+                entries.remove(&scope);
+            }
+        }
+
+        match entries.get(scope) {
+            Some(entry) if entry.expires_at > now => {
+                if entry.fingerprint != fingerprint {
+                    Lookup::Mismatch
+                } else {
+                    match &entry.response {
+                        Some(response) => Lookup::Replay(response.clone()),
+                        None => Lookup::InFlight,
+                    }
+                }
+            }
+            _ => {
+                entries.insert(
+                    scope.clone(),
+                    Entry {
+                        fingerprint,
+                        expires_at: now + self.ttl,
+                        response: None,
+                    },
+                );
+                Lookup::Run
+            }
+        }
+    }
+
+    fn complete(&self, scope: &(String, String), response: StoredResponse) {
+        if let Some(entry) = self.entries.lock().unwrap().get_mut(scope) {
+            entry.response = Some(Arc::new(response));
+        }
+    }
+
+    /// Libera a chave para uma nova tentativa
+    fn release(&self, scope: &(String, String)) {
+        self.entries.lock().unwrap().remove(scope);
+    }
+}
+
+/// Chave em andamento; liberada se a requisição não chegar ao fim
+///
+/// Quando o cliente desconecta, o hyper descarta o futuro do middleware no
+/// meio do caminho: sem isto a chave ficaria "em andamento" até expirar.
+struct InFlight {
+    store: Arc<IdempotencyStore>,
+    scope: Option<(String, String)>,
+}
+
+impl InFlight {
+    fn complete(mut self, response: StoredResponse) {
+        if let Some(scope) = self.scope.take() {
+            self.store.complete(&scope, response);
+        }
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        if let Some(scope) = self.scope.take() {
+            self.store.release(&scope);
+        }
+    }
+}
+
+/// Dono da chave: IP do cliente, separado por jogador (`X-Player-Id`)
+///
+/// O `X-Player-Id` não é autenticado: sozinho, deixaria qualquer um receber a
+/// resposta guardada de outro jogador mandando o ID e a chave dele.
+fn owner(request: &Request) -> String {
+    let ip = match request.extensions().get::<ConnectInfo<SocketAddr>>() {
+        Some(ConnectInfo(addr)) => addr.ip().to_string(),
+        None => "anonymous".to_string(),
+    };
+    match request.headers().get("x-player-id").and_then(|v| v.to_str().ok()) {
+        Some(player) => format!("ip:{} player:{}", ip, player),
+        None => format!("ip:{}", ip),
+    }
+}
+
+/// Resumo da requisição: método, rota (com query) e corpo
+fn fingerprint(request: &Request, body: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    request.method().hash(&mut hasher);
+    request.uri().hash(&mut hasher);
+    body.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Respostas que não são guardadas: a repetição pode dar outro resultado
+fn is_retryable(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+/// Middleware que aplica as chaves de idempotência
+///
+/// Sem o header, a requisição segue normalmente.
+pub async fn idempotency(
+    State(store): State<Arc<IdempotencyStore>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(key) = request.headers().get(IDEMPOTENCY_KEY) else {
+        return next.run(request).await;
+    };
+    let key = match key.to_str() {
+        Ok(key) if !key.is_empty() && key.len() <= 255 => key.to_string(),
+        _ => {
+            return ApiError::InvalidRequest {
+                reason: Reason::new("reason.idempotency_key"),
+            }
+            .into_response();
+        }
+    };
+
+    let scope = (owner(&request), key);
+    let (parts, body) = request.into_parts();
+    let body = match axum::body::to_bytes(body, MAX_BODY_BYTES).await {
+        Ok(body) => body,
+        Err(e) => {
+            return ApiError::InvalidRequest {
+                reason: e.to_string().into(),
+            }
+            .into_response();
+        }
+    };
+    let request = Request::from_parts(parts, Body::from(body.clone()));
+
+    match store.lookup(&scope, fingerprint(&request, &body)) {
+        Lookup::Run => {}
+        Lookup::Replay(stored) => {
+            info!("🔁 Repetindo resposta da chave {} ({})", scope.1, scope.0);
+            return replay(&stored);
+        }
+        Lookup::InFlight => {
+            return ApiError::Conflict {
+                reason: Reason::new("reason.idempotency_in_flight").arg("key", &scope.1),
+            }
+            .into_response();
+        }
+        Lookup::Mismatch => {
+            warn!("❌ Chave {} reutilizada com outra requisição ({})", scope.1, scope.0);
+            return ApiError::IdempotencyKeyReused { key: scope.1 }.into_response();
+        }
+    }
+
+    let in_flight = InFlight {
+        store,
+        scope: Some(scope),
+    };
+    let response = next.run(request).await;
+    if is_retryable(response.status()) {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let Ok(body) = axum::body::to_bytes(body, usize::MAX).await else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+    in_flight.complete(StoredResponse {
+        status: parts.status,
+        headers: parts.headers.clone(),
+        body: body.clone(),
+        error: parts.extensions.get::<ApiError>().cloned(),
+    });
+    Response::from_parts(parts, Body::from(body))
+}
+
+fn replay(stored: &StoredResponse) -> Response {
+    let mut response = Response::new(Body::from(stored.body.clone()));
+    *response.status_mut() = stored.status;
+    *response.headers_mut() = stored.headers.clone();
+    response
+        .headers_mut()
+        .insert(IDEMPOTENCY_REPLAYED, HeaderValue::from_static("true"));
+    if let Some(error) = &stored.error {
+        response.extensions_mut().insert(error.clone());
+    }
+    response
+}
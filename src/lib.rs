@@ -0,0 +1,35 @@
+//! Biblioteca do servidor autoritativo do jogo tático ASCII
+//!
+//! Expõe os módulos para o binário e para os testes de integração.
+
+pub mod admin;
+pub mod ai;
+pub mod arena;
+pub mod audit;
+pub mod board;
+pub mod bots;
+pub mod correspondence;
+pub mod error;
+pub mod events;
+pub mod fanout;
+pub mod fog;
+pub mod history;
+pub mod i18n;
+pub mod idempotency;
+pub mod lobby;
+pub mod notifier;
+pub mod openapi;
+pub mod players;
+pub mod rate_limit;
+pub mod routes;
+pub mod settings;
+pub mod state;
+pub mod tournament;
+pub mod webhooks;
+pub mod websocket;
+pub mod logging;
+pub mod match_actor;
+pub mod moves;
+pub mod notation;
+pub mod preview;
+pub mod replay;
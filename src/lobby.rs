@@ -0,0 +1,122 @@
+//! Lobby de partidas abertas
+//!
+//! Uma entrada do lobby é uma partida esperando o segundo jogador. Ao entrar,
+//! a partida é criada com o mesmo ID e a entrada sai do lobby. O anfitrião
+//! pode cancelar a entrada com o token recebido na criação; entradas sem
+//! ninguém por `LOBBY_TTL_MINUTES` expiram.
+
+use serde::Serialize;
+use std::net::IpAddr;
+use tatic_lib::PlayerId;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::settings::GameSettings;
+use crate::state::MatchId;
+
+/// Capacidade do canal de eventos do lobby
+pub const LOBBY_EVENTS_BUFFER: usize = 100;
+
+/// Tempo até uma entrada sem segundo jogador expirar
+pub const LOBBY_TTL_MINUTES: i64 = 30;
+
+/// Partida aguardando jogador
+#[derive(Clone)]
+pub struct LobbyEntry {
+    pub id: MatchId,
+    pub host: PlayerId,
+    /// Prova de que quem cancela é o anfitrião
+    pub host_token: String,
+    pub password: Option<String>,
+    pub settings: Option<GameSettings>,
+    /// IP de quem abriu, para o limite de partidas em andamento
+    pub origin: Option<IpAddr>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl LobbyEntry {
+    /// Cria entrada com ID no mesmo formato das partidas
+    pub fn new(
+        host: PlayerId,
+        password: Option<String>,
+        settings: Option<GameSettings>,
+        origin: Option<IpAddr>,
+    ) -> Self {
+        Self {
+            id: format!("match-{}", Uuid::new_v4()),
+            host,
+            host_token: Uuid::new_v4().simple().to_string(),
+            password: password.filter(|p| !p.is_empty()),
+            settings,
+            origin,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    /// Confere a senha informada por quem quer entrar
+    pub fn accepts_password(&self, password: Option<&str>) -> bool {
+        match &self.password {
+            Some(expected) => password == Some(expected.as_str()),
+            None => true,
+        }
+    }
+
+    /// Indica se a entrada já passou de `LOBBY_TTL_MINUTES`
+    pub fn is_expired(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        now - self.created_at >= chrono::Duration::minutes(LOBBY_TTL_MINUTES)
+    }
+
+    /// Dados públicos da entrada (sem a senha nem o token)
+    pub fn listing(&self) -> LobbyListing {
+        LobbyListing {
+            id: self.id.clone(),
+            host: self.host.clone(),
+            has_password: self.password.is_some(),
+            open_seats: 1,
+            settings: self.settings.clone(),
+            created_at: self.created_at,
+        }
+    }
+}
+
+/// Entrada do lobby como vista pelos clientes
+#[derive(Clone, Serialize, ToSchema)]
+pub struct LobbyListing {
+    pub id: MatchId,
+    #[schema(value_type = String)]
+    pub host: PlayerId,
+    pub has_password: bool,
+    pub open_seats: u32,
+    pub settings: Option<GameSettings>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Resposta de `POST /lobby/create`: o token do anfitrião só é mostrado aqui
+#[derive(Clone, Serialize, ToSchema)]
+pub struct CreatedLobby {
+    #[serde(flatten)]
+    pub lobby: LobbyListing,
+    /// Usado em `DELETE /lobby/{id}` (`Authorization: Bearer`)
+    pub host_token: String,
+}
+
+/// Evento `lobby_created` para o canal `/ws/lobby`
+pub fn created_event(listing: &LobbyListing) -> String {
+    serde_json::json!({
+        "type": "lobby_created",
+        "lobby": listing,
+    })
+    .to_string()
+}
+
+/// Evento `lobby_closed` para o canal `/ws/lobby`
+///
+/// `started` é falso quando a entrada foi cancelada ou expirou.
+pub fn closed_event(id: &str, started: bool) -> String {
+    serde_json::json!({
+        "type": "lobby_closed",
+        "id": id,
+        "started": started,
+    })
+    .to_string()
+}
@@ -17,10 +17,8 @@ use tower_http::{
 };
 use tracing::{info, Level};
 
-mod routes;
-mod state;
-mod websocket;
-mod logging;
+use server::{audit, idempotency, logging, openapi, routes, state, websocket};
+
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -32,11 +30,25 @@ async fn main() -> anyhow::Result<()> {
     // Cria estado compartilhado
     let app_state = state::AppState::new();
     
+    // Partidas por correspondência voltam antes de aceitar requisições
+    app_state.restore_correspondence_matches().await;
+    
     // Configura CORS
     let cors = CorsLayer::new()
         .allow_origin(Any)
-        .allow_methods([Method::GET, Method::POST, Method::OPTIONS])
-        .allow_headers([header::CONTENT_TYPE]);
+        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE, Method::OPTIONS])
+        .allow_headers([
+            header::CONTENT_TYPE,
+            header::ACCEPT_LANGUAGE,
+            header::AUTHORIZATION,
+            header::HeaderName::from_static("x-player-id"),
+            header::HeaderName::from_static(audit::SESSION_ID),
+            header::HeaderName::from_static(idempotency::IDEMPOTENCY_KEY),
+        ])
+        .expose_headers([
+            header::RETRY_AFTER,
+            header::HeaderName::from_static(idempotency::IDEMPOTENCY_REPLAYED),
+        ]);
     
     // Configura trace layer para logging de requests
     let trace_layer = TraceLayer::new_for_http()
@@ -48,6 +60,7 @@ async fn main() -> anyhow::Result<()> {
     let app = Router::new()
         .merge(routes::create_routes(app_state.clone()))
         .merge(websocket::websocket_routes(app_state))
+        .merge(openapi::openapi_routes())
         .layer(cors)
         .layer(trace_layer);
     
@@ -55,10 +68,12 @@ async fn main() -> anyhow::Result<()> {
     let addr = SocketAddr::from(([127, 0, 0, 1], 3000));
     info!("🎮 Servidor rodando em http://{}", addr);
     info!("📡 WebSocket disponível em ws://{}/ws", addr);
+    info!("📖 Documentação OpenAPI em http://{}/docs", addr);
     
     let listener = tokio::net::TcpListener::bind(addr).await?;
     
-    axum::serve(listener, app)
+    // ConnectInfo permite o rate limit por IP
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
         .await
         .map_err(|e| anyhow::anyhow!("Erro no servidor: {}", e))?;
     
@@ -0,0 +1,555 @@
+//! Ator por partida
+//!
+//! Cada partida vive em sua própria task e recebe comandos por um canal mpsc.
+//! Assim ações em partidas diferentes rodam em paralelo e as ações de uma
+//! mesma partida são aplicadas na ordem de chegada.
+//!
+//! Em partidas por correspondência ou com controle de tempo
+//! (`settings.time_control`) o ator também vigia o prazo do turno e passa a
+//! vez de quem não jogou a tempo.
+
+use std::net::IpAddr;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+use tatic_lib::{apply_action, Action, GameError, GameState, PlayerId};
+use tokio::sync::{broadcast, mpsc, oneshot};
+use tokio::time::{sleep_until, Instant};
+use tracing::{error, info, warn};
+
+use crate::correspondence::MatchMode;
+use crate::error::{ApiError, Reason};
+use crate::events::{MatchEvent, MatchEvents};
+use crate::fog;
+use crate::preview::ActionPreview;
+use crate::state::{is_game_over, Match, MatchId, RecordedAction};
+
+/// Capacidade da fila de comandos de cada partida
+const MAILBOX_SIZE: usize = 64;
+
+/// Comandos aceitos pelo ator da partida
+pub enum MatchCommand {
+    /// Emite os eventos de início da partida
+    Announce,
+    /// Retorna uma cópia da partida
+    GetState { reply: oneshot::Sender<Match> },
+    /// Aplica ação do jogador e notifica os observers
+    ApplyAction {
+        player_id: PlayerId,
+        action: Action,
+        reply: oneshot::Sender<Result<GameState, ApiError>>,
+    },
+    /// Aplica as ações em ordem, todas ou nenhuma, com uma só notificação
+    ApplyBatch {
+        player_id: PlayerId,
+        actions: Vec<Action>,
+        reply: oneshot::Sender<Result<GameState, ApiError>>,
+    },
+    /// Substitui o estado do jogo (só em partida em andamento, com os mesmos jogadores)
+    SetState {
+        state: GameState,
+        reply: oneshot::Sender<Result<(), ApiError>>,
+    },
+    /// Registra observer e retorna o estado no momento da inscrição
+    Subscribe {
+        reply: oneshot::Sender<(Match, broadcast::Receiver<String>)>,
+    },
+    /// Envia mensagem a todos os observers
+    Broadcast { message: String },
+    /// Número de observers conectados
+    ObserverCount { reply: oneshot::Sender<usize> },
+    /// Desconecta todos os observers; retorna quantos eram
+    KickObservers { reply: oneshot::Sender<usize> },
+    /// Avisa os observers e encerra o ator
+    Close { reply: oneshot::Sender<()> },
+}
+
+/// Referência para o ator de uma partida
+///
+/// Guarda os dados imutáveis da partida para consultas sem passar pelo ator.
+#[derive(Clone)]
+pub struct MatchHandle {
+    pub id: MatchId,
+    pub players: Vec<PlayerId>,
+    pub mode: MatchMode,
+    /// Neblina de guerra (`settings.fog`)
+    pub fog: bool,
+    /// IP de quem abriu a partida, para o limite de partidas em andamento
+    pub origin: Option<IpAddr>,
+    finished: Arc<AtomicBool>,
+    tx: mpsc::Sender<MatchCommand>,
+}
+
+impl MatchHandle {
+    /// Inicia a task da partida
+    ///
+    /// `buffer` é quantas mensagens um observer pode atrasar antes de perdê-las;
+    /// `events` recebe os eventos de ciclo de vida da partida.
+    ///
+    /// Nada é emitido aqui: quem registra o handle chama `announce` depois,
+    /// para que os assinantes dos eventos já encontrem a partida. Partidas que
+    /// já existiam (ex: gravadas em disco) não são anunciadas de novo.
+    pub fn spawn(
+        match_data: Match,
+        buffer: usize,
+        events: MatchEvents,
+    ) -> Self {
+        let (tx, rx) = mpsc::channel(MAILBOX_SIZE);
+        let finished = Arc::new(AtomicBool::new(match_data.is_finished()));
+        let handle = Self {
+            id: match_data.id.clone(),
+            players: match_data.state.players.clone(),
+            mode: match_data.mode,
+            fog: match_data.has_fog(),
+            origin: None,
+            finished: finished.clone(),
+            tx,
+        };
+
+        let actor = MatchActor {
+            data: match_data,
+            observers: broadcast::channel(buffer).0,
+            buffer,
+            events,
+            finished,
+        };
+        tokio::spawn(actor.run(rx));
+
+        handle
+    }
+
+    /// Emite `Created` e depois a vez inicial, ou `Finished` se já terminou
+    ///
+    /// Vai pela fila do ator, então sai antes dos eventos de qualquer ação.
+    pub async fn announce(&self) {
+        let _ = self.tx.send(MatchCommand::Announce).await;
+    }
+
+    /// Indica se a partida já terminou
+    pub fn is_finished(&self) -> bool {
+        self.finished.load(Ordering::Relaxed)
+    }
+
+    /// Indica se o jogador participa da partida
+    pub fn has_player(&self, player_id: &str) -> bool {
+        self.players.iter().any(|p| p == player_id)
+    }
+
+    /// Estado como `viewer` o vê (`None`: espectador); ver `fog`
+    pub fn view(&self, state: &GameState, viewer: Option<&str>) -> GameState {
+        fog::view(state, self.fog, viewer)
+    }
+
+    /// Cópia atual da partida (`None` se o ator terminou)
+    pub async fn snapshot(&self) -> Option<Match> {
+        let (reply, rx) = oneshot::channel();
+        self.tx.send(MatchCommand::GetState { reply }).await.ok()?;
+        rx.await.ok()
+    }
+
+    /// Aplica ação do jogador
+    pub async fn apply_action(
+        &self,
+        player_id: PlayerId,
+        action: Action,
+    ) -> Result<GameState, ApiError> {
+        let (reply, rx) = oneshot::channel();
+        let command = MatchCommand::ApplyAction {
+            player_id,
+            action,
+            reply,
+        };
+        let gone = || ApiError::MatchNotFound {
+            match_id: self.id.clone(),
+        };
+        self.tx.send(command).await.map_err(|_| gone())?;
+        rx.await.map_err(|_| gone())?
+    }
+
+    /// Aplica um lote de ações do jogador
+    ///
+    /// As ações rodam numa cópia do estado; se alguma falhar, a partida fica
+    /// como estava e o erro indica qual.
+    pub async fn apply_batch(
+        &self,
+        player_id: PlayerId,
+        actions: Vec<Action>,
+    ) -> Result<GameState, ApiError> {
+        let (reply, rx) = oneshot::channel();
+        let command = MatchCommand::ApplyBatch {
+            player_id,
+            actions,
+            reply,
+        };
+        let gone = || ApiError::MatchNotFound {
+            match_id: self.id.clone(),
+        };
+        self.tx.send(command).await.map_err(|_| gone())?;
+        rx.await.map_err(|_| gone())?
+    }
+
+    /// Simula a ação no estado atual sem aplicá-la nem notificar observers
+    ///
+    /// Com `fog`, a prévia compara o que o jogador vê antes e depois.
+    pub async fn preview(
+        &self,
+        player_id: &PlayerId,
+        action: Action,
+    ) -> Result<ActionPreview, ApiError> {
+        let match_data = self.snapshot().await.ok_or_else(|| ApiError::MatchNotFound {
+            match_id: self.id.clone(),
+        })?;
+        let next = simulate(&match_data.state, player_id, action)?;
+        let viewer = Some(player_id.as_str());
+        Ok(ActionPreview::new(
+            &self.view(&match_data.state, viewer),
+            self.view(&next, viewer),
+        ))
+    }
+
+    /// Substitui o estado do jogo
+    ///
+    /// Recusado se a partida já terminou (o resultado já foi contado) ou se
+    /// `players` não são os jogadores da partida.
+    pub async fn set_state(&self, state: GameState) -> Result<(), ApiError> {
+        let (reply, rx) = oneshot::channel();
+        let gone = || ApiError::MatchNotFound {
+            match_id: self.id.clone(),
+        };
+        self.tx
+            .send(MatchCommand::SetState { state, reply })
+            .await
+            .map_err(|_| gone())?;
+        rx.await.map_err(|_| gone())?
+    }
+
+    /// Registra observer, retornando o estado inicial e o receptor de updates
+    pub async fn subscribe(&self) -> Option<(Match, broadcast::Receiver<String>)> {
+        let (reply, rx) = oneshot::channel();
+        self.tx.send(MatchCommand::Subscribe { reply }).await.ok()?;
+        rx.await.ok()
+    }
+
+    /// Envia mensagem a todos os observers
+    pub async fn broadcast(&self, message: String) {
+        let _ = self.tx.send(MatchCommand::Broadcast { message }).await;
+    }
+
+    /// Número de observers conectados (0 se o ator terminou)
+    pub async fn observer_count(&self) -> usize {
+        let (reply, rx) = oneshot::channel();
+        if self.tx.send(MatchCommand::ObserverCount { reply }).await.is_err() {
+            return 0;
+        }
+        rx.await.unwrap_or(0)
+    }
+
+    /// Desconecta os observers atuais; novos podem se conectar depois
+    pub async fn kick_observers(&self) -> usize {
+        let (reply, rx) = oneshot::channel();
+        if self.tx.send(MatchCommand::KickObservers { reply }).await.is_err() {
+            return 0;
+        }
+        rx.await.unwrap_or(0)
+    }
+
+    /// Encerra a partida: observers recebem `match_closed` e são desconectados
+    pub async fn close(&self) {
+        let (reply, rx) = oneshot::channel();
+        if self.tx.send(MatchCommand::Close { reply }).await.is_ok() {
+            let _ = rx.await;
+        }
+    }
+}
+
+/// Dono do estado de uma partida
+struct MatchActor {
+    data: Match,
+    observers: broadcast::Sender<String>,
+    /// Capacidade do canal de observers, para recriá-lo
+    buffer: usize,
+    events: MatchEvents,
+    finished: Arc<AtomicBool>,
+}
+
+impl MatchActor {
+    /// Processa comandos até todos os handles serem descartados
+    async fn run(mut self, mut rx: mpsc::Receiver<MatchCommand>) {
+        loop {
+            let deadline = self.turn_deadline();
+            // O timer só dispara no próximo tick; prazo já vencido passa a vez agora
+            if deadline.is_some_and(|deadline| deadline <= Instant::now()) {
+                self.expire_turn();
+                continue;
+            }
+            tokio::select! {
+                // Prazo vencido passa a vez antes de qualquer comando atrasado
+                biased;
+                _ = sleep_until(deadline.unwrap_or_else(Instant::now)), if deadline.is_some() => {
+                    self.expire_turn();
+                }
+                command = rx.recv() => {
+                    let Some(command) = command else { break };
+                    if !self.handle(command) {
+                        break;
+                    }
+                }
+            }
+        }
+        info!("🗑️ Ator da partida {} encerrado", self.data.id);
+    }
+
+    /// Trata um comando; `false` encerra o ator
+    fn handle(&mut self, command: MatchCommand) -> bool {
+        match command {
+            MatchCommand::Announce => {
+                let _ = self.events.send(MatchEvent::Created {
+                    match_id: self.data.id.clone(),
+                    players: self.data.state.players.clone(),
+                });
+                // Uma partida importada pode já chegar terminada
+                let _ = if self.data.is_finished() {
+                    self.events.send(MatchEvent::Finished(Box::new(self.data.clone())))
+                } else {
+                    self.events.send(MatchEvent::TurnChanged {
+                        match_id: self.data.id.clone(),
+                        player: self.data.state.turn.clone(),
+                    })
+                };
+            }
+            MatchCommand::GetState { reply } => {
+                let _ = reply.send(self.data.clone());
+            }
+            MatchCommand::ApplyAction {
+                player_id,
+                action,
+                reply,
+            } => {
+                let result = self.apply(&player_id, action);
+                let applied = result.is_ok();
+                let _ = reply.send(result);
+                if applied {
+                    self.notify_state_update();
+                }
+            }
+            MatchCommand::ApplyBatch {
+                player_id,
+                actions,
+                reply,
+            } => {
+                let result = self.apply_batch(&player_id, actions);
+                let applied = result.is_ok();
+                let _ = reply.send(result);
+                if applied {
+                    self.notify_state_update();
+                }
+            }
+            MatchCommand::SetState { state, reply } => {
+                if self.data.is_finished() {
+                    let _ = reply.send(Err(ApiError::Conflict {
+                        reason: Reason::new("reason.match_finished").arg("match_id", &self.data.id),
+                    }));
+                    return true;
+                }
+                if state.players != self.data.state.players {
+                    let _ = reply.send(Err(ApiError::InvalidRequest {
+                        reason: Reason::new("reason.players_changed")
+                            .arg("players", self.data.state.players.join(", ")),
+                    }));
+                    return true;
+                }
+                // Estado definido de fora: o histórico recomeça dele
+                self.data.initial_state = state.clone();
+                self.data.actions.clear();
+                self.set_state(state);
+                let _ = reply.send(Ok(()));
+                self.notify_state_update();
+            }
+            MatchCommand::Subscribe { reply } => {
+                let _ = reply.send((self.data.clone(), self.observers.subscribe()));
+            }
+            MatchCommand::Broadcast { message } => {
+                self.broadcast(message);
+            }
+            MatchCommand::ObserverCount { reply } => {
+                let _ = reply.send(self.observers.receiver_count());
+            }
+            MatchCommand::KickObservers { reply } => {
+                let count = self.observers.receiver_count();
+                self.broadcast(serde_json::json!({ "type": "kicked" }).to_string());
+                // Sem o sender antigo, os receptores recebem `Closed` após a mensagem
+                self.observers = broadcast::channel(self.buffer).0;
+                info!("👢 {} observers desconectados da partida {}", count, self.data.id);
+                let _ = reply.send(count);
+            }
+            MatchCommand::Close { reply } => {
+                let event = serde_json::json!({
+                    "type": "match_closed",
+                    "match_id": self.data.id,
+                });
+                self.broadcast(event.to_string());
+                let _ = reply.send(());
+                return false;
+            }
+        }
+        true
+    }
+
+    fn apply(&mut self, player_id: &PlayerId, action: Action) -> Result<GameState, ApiError> {
+        let current = &self.data.state;
+
+        // Log detalhado ANTES da ação
+        info!(
+            "📊 Estado ANTES - Turno: {}, Contador: {}, Fase: {:?}",
+            current.turn, current.turn_count, current.phase
+        );
+
+        match simulate(current, player_id, action.clone()) {
+            Ok(new_state) => {
+                // Log detalhado DEPOIS da ação
+                info!(
+                    "📊 Estado DEPOIS - Turno: {}, Contador: {}, Fase: {:?}",
+                    new_state.turn, new_state.turn_count, new_state.phase
+                );
+                self.record(player_id, action, new_state.turn_count);
+                self.set_state(new_state.clone());
+                Ok(new_state)
+            }
+            Err(e) => {
+                error!("❌ Erro ao aplicar ação: {}", e);
+                Err(e)
+            }
+        }
+    }
+
+    fn apply_batch(
+        &mut self,
+        player_id: &PlayerId,
+        actions: Vec<Action>,
+    ) -> Result<GameState, ApiError> {
+        let mut working = self.data.state.clone();
+        let mut turn_counts = Vec::with_capacity(actions.len());
+        for (index, action) in actions.iter().cloned().enumerate() {
+            working = simulate(&working, player_id, action).map_err(|cause| {
+                warn!("❌ Lote recusado na ação {}: {}", index, cause);
+                ApiError::BatchFailed {
+                    index,
+                    cause: Box::new(cause),
+                }
+            })?;
+            turn_counts.push(working.turn_count);
+        }
+        for (action, turn_count) in actions.into_iter().zip(turn_counts) {
+            self.record(player_id, action, turn_count);
+        }
+        self.set_state(working.clone());
+        Ok(working)
+    }
+
+    /// Momento em que a vez atual será passada (correspondência ou controle de tempo)
+    fn turn_deadline(&self) -> Option<Instant> {
+        if self.finished.load(Ordering::Relaxed) {
+            return None;
+        }
+        let remaining = self.data.turn_deadline()? - chrono::Utc::now();
+        Some(Instant::now() + remaining.to_std().unwrap_or_default())
+    }
+
+    /// Prazo esgotado: passa a vez do jogador atual
+    fn expire_turn(&mut self) {
+        let player = self.data.state.turn.clone();
+        warn!("⏰ Prazo do turno de {} esgotado na partida {}", player, self.data.id);
+        let event = serde_json::json!({
+            "type": "turn_expired",
+            "match_id": self.data.id,
+            "player_id": player,
+        });
+        self.broadcast(event.to_string());
+
+        if self.apply(&player, Action::EndTurn).is_ok() {
+            self.notify_state_update();
+        }
+        if self.data.state.turn == player {
+            // A vez não passou: conta o prazo de novo para não repetir em seguida
+            self.data.turn_started_at = chrono::Utc::now();
+        }
+    }
+
+    fn record(&mut self, player_id: &PlayerId, action: Action, turn_count: u32) {
+        let _ = self.events.send(MatchEvent::ActionApplied {
+            match_id: self.data.id.clone(),
+            player: player_id.clone(),
+            action: action.clone(),
+            turn_count,
+        });
+        self.data.actions.push(RecordedAction {
+            player_id: player_id.clone(),
+            action,
+        });
+    }
+
+    fn set_state(&mut self, state: GameState) {
+        let finished = is_game_over(&state);
+        let was_finished = self.finished.swap(finished, Ordering::Relaxed);
+        let turn_changed = state.turn != self.data.state.turn;
+        self.data.state = state;
+        self.data.updated_at = chrono::Utc::now();
+        if turn_changed {
+            self.data.turn_started_at = self.data.updated_at;
+        }
+
+        if finished && !was_finished {
+            info!("🏁 Partida {} terminou", self.data.id);
+            let _ = self.events.send(MatchEvent::Finished(Box::new(self.data.clone())));
+        } else if turn_changed && !finished {
+            let _ = self.events.send(MatchEvent::TurnChanged {
+                match_id: self.data.id.clone(),
+                player: self.data.state.turn.clone(),
+            });
+        }
+    }
+
+    fn notify_state_update(&self) {
+        let notification = serde_json::json!({
+            "type": "state_update",
+            "match_id": self.data.id,
+            "state": &self.data.state,
+        });
+        self.broadcast(notification.to_string());
+    }
+
+    /// Publica sem esperar: observers lentos perdem as mensagens mais antigas
+    fn broadcast(&self, message: String) {
+        // Erro só significa que não há observers conectados
+        let _ = self.observers.send(message);
+    }
+}
+
+/// Aplica a ação numa cópia do estado, conferindo o turno
+///
+/// Não altera a partida: o ator usa o resultado para atualizar o estado e as
+/// prévias (`dry_run`) só o devolvem.
+pub fn simulate(
+    current: &GameState,
+    player_id: &PlayerId,
+    action: Action,
+) -> Result<GameState, ApiError> {
+    if &current.turn != player_id {
+        warn!("❌ Não é o turno de {}", player_id);
+        return Err(ApiError::NotYourTurn {
+            player_id: player_id.clone(),
+            current: current.turn.clone(),
+        });
+    }
+
+    apply_action(current, player_id, action).map_err(|e| match e {
+        GameError::NotYourTurn => ApiError::NotYourTurn {
+            player_id: player_id.clone(),
+            current: current.turn.clone(),
+        },
+        other => ApiError::InvalidAction {
+            reason: other.to_string().into(),
+        },
+    })
+}
@@ -0,0 +1,122 @@
+//! Enumeração de ações legais
+//!
+//! `tatic_lib` não tem um gerador de jogadas, então as candidatas são montadas
+//! a partir das unidades do jogador e do tamanho do tabuleiro e testadas com
+//! `apply_action`: só as aceitas são legais.
+
+use serde::Serialize;
+use tatic_lib::{apply_action, Action, Coord, GameState, PlayerId};
+use utoipa::ToSchema;
+
+/// Ação legal e o estado resultante
+#[derive(Debug, Clone)]
+pub struct LegalAction {
+    /// Unidade que age (`None` para `EndTurn`)
+    pub unit_id: Option<u32>,
+    pub action: Action,
+    pub next: GameState,
+}
+
+/// Todas as ações legais do jogador no estado atual
+///
+/// Vazia se não for a vez dele.
+pub fn legal_actions(state: &GameState, player: &PlayerId) -> Vec<LegalAction> {
+    if &state.turn != player {
+        return Vec::new();
+    }
+
+    let mut candidates = Vec::new();
+    for unit in state.units.iter().filter(|u| &u.owner == player) {
+        for x in 0..state.width {
+            for y in 0..state.height {
+                let to = Coord { x, y };
+                if to != unit.pos {
+                    candidates.push((Some(unit.id), Action::Move { unit_id: unit.id, to }));
+                }
+            }
+        }
+        for enemy in state.units.iter().filter(|u| &u.owner != player) {
+            candidates.push((
+                Some(unit.id),
+                Action::Attack {
+                    unit_id: unit.id,
+                    target: enemy.pos,
+                },
+            ));
+        }
+    }
+    candidates.push((None, Action::EndTurn));
+
+    candidates
+        .into_iter()
+        .filter_map(|(unit_id, action)| {
+            let next = apply_action(state, player, action.clone()).ok()?;
+            Some(LegalAction {
+                unit_id,
+                action,
+                next,
+            })
+        })
+        .collect()
+}
+
+/// Nome do tipo da ação (a tag `type` do JSON)
+pub fn action_kind(action: &Action) -> String {
+    serde_json::to_value(action)
+        .ok()
+        .and_then(|v| v["type"].as_str().map(str::to_string))
+        .unwrap_or_else(|| "Unknown".to_string())
+}
+
+/// Ações legais de uma unidade
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct UnitActions {
+    pub unit_id: u32,
+    #[schema(value_type = Vec<crate::openapi::ActionSchema>)]
+    pub moves: Vec<Action>,
+    #[schema(value_type = Vec<crate::openapi::ActionSchema>)]
+    pub attacks: Vec<Action>,
+    /// Ações de outros tipos
+    #[schema(value_type = Vec<crate::openapi::ActionSchema>)]
+    pub other: Vec<Action>,
+}
+
+/// Ações legais agrupadas por unidade
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct LegalActions {
+    /// `false` quando não é a vez do jogador (listas vazias)
+    pub your_turn: bool,
+    pub can_end_turn: bool,
+    pub units: Vec<UnitActions>,
+}
+
+/// Agrupa as ações legais do jogador por unidade, na ordem das unidades
+pub fn grouped_legal_actions(state: &GameState, player: &PlayerId) -> LegalActions {
+    let mut grouped = LegalActions {
+        your_turn: &state.turn == player,
+        ..Default::default()
+    };
+    for legal in legal_actions(state, player) {
+        let Some(unit_id) = legal.unit_id else {
+            grouped.can_end_turn = true;
+            continue;
+        };
+        let index = match grouped.units.iter().position(|u| u.unit_id == unit_id) {
+            Some(index) => index,
+            None => {
+                grouped.units.push(UnitActions {
+                    unit_id,
+                    ..Default::default()
+                });
+                grouped.units.len() - 1
+            }
+        };
+        let unit = &mut grouped.units[index];
+        match action_kind(&legal.action).as_str() {
+            "Move" => unit.moves.push(legal.action),
+            "Attack" => unit.attacks.push(legal.action),
+            _ => unit.other.push(legal.action),
+        }
+    }
+    grouped
+}
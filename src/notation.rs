@@ -0,0 +1,279 @@
+//! Notação de partidas para exportar e importar
+//!
+//! Documento de texto, uma informação por linha:
+//!
+//! ```text
+//! tatic-match 1
+//! id: "match-..."
+//! players: ["ana","bia"]
+//! created_at: "2026-01-01T12:00:00Z"
+//! result: {"winner":"ana"}
+//! settings: null
+//! initial_state: {"players":["ana","bia"],"turn":"ana",...}
+//! actions: 2
+//! 1. "ana" {"type":"EndTurn"}
+//! 2. "bia" {"type":"EndTurn"}
+//! ```
+//!
+//! Os valores são JSON numa linha só, então IDs com espaços ou estados
+//! grandes não quebram o formato. `result` é `"ongoing"`, `"draw"` ou
+//! `{"winner": ...}`. Linhas em branco e começadas por `#` são ignoradas.
+//! A primeira linha traz a versão; versões desconhecidas são recusadas.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use tatic_lib::{Action, GameState, PlayerId};
+
+use crate::error::{ApiError, Reason};
+use crate::match_actor::simulate;
+use crate::settings::{GameSettings, StartingPlayer};
+use crate::state::{is_game_over, winner, Match, MatchId};
+
+/// Versão atual da notação
+pub const VERSION: u32 = 1;
+
+/// Cabeçalho da primeira linha
+const MAGIC: &str = "tatic-match";
+
+/// Resultado da partida
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchResult {
+    Ongoing,
+    Draw,
+    Winner(PlayerId),
+}
+
+impl MatchResult {
+    pub fn of(state: &GameState) -> Self {
+        if !is_game_over(state) {
+            return Self::Ongoing;
+        }
+        winner(state).map_or(Self::Draw, Self::Winner)
+    }
+}
+
+/// Partida completa em forma portável
+#[derive(Debug, Clone)]
+pub struct MatchDocument {
+    pub id: MatchId,
+    pub players: Vec<PlayerId>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub result: MatchResult,
+    pub settings: Option<GameSettings>,
+    pub initial_state: GameState,
+    pub actions: Vec<(PlayerId, Action)>,
+}
+
+impl MatchDocument {
+    pub fn from_match(match_data: &Match) -> Self {
+        Self {
+            id: match_data.id.clone(),
+            players: match_data.initial_state.players.clone(),
+            created_at: match_data.created_at,
+            result: MatchResult::of(&match_data.state),
+            settings: match_data.settings.clone(),
+            initial_state: match_data.initial_state.clone(),
+            actions: match_data
+                .actions
+                .iter()
+                .map(|r| (r.player_id.clone(), r.action.clone()))
+                .collect(),
+        }
+    }
+
+    /// Escreve o documento na notação
+    pub fn to_text(&self) -> String {
+        let mut text = format!("{} {}\n", MAGIC, VERSION);
+        text += &format!("id: {}\n", line(&self.id));
+        text += &format!("players: {}\n", line(&self.players));
+        text += &format!("created_at: {}\n", line(&self.created_at));
+        text += &format!("result: {}\n", line(&self.result));
+        text += &format!("settings: {}\n", line(&self.settings));
+        text += &format!("initial_state: {}\n", line(&self.initial_state));
+        text += &format!("actions: {}\n", self.actions.len());
+        for (i, (player, action)) in self.actions.iter().enumerate() {
+            text += &format!("{}. {} {}\n", i + 1, line(player), line(action));
+        }
+        text
+    }
+
+    /// Lê um documento, validando o formato (não as jogadas)
+    pub fn parse(text: &str) -> Result<Self, ApiError> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+
+        let (n, first) = lines.next().ok_or_else(|| invalid(1, Reason::new("reason.import_empty")))?;
+        let version = first
+            .strip_prefix(MAGIC)
+            .and_then(|v| v.trim().parse::<u32>().ok())
+            .ok_or_else(|| invalid(n, Reason::new("reason.import_header").arg("magic", MAGIC)))?;
+        if version != VERSION {
+            return Err(invalid(n, Reason::new("reason.import_version").arg("version", version)));
+        }
+
+        let mut fields = Fields::new();
+        let mut actions = Vec::new();
+        for (n, line) in lines {
+            if let Some((number, rest)) = line.split_once(". ")
+                && let Ok(number) = number.parse::<usize>()
+            {
+                if number != actions.len() + 1 {
+                    return Err(invalid(
+                        n,
+                        Reason::new("reason.import_action_number").arg("number", actions.len() + 1),
+                    ));
+                }
+                actions.push(parse_action(n, rest)?);
+                continue;
+            }
+            let (key, value) = line
+                .split_once(':')
+                .ok_or_else(|| invalid(n, Reason::new("reason.import_field_syntax")))?;
+            if fields.insert(key.trim(), (n, value.trim())).is_some() {
+                return Err(invalid(
+                    n,
+                    Reason::new("reason.import_field_repeated").arg("field", key.trim()),
+                ));
+            }
+        }
+
+        let (n, count) = field(&fields, "actions")?;
+        if count.parse::<usize>().ok() != Some(actions.len()) {
+            return Err(invalid(
+                n,
+                Reason::new("reason.import_action_count")
+                    .arg("declared", count)
+                    .arg("found", actions.len()),
+            ));
+        }
+
+        let document = Self {
+            id: decode(&fields, "id")?,
+            players: decode(&fields, "players")?,
+            created_at: decode(&fields, "created_at")?,
+            result: decode(&fields, "result")?,
+            settings: decode(&fields, "settings")?,
+            initial_state: decode(&fields, "initial_state")?,
+            actions,
+        };
+        if document.players != document.initial_state.players {
+            return Err(invalid(
+                field(&fields, "players")?.0,
+                Reason::new("reason.import_players_mismatch"),
+            ));
+        }
+        Ok(document)
+    }
+
+    /// Reaplica as ações a partir do estado inicial
+    ///
+    /// O estado inicial precisa ser o que o servidor criaria para os jogadores
+    /// e configurações do documento. Para na primeira ação ilegal. O resultado
+    /// obtido precisa ser o declarado.
+    pub fn replay(&self) -> Result<GameState, ApiError> {
+        self.check_initial_state()?;
+        let mut state = self.initial_state.clone();
+        for (i, (player_id, action)) in self.actions.iter().enumerate() {
+            state = simulate(&state, player_id, action.clone()).map_err(|cause| {
+                ApiError::ImportFailed {
+                    number: i + 1,
+                    player_id: player_id.clone(),
+                    cause: Box::new(cause),
+                }
+            })?;
+        }
+        let result = MatchResult::of(&state);
+        if result != self.result {
+            return Err(ApiError::InvalidRequest {
+                reason: Reason::new("reason.import_result")
+                    .arg("declared", format!("{:?}", self.result))
+                    .arg("obtained", format!("{:?}", result)),
+            });
+        }
+        Ok(state)
+    }
+
+    /// Recusa posições que uma partida nova não teria
+    fn check_initial_state(&self) -> Result<(), ApiError> {
+        let invalid = |key| ApiError::InvalidRequest {
+            reason: Reason::new(key),
+        };
+        let [player1, player2] = self.players.as_slice() else {
+            return Err(invalid("reason.import_players"));
+        };
+        let expected = match &self.settings {
+            Some(settings) => {
+                if settings.seed.is_none() && settings.starting_player == StartingPlayer::Random {
+                    return Err(invalid("reason.import_seed"));
+                }
+                settings.clone().initial_state(player1, player2)?
+            }
+            None => GameState::new(player1.clone(), player2.clone()),
+        };
+        if serde_json::to_value(&expected).ok() != serde_json::to_value(&self.initial_state).ok() {
+            return Err(invalid("reason.import_initial_state"));
+        }
+        Ok(())
+    }
+}
+
+/// Valor em JSON numa linha
+fn line<T: Serialize>(value: &T) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| "null".to_string())
+}
+
+type Fields<'a> = HashMap<&'a str, (usize, &'a str)>;
+
+/// Linha e valor bruto de um campo obrigatório
+fn field<'a>(fields: &Fields<'a>, key: &str) -> Result<(usize, &'a str), ApiError> {
+    fields
+        .get(key)
+        .copied()
+        .ok_or_else(|| invalid(0, Reason::new("reason.import_field_missing").arg("field", key)))
+}
+
+fn decode<T: serde::de::DeserializeOwned>(fields: &Fields, key: &str) -> Result<T, ApiError> {
+    let (n, raw) = field(fields, key)?;
+    serde_json::from_str(raw).map_err(|e| {
+        invalid(
+            n,
+            Reason::new("reason.import_field_invalid")
+                .arg("field", key)
+                .arg("error", e),
+        )
+    })
+}
+
+/// `"jogador" {ação}`
+fn parse_action(n: usize, rest: &str) -> Result<(PlayerId, Action), ApiError> {
+    let mut values = serde_json::Deserializer::from_str(rest).into_iter::<Value>();
+    let mut next = |missing| match values.next() {
+        Some(Ok(value)) => Ok(value),
+        Some(Err(e)) => Err(invalid(n, e.to_string().into())),
+        None => Err(invalid(n, Reason::new(missing))),
+    };
+    let player = next("reason.import_player_missing")?;
+    let action = next("reason.import_action_missing")?;
+    if values.next().is_some() {
+        return Err(invalid(n, Reason::new("reason.import_trailing")));
+    }
+    Ok((
+        serde_json::from_value(player).map_err(|e| invalid(n, e.to_string().into()))?,
+        serde_json::from_value(action).map_err(|e| invalid(n, e.to_string().into()))?,
+    ))
+}
+
+/// Erro de formato na linha `n` (0 quando não há linha)
+fn invalid(n: usize, reason: Reason) -> ApiError {
+    let reason = if n == 0 {
+        reason
+    } else {
+        Reason::new("reason.import_line").arg("line", n).with("reason", reason)
+    };
+    ApiError::InvalidRequest { reason }
+}
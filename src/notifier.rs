@@ -0,0 +1,133 @@
+//! Avisos de "sua vez" nas partidas por correspondência
+//!
+//! Cada canal implementa `Notifier`. Os canais ativos vêm de `NOTIFIERS`
+//! (separados por vírgula, padrão `webhook`); outros podem ser adicionados
+//! com `Notifiers::add`.
+
+use serde::Serialize;
+use std::sync::{Arc, RwLock};
+use tatic_lib::PlayerId;
+use tracing::{info, warn};
+use utoipa::ToSchema;
+
+use crate::state::MatchId;
+use crate::webhooks::{WebhookEvent, Webhooks};
+
+/// Aviso de que a vez passou para o jogador
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TurnNotification {
+    #[schema(value_type = String)]
+    pub player_id: PlayerId,
+    pub match_id: MatchId,
+    #[schema(value_type = Vec<String>)]
+    pub opponents: Vec<PlayerId>,
+    pub turn_count: u32,
+    /// Quando a vez será passada se o jogador não jogar
+    pub deadline: chrono::DateTime<chrono::Utc>,
+}
+
+/// Canal de avisos
+pub trait Notifier: Send + Sync {
+    /// Nome do canal, para os logs
+    fn name(&self) -> &str;
+
+    /// Envia o aviso; não deve bloquear (envios lentos vão para outra task)
+    fn notify(&self, notification: &TurnNotification);
+}
+
+/// Evento `your_turn` para os webhooks cadastrados
+pub struct WebhookNotifier {
+    webhooks: Arc<Webhooks>,
+}
+
+impl WebhookNotifier {
+    pub fn new(webhooks: Arc<Webhooks>) -> Self {
+        Self { webhooks }
+    }
+}
+
+impl Notifier for WebhookNotifier {
+    fn name(&self) -> &str {
+        "webhook"
+    }
+
+    fn notify(&self, notification: &TurnNotification) {
+        let data = serde_json::to_value(notification).unwrap_or_default();
+        self.webhooks.dispatch(WebhookEvent::YourTurn, None, data);
+    }
+}
+
+/// E-mail de mentira: só escreve no log o que seria enviado
+///
+/// O endereço é `<jogador>@<domínio>` (`NOTIFY_EMAIL_DOMAIN`, padrão
+/// `example.com`) até existir cadastro de e-mails.
+pub struct EmailNotifier {
+    domain: String,
+}
+
+impl EmailNotifier {
+    pub fn new(domain: String) -> Self {
+        Self { domain }
+    }
+
+    pub fn from_env() -> Self {
+        let domain = std::env::var("NOTIFY_EMAIL_DOMAIN")
+            .ok()
+            .filter(|d| !d.is_empty())
+            .unwrap_or_else(|| "example.com".to_string());
+        Self::new(domain)
+    }
+}
+
+impl Notifier for EmailNotifier {
+    fn name(&self) -> &str {
+        "email"
+    }
+
+    fn notify(&self, notification: &TurnNotification) {
+        info!(
+            "📧 Para: {}@{} | Assunto: sua vez na partida {} | Prazo: {}",
+            notification.player_id, self.domain, notification.match_id, notification.deadline
+        );
+    }
+}
+
+/// Canais de aviso ativos
+#[derive(Default)]
+pub struct Notifiers {
+    notifiers: RwLock<Vec<Arc<dyn Notifier>>>,
+}
+
+impl Notifiers {
+    /// Lê `NOTIFIERS` (`webhook`, `email`); nomes desconhecidos são ignorados
+    pub fn from_env(webhooks: &Arc<Webhooks>) -> Self {
+        let names = std::env::var("NOTIFIERS").unwrap_or_else(|_| "webhook".to_string());
+        let notifiers = Self::default();
+        for name in names.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            match name {
+                "webhook" => notifiers.add(Arc::new(WebhookNotifier::new(webhooks.clone()))),
+                "email" => notifiers.add(Arc::new(EmailNotifier::from_env())),
+                other => warn!("⚠️ Canal de aviso desconhecido: {}", other),
+            }
+        }
+        notifiers
+    }
+
+    /// Ativa mais um canal
+    pub fn add(&self, notifier: Arc<dyn Notifier>) {
+        self.notifiers.write().unwrap().push(notifier);
+    }
+
+    /// Envia o aviso por todos os canais
+    pub fn notify(&self, notification: &TurnNotification) {
+        for notifier in self.notifiers.read().unwrap().iter() {
+            info!(
+                "🔔 Avisando {} pela partida {} ({})",
+                notification.player_id,
+                notification.match_id,
+                notifier.name()
+            );
+            notifier.notify(notification);
+        }
+    }
+}
@@ -0,0 +1,212 @@
+//! Documentação OpenAPI 3 gerada a partir dos handlers REST
+//!
+//! Os tipos de `tatic_lib` (`GameState`, `Action`) não implementam `ToSchema`,
+//! então são descritos aqui por tipos marcadores com schema manual.
+
+use std::borrow::Cow;
+
+use axum::Router;
+use utoipa::{
+    openapi::{
+        security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+        ObjectBuilder, RefOr, Schema, Type,
+    },
+    Modify, OpenApi, PartialSchema, ToSchema,
+};
+use utoipa_swagger_ui::SwaggerUi;
+
+use crate::{
+    admin, ai, arena, audit, bots, correspondence, error, fanout, history, i18n, lobby, moves,
+    notifier, players, preview, routes, settings, tournament, webhooks,
+};
+
+/// Schema de `tatic_lib::GameState`
+pub struct GameStateSchema;
+
+impl PartialSchema for GameStateSchema {
+    fn schema() -> RefOr<Schema> {
+        ObjectBuilder::new()
+            .schema_type(Type::Object)
+            .description(Some("Estado completo da partida (tatic_lib::GameState)"))
+            .property("players", ObjectBuilder::new().schema_type(Type::Array))
+            .property("turn", ObjectBuilder::new().schema_type(Type::String))
+            .property("turn_count", ObjectBuilder::new().schema_type(Type::Integer))
+            .property("phase", ObjectBuilder::new())
+            .required("turn")
+            .required("turn_count")
+            .required("phase")
+            .into()
+    }
+}
+
+impl ToSchema for GameStateSchema {
+    fn name() -> Cow<'static, str> {
+        Cow::Borrowed("GameState")
+    }
+}
+
+/// Schema de `tatic_lib::Action`
+pub struct ActionSchema;
+
+impl PartialSchema for ActionSchema {
+    fn schema() -> RefOr<Schema> {
+        ObjectBuilder::new()
+            .schema_type(Type::Object)
+            .description(Some("Ação do jogador (tatic_lib::Action), ex: {\"type\": \"EndTurn\"}"))
+            .property("type", ObjectBuilder::new().schema_type(Type::String))
+            .required("type")
+            .into()
+    }
+}
+
+impl ToSchema for ActionSchema {
+    fn name() -> Cow<'static, str> {
+        Cow::Borrowed("Action")
+    }
+}
+
+/// Documento OpenAPI do servidor
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "RPG ASCII Tático - Servidor", version = "0.1.0"),
+    paths(
+        routes::root_handler,
+        routes::get_state_handler,
+        routes::legal_actions_handler,
+        routes::export_match_handler,
+        routes::board_handler,
+        routes::import_match_handler,
+        routes::post_action_handler,
+        routes::post_actions_handler,
+        routes::list_matches_handler,
+        routes::metrics_handler,
+        routes::create_match_handler,
+        routes::ai_action_handler,
+        admin::set_language_handler,
+        routes::my_turn_handler,
+        routes::player_matches_handler,
+        routes::get_player_handler,
+        admin::update_player_handler,
+        routes::create_lobby_handler,
+        routes::list_lobby_handler,
+        routes::cancel_lobby_handler,
+        routes::join_lobby_handler,
+        routes::create_tournament_handler,
+        routes::list_tournaments_handler,
+        routes::get_tournament_handler,
+        routes::tournament_standings_handler,
+        admin::forfeit_tournament_handler,
+        admin::arena_handler,
+        admin::register_bot_handler,
+        routes::list_bots_handler,
+        admin::list_matches_handler,
+        admin::set_state_handler,
+        admin::end_turn_handler,
+        admin::observers_handler,
+        admin::kick_observers_handler,
+        admin::close_match_handler,
+        admin::notice_handler,
+        admin::audit_handler,
+        admin::list_webhooks_handler,
+        admin::create_webhook_handler,
+        admin::delete_webhook_handler,
+        admin::webhook_deliveries_handler,
+        admin::dead_letters_handler,
+        admin::retry_dead_letter_handler,
+    ),
+    components(schemas(
+        routes::ActionRequest,
+        routes::BatchActionRequest,
+        routes::CreateMatchRequest,
+        correspondence::MatchMode,
+        settings::GameSettings,
+        settings::BoardSize,
+        settings::Roster,
+        settings::StartingPlayer,
+        settings::TimeControl,
+        correspondence::PendingTurn,
+        notifier::TurnNotification,
+        players::Player,
+        players::PlayerStats,
+        players::ProfileUpdate,
+        history::HistoryStatus,
+        history::PlayerOutcome,
+        history::MatchLinks,
+        history::MatchHistoryItem,
+        history::MatchHistoryPage,
+        routes::AiActionRequest,
+        ai::AiStrategy,
+        ai::AiDecision,
+        ai::ScoredAction,
+        ai::Effects,
+        moves::LegalActions,
+        moves::UnitActions,
+        preview::ActionPreview,
+        preview::StateChange,
+        admin::LanguageRequest,
+        routes::CreateLobbyRequest,
+        routes::JoinLobbyRequest,
+        lobby::LobbyListing,
+        lobby::CreatedLobby,
+        routes::CreateTournamentRequest,
+        admin::ForfeitRequest,
+        tournament::Tournament,
+        tournament::TournamentFormat,
+        tournament::TournamentStatus,
+        tournament::Pairing,
+        tournament::PairingResult,
+        tournament::Standing,
+        arena::ArenaConfig,
+        arena::ArenaReport,
+        arena::ArenaMatch,
+        arena::ArenaOutcome,
+        admin::RegisterBotRequest,
+        bots::Bot,
+        bots::BotCredentials,
+        admin::AdminMatch,
+        admin::NoticeRequest,
+        admin::AuditFormat,
+        audit::AuditEntry,
+        admin::CreateWebhookRequest,
+        webhooks::Webhook,
+        webhooks::WebhookCredentials,
+        webhooks::WebhookEvent,
+        webhooks::DeliveryAttempt,
+        webhooks::DeadLetter,
+        audit::AuditRecord,
+        audit::Who,
+        audit::StateStamp,
+        audit::Outcome,
+        i18n::Lang,
+        error::ErrorResponse,
+        error::ErrorBody,
+        fanout::FanoutMetricsSnapshot,
+        GameStateSchema,
+        ActionSchema,
+    )),
+    modifiers(&BearerSecurity)
+)]
+pub struct ApiDoc;
+
+/// Esquemas Bearer: `admin_token` (rotas `/admin`) e `host_token` (`DELETE /lobby/{id}`)
+struct BearerSecurity;
+
+impl Modify for BearerSecurity {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            for name in ["admin_token", "host_token"] {
+                components.add_security_scheme(
+                    name,
+                    SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+                );
+            }
+        }
+    }
+}
+
+/// Cria rotas de documentação (`/openapi.json` e UI em `/docs`)
+pub fn openapi_routes() -> Router {
+    SwaggerUi::new("/docs")
+        .url("/openapi.json", ApiDoc::openapi())
+        .into()
+}
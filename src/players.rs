@@ -0,0 +1,195 @@
+//! Perfis e estatísticas de jogadores
+//!
+//! O perfil é criado na primeira partida do jogador (ou ao ser editado em
+//! `POST /admin/players/{id}`). As estatísticas são acumuladas quando uma partida
+//! termina; a unidade favorita é a que o jogador mais usou nas ações.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tatic_lib::{Action, PlayerId};
+use utoipa::ToSchema;
+
+use crate::error::{ApiError, Reason};
+use crate::state::{winner, Match};
+
+/// Tamanho máximo do nome de exibição
+const MAX_DISPLAY_NAME: usize = 32;
+
+/// Perfil público do jogador
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Player {
+    #[schema(value_type = String)]
+    pub id: PlayerId,
+    pub display_name: String,
+    /// Caractere ASCII que representa o jogador no tabuleiro
+    #[schema(value_type = String)]
+    pub avatar: char,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub stats: PlayerStats,
+}
+
+/// Estatísticas das partidas terminadas
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct PlayerStats {
+    pub games_played: u32,
+    pub won: u32,
+    pub lost: u32,
+    pub drawn: u32,
+    /// Média de `turn_count` no fim das partidas
+    pub average_turns: f64,
+    /// Média do tempo entre criação e fim das partidas
+    pub average_duration_secs: f64,
+    /// Unidade com mais ações (tipo da unidade, ou `#<id>` se o estado não traz o tipo)
+    pub favorite_unit: Option<String>,
+}
+
+/// Alterações de perfil (`POST /admin/players/{id}`)
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ProfileUpdate {
+    pub display_name: Option<String>,
+    #[schema(value_type = Option<String>)]
+    pub avatar: Option<char>,
+}
+
+/// Perfil e os totais de onde saem as médias
+#[derive(Debug, Clone)]
+struct PlayerRecord {
+    player: Player,
+    total_turns: u64,
+    total_duration_secs: i64,
+    unit_uses: HashMap<String, u32>,
+}
+
+impl PlayerRecord {
+    fn new(id: &str) -> Self {
+        Self {
+            player: Player {
+                id: id.to_string(),
+                display_name: id.to_string(),
+                avatar: default_avatar(id),
+                created_at: chrono::Utc::now(),
+                stats: PlayerStats::default(),
+            },
+            total_turns: 0,
+            total_duration_secs: 0,
+            unit_uses: HashMap::new(),
+        }
+    }
+}
+
+/// Primeira letra do ID em maiúscula, ou `@`
+pub(crate) fn default_avatar(id: &str) -> char {
+    id.chars()
+        .find(char::is_ascii_alphanumeric)
+        .map_or('@', |c| c.to_ascii_uppercase())
+}
+
+/// Jogadores conhecidos pelo servidor
+#[derive(Default)]
+pub struct PlayerRegistry {
+    players: HashMap<PlayerId, PlayerRecord>,
+}
+
+impl PlayerRegistry {
+    /// Cria o perfil se o jogador ainda não tem um
+    pub fn ensure(&mut self, id: &str) {
+        if !self.players.contains_key(id) {
+            self.players.insert(id.to_string(), PlayerRecord::new(id));
+        }
+    }
+
+    pub fn get(&self, id: &str) -> Result<Player, ApiError> {
+        self.players
+            .get(id)
+            .map(|r| r.player.clone())
+            .ok_or_else(|| ApiError::PlayerNotFound {
+                player_id: id.to_string(),
+            })
+    }
+
+    /// Cria ou altera o perfil
+    pub fn update(&mut self, id: &str, update: ProfileUpdate) -> Result<Player, ApiError> {
+        let display_name = update
+            .display_name
+            .map(|name| name.trim().to_string())
+            .map(|name| {
+                if name.is_empty() || name.chars().count() > MAX_DISPLAY_NAME {
+                    Err(ApiError::InvalidRequest {
+                        reason: Reason::new("reason.display_name").arg("max", MAX_DISPLAY_NAME),
+                    })
+                } else {
+                    Ok(name)
+                }
+            })
+            .transpose()?;
+        if let Some(avatar) = update.avatar
+            && !avatar.is_ascii_graphic()
+        {
+            return Err(ApiError::InvalidRequest {
+                reason: Reason::new("reason.avatar"),
+            });
+        }
+
+        self.ensure(id);
+        let player = &mut self.players.get_mut(id).expect("criado acima").player;
+        if let Some(name) = display_name {
+            player.display_name = name;
+        }
+        if let Some(avatar) = update.avatar {
+            player.avatar = avatar;
+        }
+        Ok(player.clone())
+    }
+
+    /// Soma uma partida terminada às estatísticas dos jogadores dela
+    pub fn record_finished(&mut self, match_data: &Match) {
+        let winner = winner(&match_data.state);
+        let duration = (match_data.updated_at - match_data.created_at).num_seconds();
+        let units = unit_names(match_data);
+
+        for id in &match_data.state.players {
+            self.ensure(id);
+            let record = self.players.get_mut(id).expect("criado acima");
+            let stats = &mut record.player.stats;
+            stats.games_played += 1;
+            match &winner {
+                None => stats.drawn += 1,
+                Some(w) if w == id => stats.won += 1,
+                Some(_) => stats.lost += 1,
+            }
+
+            record.total_turns += u64::from(match_data.state.turn_count);
+            record.total_duration_secs += duration;
+            let games = f64::from(stats.games_played);
+            stats.average_turns = record.total_turns as f64 / games;
+            stats.average_duration_secs = record.total_duration_secs as f64 / games;
+
+            for recorded in match_data.actions.iter().filter(|a| &a.player_id == id) {
+                let unit = match &recorded.action {
+                    Action::Move { unit_id, .. } | Action::Attack { unit_id, .. } => Some(*unit_id),
+                    _ => None,
+                };
+                if let Some(unit) = unit {
+                    let name = units.get(&unit).cloned().unwrap_or_else(|| format!("#{}", unit));
+                    *record.unit_uses.entry(name).or_default() += 1;
+                }
+            }
+            // Empate fica com o nome em ordem alfabética, para não variar
+            stats.favorite_unit = record
+                .unit_uses
+                .iter()
+                .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+                .map(|(name, _)| name.clone());
+        }
+    }
+}
+
+/// Tipo de cada unidade no estado inicial
+fn unit_names(match_data: &Match) -> HashMap<u32, String> {
+    match_data
+        .initial_state
+        .units
+        .iter()
+        .map(|unit| (unit.id, unit.kind.clone()))
+        .collect()
+}
@@ -0,0 +1,91 @@
+//! Prévia de ações (`dry_run`)
+//!
+//! A ação é aplicada numa cópia do estado; a partida não muda e os observers
+//! não são notificados. A resposta traz o estado resultante e a lista do que
+//! mudou, para a interface mostrar dano e movimento antes da confirmação.
+
+use serde::Serialize;
+use serde_json::Value;
+use tatic_lib::GameState;
+use utoipa::ToSchema;
+
+/// Valor que mudou entre dois estados
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct StateChange {
+    /// JSON Pointer do campo, ex: `/units/0/hp`
+    pub path: String,
+    /// `null` quando o campo foi criado
+    #[schema(value_type = Object)]
+    pub before: Value,
+    /// `null` quando o campo foi removido
+    #[schema(value_type = Object)]
+    pub after: Value,
+}
+
+/// Resultado de uma prévia
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ActionPreview {
+    #[schema(value_type = crate::openapi::GameStateSchema)]
+    pub state: GameState,
+    pub changes: Vec<StateChange>,
+}
+
+impl ActionPreview {
+    pub fn new(before: &GameState, after: GameState) -> Self {
+        let changes = match (serde_json::to_value(before), serde_json::to_value(&after)) {
+            (Ok(before), Ok(after)) => diff(&before, &after),
+            _ => Vec::new(),
+        };
+        Self {
+            state: after,
+            changes,
+        }
+    }
+}
+
+/// Diferenças entre dois JSON, campo a campo
+///
+/// Objetos e arrays são comparados por chave e por posição; os demais
+/// valores aparecem inteiros.
+pub fn diff(before: &Value, after: &Value) -> Vec<StateChange> {
+    let mut changes = Vec::new();
+    diff_into(String::new(), before, after, &mut changes);
+    changes
+}
+
+fn diff_into(path: String, before: &Value, after: &Value, changes: &mut Vec<StateChange>) {
+    match (before, after) {
+        (Value::Object(b), Value::Object(a)) => {
+            for (key, b_value) in b {
+                let child = format!("{}/{}", path, escape(key));
+                diff_into(child, b_value, a.get(key).unwrap_or(&Value::Null), changes);
+            }
+            for (key, a_value) in a.iter().filter(|(key, _)| !b.contains_key(*key)) {
+                let child = format!("{}/{}", path, escape(key));
+                diff_into(child, &Value::Null, a_value, changes);
+            }
+        }
+        (Value::Array(b), Value::Array(a)) => {
+            for i in 0..b.len().max(a.len()) {
+                let child = format!("{}/{}", path, i);
+                diff_into(
+                    child,
+                    b.get(i).unwrap_or(&Value::Null),
+                    a.get(i).unwrap_or(&Value::Null),
+                    changes,
+                );
+            }
+        }
+        _ if before != after => changes.push(StateChange {
+            path,
+            before: before.clone(),
+            after: after.clone(),
+        }),
+        _ => {}
+    }
+}
+
+/// Escapa `~` e `/` conforme o JSON Pointer (RFC 6901)
+fn escape(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
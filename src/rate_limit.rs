@@ -0,0 +1,193 @@
+//! Rate limiting por IP (token bucket)
+//!
+//! Cada grupo de rotas tem seu próprio limitador. Só o IP entra na chave:
+//! `X-Player-Id` não é autenticado, e limitar por ele deixaria qualquer um
+//! esgotar os tokens de outro jogador. Requisições sem IP conhecido (servidor
+//! montado sem `ConnectInfo`) dividem um mesmo bucket.
+//!
+//! Buckets parados até encher de novo são iguais a um bucket novo: uma
+//! varredura periódica os descarta. Se ainda assim a tabela chegar a
+//! `MAX_TRACKED_KEYS`, os vistos há mais tempo saem primeiro.
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tracing::warn;
+
+use crate::error::ApiError;
+
+/// Máximo de buckets guardados; ao chegar nele, um décimo (os vistos há mais
+/// tempo) é descartado
+const MAX_TRACKED_KEYS: usize = 10_000;
+
+/// Intervalo entre as varreduras de buckets parados
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Chave das requisições sem IP conhecido
+const UNKNOWN_CLIENT: &str = "ip:desconhecido";
+
+/// Parâmetros de um token bucket
+#[derive(Debug, Clone, Copy)]
+pub struct Limit {
+    /// Máximo de requisições em rajada
+    pub burst: u32,
+    /// Tokens repostos por segundo
+    pub per_second: f64,
+}
+
+/// Limites por grupo de rotas
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimits {
+    /// Leituras (`/state`, `/matches`, ...)
+    pub read: Limit,
+    /// Ações de jogo (`/action`, `/ai/action`)
+    pub action: Limit,
+    /// Criação de partidas (`/match/create`)
+    pub create: Limit,
+    /// Partidas e lobbies em andamento abertos por um mesmo IP
+    pub max_active_matches_per_ip: usize,
+}
+
+impl Default for RateLimits {
+    fn default() -> Self {
+        Self {
+            read: Limit { burst: 100, per_second: 50.0 },
+            action: Limit { burst: 20, per_second: 10.0 },
+            create: Limit { burst: 5, per_second: 0.2 },
+            max_active_matches_per_ip: 5,
+        }
+    }
+}
+
+struct Bucket {
+    tokens: f64,
+    /// Última requisição da chave
+    last_refill: Instant,
+}
+
+struct Buckets {
+    by_key: HashMap<String, Bucket>,
+    last_sweep: Instant,
+}
+
+impl Buckets {
+    /// Descarta os buckets parados há mais de `idle`
+    fn sweep(&mut self, now: Instant, idle: Duration) {
+        self.by_key.retain(|_, b| now.duration_since(b.last_refill) < idle);
+        self.last_sweep = now;
+    }
+
+    /// Descarta um décimo dos buckets, os vistos há mais tempo
+    ///
+    /// Um décimo de uma vez, para não ordenar a cada chave nova.
+    fn evict_oldest(&mut self) {
+        let mut oldest: Vec<_> = self
+            .by_key
+            .iter()
+            .map(|(key, b)| (b.last_refill, key.clone()))
+            .collect();
+        oldest.sort_unstable_by_key(|(last_refill, _)| *last_refill);
+        for (_, key) in oldest.into_iter().take(MAX_TRACKED_KEYS / 10) {
+            self.by_key.remove(&key);
+        }
+    }
+}
+
+/// Token bucket indexado por chave (IP)
+pub struct RateLimiter {
+    limit: Limit,
+    buckets: Mutex<Buckets>,
+}
+
+impl RateLimiter {
+    pub fn new(limit: Limit) -> Self {
+        Self {
+            limit,
+            buckets: Mutex::new(Buckets {
+                by_key: HashMap::new(),
+                last_sweep: Instant::now(),
+            }),
+        }
+    }
+
+    /// Tempo para um bucket vazio encher de novo
+    fn refill_time(&self) -> Duration {
+        Duration::try_from_secs_f64(f64::from(self.limit.burst) / self.limit.per_second)
+            .unwrap_or(Duration::MAX)
+    }
+
+    /// Consome um token de cada chave, ou retorna quanto esperar
+    ///
+    /// Nenhum token é consumido se alguma das chaves estiver sem saldo.
+    pub fn check(&self, keys: &[String]) -> Result<(), Duration> {
+        let capacity = f64::from(self.limit.burst);
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+
+        if now.duration_since(buckets.last_sweep) >= SWEEP_INTERVAL {
+            buckets.sweep(now, self.refill_time());
+        }
+        let new_keys = keys.iter().filter(|k| !buckets.by_key.contains_key(*k)).count();
+        if buckets.by_key.len() + new_keys > MAX_TRACKED_KEYS {
+            buckets.evict_oldest();
+        }
+
+        let mut wait = Duration::ZERO;
+        for key in keys {
+            let bucket = buckets.by_key.entry(key.clone()).or_insert(Bucket {
+                tokens: capacity,
+                last_refill: now,
+            });
+            let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+            bucket.tokens = (bucket.tokens + elapsed * self.limit.per_second).min(capacity);
+            bucket.last_refill = now;
+
+            if bucket.tokens < 1.0 {
+                let missing = (1.0 - bucket.tokens) / self.limit.per_second;
+                wait = wait.max(Duration::from_secs_f64(missing));
+            }
+        }
+
+        if !wait.is_zero() {
+            return Err(wait);
+        }
+        for key in keys {
+            if let Some(bucket) = buckets.by_key.get_mut(key) {
+                bucket.tokens -= 1.0;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Chaves de limite da requisição: o IP, ou `UNKNOWN_CLIENT` sem ele
+fn request_keys(request: &Request) -> Vec<String> {
+    let key = match request.extensions().get::<ConnectInfo<SocketAddr>>() {
+        Some(ConnectInfo(addr)) => format!("ip:{}", addr.ip()),
+        None => UNKNOWN_CLIENT.to_string(),
+    };
+    vec![key]
+}
+
+/// Middleware que aplica o limitador à requisição
+pub async fn rate_limit(State(limiter): State<Arc<RateLimiter>>, request: Request, next: Next) -> Response {
+    let keys = request_keys(&request);
+    match limiter.check(&keys) {
+        Ok(()) => next.run(request).await,
+        Err(wait) => {
+            warn!("🚫 Rate limit excedido para {:?} em {}", keys, request.uri().path());
+            ApiError::RateLimited {
+                retry_after_secs: (wait.as_secs_f64().ceil() as u64).max(1),
+            }
+            .into_response()
+        }
+    }
+}
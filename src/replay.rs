@@ -0,0 +1,147 @@
+//! Replay de partidas terminadas (`/ws/replay`)
+//!
+//! Os estados são recriados a partir do estado inicial e das ações gravadas
+//! na partida, e enviados um a um como se a partida estivesse ao vivo. O
+//! cliente controla a reprodução (pausa, velocidade, busca por turno).
+
+use serde::Deserialize;
+use std::time::Duration;
+use tatic_lib::GameState;
+
+use crate::error::{ApiError, Reason};
+use crate::match_actor::simulate;
+use crate::state::Match;
+
+/// Intervalo entre estados na velocidade 1
+const FRAME_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Velocidades aceitas
+const MIN_SPEED: f64 = 0.1;
+const MAX_SPEED: f64 = 16.0;
+
+/// Comandos do cliente durante o replay
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ReplayControl {
+    Pause,
+    Resume,
+    /// Vai para o primeiro estado com `turn_count` igual ou maior
+    Seek { turn: u32 },
+    /// Avança um estado e pausa
+    StepForward,
+    /// Volta um estado e pausa
+    StepBack,
+    Speed { speed: f64 },
+}
+
+/// Estados de uma partida e a posição da reprodução
+pub struct Playback {
+    frames: Vec<GameState>,
+    position: usize,
+    speed: f64,
+    paused: bool,
+}
+
+impl Playback {
+    /// Recria os estados de uma partida terminada
+    pub fn new(match_data: &Match, speed: f64) -> Result<Self, ApiError> {
+        if !match_data.is_finished() {
+            return Err(ApiError::Conflict {
+                reason: Reason::new("reason.match_in_progress").arg("match_id", &match_data.id),
+            });
+        }
+        validate_speed(speed)?;
+
+        let mut frames = vec![match_data.initial_state.clone()];
+        for recorded in &match_data.actions {
+            let current = frames.last().expect("sempre há o estado inicial");
+            frames.push(simulate(current, &recorded.player_id, recorded.action.clone())?);
+        }
+
+        Ok(Self {
+            frames,
+            position: 0,
+            speed,
+            paused: false,
+        })
+    }
+
+    pub fn current(&self) -> &GameState {
+        &self.frames[self.position]
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.position + 1 == self.frames.len()
+    }
+
+    /// Indica se o próximo estado deve ser enviado sozinho
+    pub fn is_playing(&self) -> bool {
+        !self.paused && !self.is_finished()
+    }
+
+    /// Tempo até o próximo estado na velocidade atual
+    pub fn interval(&self) -> Duration {
+        FRAME_INTERVAL.div_f64(self.speed)
+    }
+
+    /// Avança um estado; `false` se já está no último
+    pub fn advance(&mut self) -> bool {
+        if self.is_finished() {
+            return false;
+        }
+        self.position += 1;
+        true
+    }
+
+    /// Aplica um comando; `true` se o estado atual mudou
+    pub fn apply(&mut self, control: ReplayControl) -> Result<bool, ApiError> {
+        let before = self.position;
+        match control {
+            ReplayControl::Pause => self.paused = true,
+            ReplayControl::Resume => self.paused = false,
+            ReplayControl::Seek { turn } => {
+                self.position = self
+                    .frames
+                    .iter()
+                    .position(|s| s.turn_count >= turn)
+                    .unwrap_or(self.frames.len() - 1);
+            }
+            ReplayControl::StepForward => {
+                self.paused = true;
+                self.advance();
+            }
+            ReplayControl::StepBack => {
+                self.paused = true;
+                self.position = self.position.saturating_sub(1);
+            }
+            ReplayControl::Speed { speed } => {
+                validate_speed(speed)?;
+                self.speed = speed;
+            }
+        }
+        Ok(self.position != before)
+    }
+
+    /// Posição e controles, incluídos nas mensagens do replay
+    pub fn status(&self) -> serde_json::Value {
+        serde_json::json!({
+            "frame": self.position,
+            "frames": self.frames.len(),
+            "speed": self.speed,
+            "paused": self.paused,
+            "finished": self.is_finished(),
+        })
+    }
+}
+
+fn validate_speed(speed: f64) -> Result<(), ApiError> {
+    if (MIN_SPEED..=MAX_SPEED).contains(&speed) {
+        Ok(())
+    } else {
+        Err(ApiError::InvalidRequest {
+            reason: Reason::new("reason.replay_speed")
+                .arg("min", MIN_SPEED)
+                .arg("max", MAX_SPEED),
+        })
+    }
+}
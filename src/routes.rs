@@ -1,78 +1,214 @@
 use axum::{
-    extract::{Query, State},
-    http::StatusCode,
-    response::Json,
-    routing::{get, post},
-    Router,
+    extract::{ConnectInfo, Path, Query, RawQuery, State},
+    http::{header, HeaderMap},
+    middleware,
+    response::{IntoResponse, Json, Response},
+    routing::{delete, get, post},
+    Extension, Router,
 };
-use tatic_lib::{ai_choose_action, apply_action, Action, PlayerId};
+use tatic_lib::{Action, PlayerId};
 use serde::{Deserialize, Serialize};
-use tracing::{error, info, warn};
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+};
+use tracing::{info, warn};
+use utoipa::{IntoParams, OpenApi, ToSchema};
 
+use crate::admin;
+use crate::ai::{self, AiDecision, AiStrategy};
+use crate::audit;
+use crate::board::{self, Piece};
+use crate::bots::Bot;
+use crate::correspondence::{MatchMode, PendingTurn};
+use crate::error::{ApiError, ApiJson, ErrorResponse, Reason};
+use crate::fanout::FanoutMetricsSnapshot;
+use crate::history::{HistoryQuery, MatchHistoryPage};
+use crate::i18n;
+use crate::idempotency::{self, IdempotencyStore};
+use crate::lobby::{CreatedLobby, LobbyListing};
+use crate::moves::{self, LegalActions};
+use crate::notation::MatchDocument;
+use crate::players::{default_avatar, Player};
+use crate::openapi::{ActionSchema, ApiDoc, GameStateSchema};
+use crate::rate_limit::{self, RateLimiter};
+use crate::settings::GameSettings;
+use crate::tournament::{Standing, Tournament, TournamentFormat};
 use crate::state::{AppState, MatchId};
+use crate::websocket::WS_ENDPOINTS;
 
 /// Query params para GET /state
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
 pub struct StateQuery {
     match_id: String,
+    /// Quem pede; com `fog`, define o que é visível (ausente: espectador)
+    #[param(value_type = Option<String>)]
+    player_id: Option<PlayerId>,
+}
+
+/// Query params para GET /match/{id}/legal_actions
+#[derive(Deserialize, IntoParams)]
+pub struct LegalActionsQuery {
+    #[param(value_type = String)]
+    player_id: PlayerId,
+}
+
+/// Query params para GET /match/{id}/board
+#[derive(Deserialize, IntoParams)]
+pub struct BoardQuery {
+    /// Quem pede; com `fog`, define o que é visível (ausente: espectador)
+    #[param(value_type = Option<String>)]
+    player_id: Option<PlayerId>,
 }
 
 /// Request body para POST /action
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct ActionRequest {
     match_id: MatchId,
+    #[schema(value_type = String)]
     player_id: PlayerId,
+    #[schema(value_type = ActionSchema)]
     action: Action,
 }
 
-/// Response para requisições bem-sucedidas
-#[derive(Serialize)]
-pub struct SuccessResponse<T> {
-    success: bool,
-    data: T,
+/// Máximo de ações num lote de POST /actions
+const MAX_BATCH_ACTIONS: usize = 50;
+
+/// Request body para POST /actions
+#[derive(Deserialize, ToSchema)]
+pub struct BatchActionRequest {
+    match_id: MatchId,
+    #[schema(value_type = String)]
+    player_id: PlayerId,
+    /// Aplicadas em ordem; se uma falhar, nenhuma é aplicada
+    #[schema(value_type = Vec<ActionSchema>)]
+    actions: Vec<Action>,
 }
 
-/// Response para erros
-#[derive(Serialize)]
-pub struct ErrorResponse {
-    success: bool,
-    error: String,
+/// Response para requisições bem-sucedidas
+#[derive(Serialize, ToSchema)]
+pub struct SuccessResponse<T> {
+    pub(crate) success: bool,
+    pub(crate) data: T,
 }
 
 /// Cria as rotas REST
+///
+/// Cada grupo (leitura, ações, criação) tem seu próprio rate limit. Ações e
+/// criação aceitam `Idempotency-Key`.
 pub fn create_routes(state: AppState) -> Router {
-    Router::new()
+    let limiter = |limit| {
+        middleware::from_fn_with_state(Arc::new(RateLimiter::new(limit)), rate_limit::rate_limit)
+    };
+    // Uma só tabela de chaves para todas as rotas que alteram estado; fica por
+    // fora do rate limit para que repetições não gastem tokens
+    let idempotency = middleware::from_fn_with_state(
+        Arc::new(IdempotencyStore::from_env()),
+        idempotency::idempotency,
+    );
+    
+    let read_routes = Router::new()
         .route("/", get(root_handler))
         .route("/state", get(get_state_handler))
-        .route("/action", post(post_action_handler))
+        .route("/match/{id}/legal_actions", get(legal_actions_handler))
+        .route("/match/{id}/export", get(export_match_handler))
+        .route("/match/{id}/board", get(board_handler))
         .route("/matches", get(list_matches_handler))
-        .route("/match/create", post(create_match_handler))
+        .route("/metrics", get(metrics_handler))
+        .route("/lobby", get(list_lobby_handler))
+        .route("/tournaments", get(list_tournaments_handler))
+        .route("/tournaments/{id}", get(get_tournament_handler))
+        .route("/tournaments/{id}/standings", get(tournament_standings_handler))
+        .route("/players/{id}", get(get_player_handler))
+        .route("/players/{id}/my_turn", get(my_turn_handler))
+        .route("/players/{id}/matches", get(player_matches_handler))
+        .route("/bots", get(list_bots_handler))
+        .route_layer(limiter(state.limits.read));
+    
+    let action_routes = Router::new()
+        .route("/action", post(post_action_handler))
+        .route("/actions", post(post_actions_handler))
         .route("/ai/action", post(ai_action_handler))
+        .route("/lobby/{id}", delete(cancel_lobby_handler))
+        .route("/lobby/{id}/join", post(join_lobby_handler))
+        .route_layer(limiter(state.limits.action))
+        .route_layer(idempotency.clone());
+    
+    let create_routes = Router::new()
+        .route("/match/create", post(create_match_handler))
+        .route("/match/import", post(import_match_handler))
+        .route("/lobby/create", post(create_lobby_handler))
+        .route("/tournaments/create", post(create_tournament_handler))
+        .route_layer(limiter(state.limits.create))
+        .route_layer(idempotency);
+    
+    Router::new()
+        .merge(read_routes)
+        .merge(action_routes)
+        .merge(create_routes)
+        .merge(admin::admin_routes(state.clone()))
+        .layer(middleware::from_fn_with_state(state.clone(), audit::audit))
+        .layer(middleware::from_fn_with_state(state.clone(), i18n::localize_errors))
         .with_state(state)
 }
 
+/// IP de quem fez a requisição (ausente fora de `serve`, ex: nos testes)
+fn client_ip(connect_info: Option<Extension<ConnectInfo<SocketAddr>>>) -> Option<IpAddr> {
+    connect_info.map(|Extension(ConnectInfo(addr))| addr.ip())
+}
+
 /// Handler raiz - informações da API
-async fn root_handler() -> Json<serde_json::Value> {
+///
+/// A lista de endpoints vem do documento OpenAPI e de `WS_ENDPOINTS`, então não fica desatualizada.
+#[utoipa::path(
+    get,
+    path = "/",
+    responses((status = 200, description = "Informações da API"))
+)]
+pub(crate) async fn root_handler() -> Json<serde_json::Value> {
+    let doc = ApiDoc::openapi();
+    let mut endpoints = serde_json::Map::new();
+    for (path, item) in &doc.paths.paths {
+        let operations = [
+            ("GET", &item.get),
+            ("POST", &item.post),
+            ("PUT", &item.put),
+            ("DELETE", &item.delete),
+        ];
+        for (method, operation) in operations {
+            if let Some(operation) = operation {
+                let summary = operation.summary.clone().unwrap_or_default();
+                endpoints.insert(format!("{} {}", method, path), summary.into());
+            }
+        }
+    }
+    for (path, description) in WS_ENDPOINTS {
+        endpoints.insert(format!("WS {}", path), (*description).into());
+    }
+
     Json(serde_json::json!({
         "name": "RPG ASCII Tático - Servidor",
         "version": "0.1.0",
-        "endpoints": {
-            "GET /": "Informações da API",
-            "GET /state?match_id={id}": "Obtém estado do jogo",
-            "POST /action": "Envia ação do jogador",
-            "GET /matches": "Lista partidas disponíveis",
-            "POST /match/create": "Cria nova partida",
-            "POST /ai/action": "Solicita ação da IA",
-            "WS /ws?match_id={id}": "WebSocket para observar partida"
-        }
+        "openapi": "/openapi.json",
+        "docs": "/docs",
+        "endpoints": endpoints,
     }))
 }
 
 /// GET /state - Retorna estado atual da partida
-async fn get_state_handler(
+#[utoipa::path(
+    get,
+    path = "/state",
+    params(StateQuery),
+    responses(
+        (status = 200, description = "Estado da partida", body = SuccessResponse<GameStateSchema>),
+        (status = 404, description = "Partida não encontrada (match_not_found)", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn get_state_handler(
     Query(params): Query<StateQuery>,
     State(state): State<AppState>,
-) -> Result<Json<SuccessResponse<tatic_lib::GameState>>, (StatusCode, Json<ErrorResponse>)> {
+) -> Result<Json<SuccessResponse<tatic_lib::GameState>>, ApiError> {
     info!("📥 GET /state - match_id: {}", params.match_id);
     
     match state.get_match(&params.match_id).await {
@@ -80,101 +216,199 @@ async fn get_state_handler(
             info!("✅ Estado retornado para partida {}", params.match_id);
             Ok(Json(SuccessResponse {
                 success: true,
-                data: match_data.state,
+                data: match_data.view(params.player_id.as_deref()),
             }))
         }
         None => {
             warn!("❌ Partida não encontrada: {}", params.match_id);
-            Err((
-                StatusCode::NOT_FOUND,
-                Json(ErrorResponse {
-                    success: false,
-                    error: format!("Partida {} não encontrada", params.match_id),
-                }),
-            ))
+            Err(ApiError::MatchNotFound {
+                match_id: params.match_id,
+            })
         }
     }
 }
 
+/// GET /match/{id}/legal_actions - Ações legais do jogador, por unidade
+///
+/// Cada ação candidata é testada com `apply_action`, então a lista serve
+/// para destacar movimentos e alvos na interface.
+#[utoipa::path(
+    get,
+    path = "/match/{id}/legal_actions",
+    params(("id" = String, Path, description = "ID da partida"), LegalActionsQuery),
+    responses(
+        (status = 200, description = "Ações legais agrupadas por unidade", body = SuccessResponse<LegalActions>),
+        (status = 400, description = "Jogador fora da partida (invalid_request)", body = ErrorResponse),
+        (status = 404, description = "Partida não encontrada (match_not_found)", body = ErrorResponse),
+        (status = 500, description = "Falha ao gerar as ações (internal)", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn legal_actions_handler(
+    Path(match_id): Path<MatchId>,
+    Query(params): Query<LegalActionsQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<LegalActions>>, ApiError> {
+    info!("📥 GET /match/{}/legal_actions - player: {}", match_id, params.player_id);
+    
+    let not_found = || ApiError::MatchNotFound {
+        match_id: match_id.clone(),
+    };
+    let handle = state.get_handle(&match_id).await.ok_or_else(not_found)?;
+    if !handle.has_player(&params.player_id) {
+        return Err(ApiError::InvalidRequest {
+            reason: Reason::new("reason.not_a_player")
+                .arg("player_id", &params.player_id)
+                .arg("match_id", &match_id),
+        });
+    }
+    let match_data = handle.snapshot().await.ok_or_else(not_found)?;
+    
+    let legal = tokio::task::spawn_blocking(move || {
+        let view = match_data.view(Some(&params.player_id));
+        moves::grouped_legal_actions(&view, &params.player_id)
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("❌ Falha ao listar ações legais de {}: {}", match_id, e);
+        ApiError::Internal
+    })?;
+    
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: legal,
+    }))
+}
+
+/// Query params para POST /action
+#[derive(Deserialize, IntoParams)]
+pub struct ActionQuery {
+    /// Só simula: devolve o estado resultante e as mudanças, sem aplicar
+    #[serde(default)]
+    dry_run: bool,
+}
+
 /// POST /action - Processa ação do jogador
-async fn post_action_handler(
+///
+/// Com `?dry_run=true` a resposta é uma `ActionPreview` e a partida não muda.
+#[utoipa::path(
+    post,
+    path = "/action",
+    params(ActionQuery),
+    request_body = ActionRequest,
+    responses(
+        (status = 200, description = "Novo estado após a ação (ou prévia, com dry_run)", body = SuccessResponse<GameStateSchema>),
+        (status = 400, description = "Ação inválida (invalid_action)", body = ErrorResponse),
+        (status = 404, description = "Partida não encontrada (match_not_found)", body = ErrorResponse),
+        (status = 409, description = "Não é o turno do jogador (not_your_turn)", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn post_action_handler(
+    Query(params): Query<ActionQuery>,
     State(state): State<AppState>,
-    Json(request): Json<ActionRequest>,
-) -> Result<Json<SuccessResponse<tatic_lib::GameState>>, (StatusCode, Json<ErrorResponse>)> {
+    ApiJson(request): ApiJson<ActionRequest>,
+) -> Result<Response, ApiError> {
     info!(
-        "📥 POST /action - match: {}, player: {}, action: {:?}",
-        request.match_id, request.player_id, request.action
+        "📥 POST /action - match: {}, player: {}, action: {:?}, dry_run: {}",
+        request.match_id, request.player_id, request.action, params.dry_run
     );
     
-    // Obtém partida
-    let match_data = state.get_match(&request.match_id).await.ok_or_else(|| {
+    // Obtém o ator da partida
+    let handle = state.get_handle(&request.match_id).await.ok_or_else(|| {
         warn!("❌ Partida não encontrada: {}", request.match_id);
-        (
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                success: false,
-                error: format!("Partida {} não encontrada", request.match_id),
-            }),
-        )
+        ApiError::MatchNotFound {
+            match_id: request.match_id.clone(),
+        }
     })?;
     
-    // Log detalhado ANTES da ação
+    if params.dry_run {
+        let preview = handle.preview(&request.player_id, request.action).await?;
+        info!("🔍 Prévia calculada: {} mudanças", preview.changes.len());
+        return Ok(Json(SuccessResponse {
+            success: true,
+            data: preview,
+        })
+        .into_response());
+    }
+    
+    // Aplica ação (o ator atualiza o estado e notifica os observers)
+    let new_state = handle
+        .apply_action(request.player_id.clone(), request.action)
+        .await?;
+    
+    info!("✅ Ação aplicada com sucesso");
+    
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: handle.view(&new_state, Some(&request.player_id)),
+    })
+    .into_response())
+}
+
+/// POST /actions - Aplica várias ações do jogador de uma vez (tudo ou nada)
+///
+/// Observers recebem um só `state_update` com o estado final.
+#[utoipa::path(
+    post,
+    path = "/actions",
+    request_body = BatchActionRequest,
+    responses(
+        (status = 200, description = "Estado após todas as ações", body = SuccessResponse<GameStateSchema>),
+        (status = 400, description = "Lote vazio ou grande demais (invalid_request), ou ação inválida (batch_failed)", body = ErrorResponse),
+        (status = 404, description = "Partida não encontrada (match_not_found)", body = ErrorResponse),
+        (status = 409, description = "Ação fora do turno (batch_failed)", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn post_actions_handler(
+    State(state): State<AppState>,
+    ApiJson(request): ApiJson<BatchActionRequest>,
+) -> Result<Json<SuccessResponse<tatic_lib::GameState>>, ApiError> {
     info!(
-        "📊 Estado ANTES - Turno: {}, Contador: {}, Fase: {:?}",
-        match_data.state.turn, match_data.state.turn_count, match_data.state.phase
+        "📥 POST /actions - match: {}, player: {}, {} ações",
+        request.match_id,
+        request.player_id,
+        request.actions.len()
     );
     
-    // Aplica ação
-    match apply_action(&match_data.state, &request.player_id, request.action.clone()) {
-        Ok(new_state) => {
-            // Log detalhado DEPOIS da ação
-            info!(
-                "📊 Estado DEPOIS - Turno: {}, Contador: {}, Fase: {:?}",
-                new_state.turn, new_state.turn_count, new_state.phase
-            );
-            info!("✅ Ação aplicada com sucesso");
-            
-            // Atualiza estado
-            state.update_match(&request.match_id, new_state.clone()).await;
-            
-            // Notifica observers via WebSocket
-            let notification = serde_json::json!({
-                "type": "state_update",
-                "match_id": request.match_id,
-                "state": &new_state,
-            });
-            
-            state
-                .notify_observers(&request.match_id, notification.to_string())
-                .await;
-            
-            Ok(Json(SuccessResponse {
-                success: true,
-                data: new_state,
-            }))
-        }
-        Err(e) => {
-            error!("❌ Erro ao aplicar ação: {}", e);
-            Err((
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    success: false,
-                    error: e.to_string(),
-                }),
-            ))
-        }
+    if request.actions.is_empty() || request.actions.len() > MAX_BATCH_ACTIONS {
+        return Err(ApiError::InvalidRequest {
+            reason: Reason::new("reason.batch_size").arg("max", MAX_BATCH_ACTIONS),
+        });
     }
+    
+    let handle = state.get_handle(&request.match_id).await.ok_or_else(|| {
+        warn!("❌ Partida não encontrada: {}", request.match_id);
+        ApiError::MatchNotFound {
+            match_id: request.match_id.clone(),
+        }
+    })?;
+    
+    let new_state = handle
+        .apply_batch(request.player_id.clone(), request.actions)
+        .await?;
+    
+    info!("✅ Lote aplicado com sucesso");
+    
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: handle.view(&new_state, Some(&request.player_id)),
+    }))
 }
 
 /// GET /matches - Lista partidas disponíveis
-async fn list_matches_handler(
+#[utoipa::path(
+    get,
+    path = "/matches",
+    responses((status = 200, description = "Partidas ativas", body = SuccessResponse<Vec<Object>>))
+)]
+pub(crate) async fn list_matches_handler(
     State(state): State<AppState>,
 ) -> Json<SuccessResponse<Vec<serde_json::Value>>> {
     info!("📥 GET /matches");
     
-    let matches = state.matches.read().await;
-    let match_list: Vec<_> = matches
-        .values()
+    let match_list: Vec<_> = state
+        .list_matches()
+        .await
+        .iter()
         .map(|m| {
             serde_json::json!({
                 "id": m.id,
@@ -182,6 +416,9 @@ async fn list_matches_handler(
                 "turn": m.state.turn,
                 "turn_count": m.state.turn_count,
                 "phase": m.state.phase,
+                "mode": m.mode,
+                "settings": m.settings,
+                "turn_deadline": m.turn_deadline(),
                 "created_at": m.created_at,
                 "updated_at": m.updated_at,
             })
@@ -196,78 +433,621 @@ async fn list_matches_handler(
     })
 }
 
+/// GET /metrics - Métricas do broadcast para observers
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    responses((status = 200, description = "Contadores de mensagens perdidas", body = SuccessResponse<FanoutMetricsSnapshot>))
+)]
+pub(crate) async fn metrics_handler(
+    State(state): State<AppState>,
+) -> Json<SuccessResponse<FanoutMetricsSnapshot>> {
+    Json(SuccessResponse {
+        success: true,
+        data: state.fanout_metrics.snapshot(),
+    })
+}
+
 /// Request para criar partida
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct CreateMatchRequest {
+    #[schema(value_type = String)]
     player1: PlayerId,
+    #[schema(value_type = String)]
     player2: PlayerId,
+    /// Padrão `live`; `correspondence` exige `turn_deadline_hours`
+    #[serde(default)]
+    mode: MatchMode,
+    /// Ausente: tudo como em `GameState::new`
+    settings: Option<GameSettings>,
 }
 
 /// POST /match/create - Cria nova partida
-async fn create_match_handler(
+#[utoipa::path(
+    post,
+    path = "/match/create",
+    request_body = CreateMatchRequest,
+    responses(
+        (status = 200, description = "ID da partida criada", body = SuccessResponse<String>),
+        (status = 400, description = "Prazo de turno ou configurações inválidas (invalid_request)", body = ErrorResponse),
+        (status = 429, description = "Limite de partidas ou de requisições (too_many_matches, rate_limited)", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn create_match_handler(
     State(state): State<AppState>,
-    Json(request): Json<CreateMatchRequest>,
-) -> Json<SuccessResponse<String>> {
+    connect_info: Option<Extension<ConnectInfo<SocketAddr>>>,
+    ApiJson(request): ApiJson<CreateMatchRequest>,
+) -> Result<Json<SuccessResponse<String>>, ApiError> {
     info!(
         "📥 POST /match/create - player1: {}, player2: {}",
         request.player1, request.player2
     );
     
-    let match_id = state.create_match(request.player1, request.player2).await;
+    let match_id = state
+        .create_match(
+            request.player1,
+            request.player2,
+            request.mode,
+            request.settings,
+            client_ip(connect_info),
+        )
+        .await
+        .inspect_err(|e| warn!("❌ Partida não criada: {}", e))?;
     
     info!("✅ Partida criada: {}", match_id);
     
-    Json(SuccessResponse {
+    Ok(Json(SuccessResponse {
         success: true,
         data: match_id,
-    })
+    }))
+}
+
+/// GET /match/{id}/export - Exporta a partida na notação de texto
+///
+/// Ver `notation` para o formato. O documento é aceito por `POST /match/import`.
+/// Partidas com `fog` só podem ser exportadas depois do fim: o documento traz
+/// todas as jogadas.
+#[utoipa::path(
+    get,
+    path = "/match/{id}/export",
+    params(("id" = String, Path, description = "ID da partida")),
+    responses(
+        (status = 200, description = "Documento da partida", body = String, content_type = "text/plain"),
+        (status = 404, description = "Partida não encontrada (match_not_found)", body = ErrorResponse),
+        (status = 409, description = "Partida com fog em andamento (conflict)", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn export_match_handler(
+    Path(match_id): Path<MatchId>,
+    State(state): State<AppState>,
+) -> Result<Response, ApiError> {
+    info!("📥 GET /match/{}/export", match_id);
+    
+    let match_data = state.get_match(&match_id).await.ok_or_else(|| ApiError::MatchNotFound {
+        match_id: match_id.clone(),
+    })?;
+    if match_data.has_fog() && !match_data.is_finished() {
+        return Err(ApiError::Conflict {
+            reason: Reason::new("reason.match_in_progress").arg("match_id", &match_id),
+        });
+    }
+    let document = MatchDocument::from_match(&match_data).to_text();
+    
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/plain; charset=utf-8".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}.tatic\"", match_id),
+            ),
+        ],
+        document,
+    )
+        .into_response())
+}
+
+/// GET /match/{id}/board - Tabuleiro em texto, com legenda
+///
+/// A legenda sai no idioma da requisição (`?lang=`, preferência do jogador ou
+/// `Accept-Language`).
+#[utoipa::path(
+    get,
+    path = "/match/{id}/board",
+    params(
+        ("id" = String, Path, description = "ID da partida"),
+        BoardQuery,
+        ("lang" = Option<String>, Query, description = "Idioma da legenda (pt-BR, en)"),
+    ),
+    responses(
+        (status = 200, description = "Tabuleiro e legenda", body = String, content_type = "text/plain"),
+        (status = 404, description = "Partida não encontrada (match_not_found)", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn board_handler(
+    Path(match_id): Path<MatchId>,
+    Query(params): Query<BoardQuery>,
+    State(state): State<AppState>,
+    RawQuery(query): RawQuery,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    info!("📥 GET /match/{}/board", match_id);
+    
+    let match_data = state.get_match(&match_id).await.ok_or_else(|| ApiError::MatchNotFound {
+        match_id: match_id.clone(),
+    })?;
+    let lang = i18n::resolve_lang(&state, query.as_deref(), &headers).await;
+    
+    let mut pieces = Vec::new();
+    for player_id in &match_data.state.players {
+        pieces.push(match state.get_player(player_id).await {
+            Ok(player) => Piece {
+                player_id: player.id,
+                display_name: player.display_name,
+                avatar: player.avatar,
+            },
+            Err(_) => Piece {
+                player_id: player_id.clone(),
+                display_name: player_id.clone(),
+                avatar: default_avatar(player_id),
+            },
+        });
+    }
+    
+    Ok((
+        [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+        board::render(&match_data.view(params.player_id.as_deref()), &pieces, lang),
+    )
+        .into_response())
+}
+
+/// POST /match/import - Recria uma partida exportada
+///
+/// As ações do documento são reaplicadas com `apply_action` a partir do
+/// estado inicial; a primeira ilegal é recusada com `import_failed`. A partida
+/// recebe um novo ID.
+#[utoipa::path(
+    post,
+    path = "/match/import",
+    request_body(content = String, content_type = "text/plain", description = "Documento gerado por /match/{id}/export"),
+    responses(
+        (status = 200, description = "ID da partida criada", body = SuccessResponse<String>),
+        (status = 400, description = "Documento mal formado ou resultado divergente (invalid_request)", body = ErrorResponse),
+        (status = 422, description = "Ação ilegal no documento (import_failed)", body = ErrorResponse),
+        (status = 429, description = "Limite de partidas ou de requisições (too_many_matches, rate_limited)", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn import_match_handler(
+    State(state): State<AppState>,
+    connect_info: Option<Extension<ConnectInfo<SocketAddr>>>,
+    body: String,
+) -> Result<Json<SuccessResponse<String>>, ApiError> {
+    info!("📥 POST /match/import - {} bytes", body.len());
+    
+    let document = MatchDocument::parse(&body)?;
+    let match_id = state
+        .import_match(document, client_ip(connect_info))
+        .await
+        .inspect_err(|e| warn!("❌ Partida não importada: {}", e))?;
+    
+    info!("✅ Partida importada: {}", match_id);
+    
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: match_id,
+    }))
 }
 
 /// Request para ação da IA
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct AiActionRequest {
     match_id: MatchId,
+    #[schema(value_type = String)]
     ai_player: PlayerId,
+    /// Dificuldade; padrão `builtin` (`ai_choose_action`)
+    #[serde(default)]
+    strategy: AiStrategy,
+    /// Quantas candidatas pontuadas devolver (dicas); 0 não devolve nenhuma
+    #[serde(default)]
+    top_k: usize,
 }
 
 /// POST /ai/action - Solicita ação da IA
-async fn ai_action_handler(
+///
+/// Com `top_k`, devolve também as melhores candidatas com pontuação e efeitos.
+#[utoipa::path(
+    post,
+    path = "/ai/action",
+    request_body = AiActionRequest,
+    responses(
+        (status = 200, description = "Ação escolhida pela IA", body = SuccessResponse<AiDecision>),
+        (status = 400, description = "Parâmetros da estratégia inválidos (invalid_request)", body = ErrorResponse),
+        (status = 404, description = "Partida não encontrada (match_not_found)", body = ErrorResponse),
+        (status = 500, description = "IA não conseguiu escolher ação (ai_failed)", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn ai_action_handler(
     State(state): State<AppState>,
-    Json(request): Json<AiActionRequest>,
-) -> Result<Json<SuccessResponse<Action>>, (StatusCode, Json<ErrorResponse>)> {
+    ApiJson(request): ApiJson<AiActionRequest>,
+) -> Result<Json<SuccessResponse<AiDecision>>, ApiError> {
     info!(
-        "🤖 POST /ai/action - match: {}, ai_player: {}",
-        request.match_id, request.ai_player
+        "🤖 POST /ai/action - match: {}, ai_player: {}, strategy: {:?}",
+        request.match_id, request.ai_player, request.strategy
     );
     
+    request
+        .strategy
+        .validate()
+        .map_err(|reason| ApiError::InvalidRequest { reason })?;
+    
     let match_data = state.get_match(&request.match_id).await.ok_or_else(|| {
-        (
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                success: false,
-                error: format!("Partida {} não encontrada", request.match_id),
-            }),
-        )
+        ApiError::MatchNotFound {
+            match_id: request.match_id.clone(),
+        }
     })?;
     
-    // IA escolhe ação
-    match ai_choose_action(&match_data.state, &request.ai_player) {
-        Some(action) => {
-            info!("🎯 IA escolheu ação: {:?}", action);
+    // Busca pode levar até o orçamento de tempo: roda fora do runtime async
+    let top_k = request.top_k.min(ai::MAX_TOP_K);
+    let decision = tokio::task::spawn_blocking(move || {
+        // Com `fog`, a IA só conhece o que o jogador vê
+        ai::decide(
+            &match_data.view(Some(&request.ai_player)),
+            &request.ai_player,
+            request.strategy,
+            top_k,
+            &mut rand::rng(),
+        )
+    })
+    .await
+    .ok()
+    .flatten();
+    
+    match decision {
+        Some(decision) => {
+            info!("🎯 IA escolheu ação: {:?}", decision.action);
             Ok(Json(SuccessResponse {
                 success: true,
-                data: action,
+                data: decision,
             }))
         }
         None => {
             warn!("❌ IA não conseguiu escolher ação");
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse {
-                    success: false,
-                    error: "IA não conseguiu escolher ação".to_string(),
-                }),
-            ))
+            Err(ApiError::AiFailed)
         }
     }
 }
+
+/// GET /players/{id} - Perfil e estatísticas do jogador
+#[utoipa::path(
+    get,
+    path = "/players/{id}",
+    params(("id" = String, Path, description = "ID do jogador")),
+    responses(
+        (status = 200, description = "Perfil do jogador", body = SuccessResponse<Player>),
+        (status = 404, description = "Jogador sem partidas nem perfil (player_not_found)", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn get_player_handler(
+    Path(player_id): Path<PlayerId>,
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<Player>>, ApiError> {
+    info!("📥 GET /players/{}", player_id);
+    
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: state.get_player(&player_id).await?,
+    }))
+}
+
+/// GET /players/{id}/matches - Partidas do jogador, das mais novas para as mais antigas
+///
+/// Inclui partidas em andamento, terminadas e removidas, com links para
+/// exportar, observar ou assistir o replay.
+#[utoipa::path(
+    get,
+    path = "/players/{id}/matches",
+    params(("id" = String, Path, description = "ID do jogador"), HistoryQuery),
+    responses(
+        (status = 200, description = "Página do histórico", body = SuccessResponse<MatchHistoryPage>),
+        (status = 400, description = "Query inválida", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn player_matches_handler(
+    Path(player_id): Path<PlayerId>,
+    Query(query): Query<HistoryQuery>,
+    State(state): State<AppState>,
+) -> Json<SuccessResponse<MatchHistoryPage>> {
+    info!("📥 GET /players/{}/matches - {:?}", player_id, query);
+    
+    Json(SuccessResponse {
+        success: true,
+        data: state.player_matches(&player_id, &query).await,
+    })
+}
+
+/// GET /players/{id}/my_turn - Partidas em que é a vez do jogador
+///
+/// Inclui partidas ao vivo e por correspondência; as com prazo mais curto vêm
+/// primeiro.
+#[utoipa::path(
+    get,
+    path = "/players/{id}/my_turn",
+    params(("id" = String, Path, description = "ID do jogador")),
+    responses((status = 200, description = "Turnos pendentes", body = SuccessResponse<Vec<PendingTurn>>))
+)]
+pub(crate) async fn my_turn_handler(
+    Path(player_id): Path<PlayerId>,
+    State(state): State<AppState>,
+) -> Json<SuccessResponse<Vec<PendingTurn>>> {
+    info!("📥 GET /players/{}/my_turn", player_id);
+    
+    Json(SuccessResponse {
+        success: true,
+        data: state.pending_turns(&player_id).await,
+    })
+}
+
+/// Request para abrir partida no lobby
+#[derive(Deserialize, ToSchema)]
+pub struct CreateLobbyRequest {
+    #[schema(value_type = String)]
+    host: PlayerId,
+    password: Option<String>,
+    settings: Option<GameSettings>,
+}
+
+/// POST /lobby/create - Abre partida aguardando segundo jogador
+#[utoipa::path(
+    post,
+    path = "/lobby/create",
+    request_body = CreateLobbyRequest,
+    responses(
+        (status = 200, description = "Partida aberta no lobby e token do anfitrião", body = SuccessResponse<CreatedLobby>),
+        (status = 400, description = "Configurações inválidas (invalid_request)", body = ErrorResponse),
+        (status = 429, description = "Limite de partidas ou de requisições (too_many_matches, rate_limited)", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn create_lobby_handler(
+    State(state): State<AppState>,
+    connect_info: Option<Extension<ConnectInfo<SocketAddr>>>,
+    ApiJson(request): ApiJson<CreateLobbyRequest>,
+) -> Result<Json<SuccessResponse<CreatedLobby>>, ApiError> {
+    info!("📥 POST /lobby/create - host: {}", request.host);
+    
+    let created = state
+        .create_lobby(
+            request.host,
+            request.password,
+            request.settings,
+            client_ip(connect_info),
+        )
+        .await
+        .inspect_err(|e| warn!("❌ Lobby não criado: {}", e))?;
+    
+    info!("✅ Lobby aberto: {}", created.lobby.id);
+    
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: created,
+    }))
+}
+
+/// DELETE /lobby/{id} - Cancela a partida aberta (só o anfitrião)
+///
+/// O anfitrião se identifica com o `host_token` recebido em `POST /lobby/create`.
+#[utoipa::path(
+    delete,
+    path = "/lobby/{id}",
+    params(("id" = String, Path, description = "ID da partida no lobby")),
+    security(("host_token" = [])),
+    responses(
+        (status = 200, description = "ID da partida cancelada", body = SuccessResponse<String>),
+        (status = 401, description = "Token ausente ou de outro lobby (unauthorized)", body = ErrorResponse),
+        (status = 404, description = "Partida não está no lobby (match_not_found)", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn cancel_lobby_handler(
+    Path(lobby_id): Path<MatchId>,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<SuccessResponse<MatchId>>, ApiError> {
+    info!("📥 DELETE /lobby/{}", lobby_id);
+    
+    let token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .ok_or(ApiError::Unauthorized)?;
+    state
+        .cancel_lobby(&lobby_id, token)
+        .await
+        .inspect_err(|e| warn!("❌ Lobby não cancelado: {}", e))?;
+    
+    info!("✅ Lobby cancelado: {}", lobby_id);
+    
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: lobby_id,
+    }))
+}
+
+/// GET /lobby - Lista partidas abertas
+#[utoipa::path(
+    get,
+    path = "/lobby",
+    responses((status = 200, description = "Partidas aguardando jogador", body = SuccessResponse<Vec<LobbyListing>>))
+)]
+pub(crate) async fn list_lobby_handler(
+    State(state): State<AppState>,
+) -> Json<SuccessResponse<Vec<LobbyListing>>> {
+    info!("📥 GET /lobby");
+    
+    Json(SuccessResponse {
+        success: true,
+        data: state.list_lobby().await,
+    })
+}
+
+/// Request para entrar numa partida do lobby
+#[derive(Deserialize, ToSchema)]
+pub struct JoinLobbyRequest {
+    #[schema(value_type = String)]
+    player_id: PlayerId,
+    password: Option<String>,
+}
+
+/// POST /lobby/{id}/join - Ocupa o assento livre e inicia a partida
+#[utoipa::path(
+    post,
+    path = "/lobby/{id}/join",
+    params(("id" = String, Path, description = "ID da partida no lobby")),
+    request_body = JoinLobbyRequest,
+    responses(
+        (status = 200, description = "ID da partida iniciada", body = SuccessResponse<String>),
+        (status = 401, description = "Senha incorreta (unauthorized)", body = ErrorResponse),
+        (status = 404, description = "Partida não está no lobby (match_not_found)", body = ErrorResponse),
+        (status = 409, description = "Anfitrião tentando entrar na própria partida (conflict)", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn join_lobby_handler(
+    Path(lobby_id): Path<MatchId>,
+    State(state): State<AppState>,
+    ApiJson(request): ApiJson<JoinLobbyRequest>,
+) -> Result<Json<SuccessResponse<MatchId>>, ApiError> {
+    info!("📥 POST /lobby/{}/join - player: {}", lobby_id, request.player_id);
+    
+    let match_id = state
+        .join_lobby(&lobby_id, request.player_id, request.password.as_deref())
+        .await
+        .inspect_err(|e| warn!("❌ Entrada no lobby recusada: {}", e))?;
+    
+    info!("✅ Partida iniciada a partir do lobby: {}", match_id);
+    
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: match_id,
+    }))
+}
+
+/// Request para criar torneio
+#[derive(Deserialize, ToSchema)]
+pub struct CreateTournamentRequest {
+    name: String,
+    format: TournamentFormat,
+    /// Na ordem de seed
+    #[schema(value_type = Vec<String>)]
+    players: Vec<PlayerId>,
+}
+
+/// POST /tournaments/create - Cria torneio e inicia a primeira rodada
+#[utoipa::path(
+    post,
+    path = "/tournaments/create",
+    request_body = CreateTournamentRequest,
+    responses(
+        (status = 200, description = "Torneio criado com o chaveamento inicial", body = SuccessResponse<Tournament>),
+        (status = 400, description = "Jogadores inválidos (invalid_request)", body = ErrorResponse),
+        (status = 429, description = "A primeira rodada passaria do limite de partidas (too_many_matches)", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn create_tournament_handler(
+    State(state): State<AppState>,
+    connect_info: Option<Extension<ConnectInfo<SocketAddr>>>,
+    ApiJson(request): ApiJson<CreateTournamentRequest>,
+) -> Result<Json<SuccessResponse<Tournament>>, ApiError> {
+    info!(
+        "📥 POST /tournaments/create - {} ({:?}, {} jogadores)",
+        request.name,
+        request.format,
+        request.players.len()
+    );
+    
+    let tournament = state
+        .create_tournament(request.name, request.format, request.players, client_ip(connect_info))
+        .await
+        .inspect_err(|e| warn!("❌ Torneio não criado: {}", e))?;
+    
+    info!("✅ Torneio criado: {}", tournament.id);
+    
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: tournament,
+    }))
+}
+
+/// GET /tournaments - Lista torneios
+#[utoipa::path(
+    get,
+    path = "/tournaments",
+    responses((status = 200, description = "Torneios", body = SuccessResponse<Vec<Tournament>>))
+)]
+pub(crate) async fn list_tournaments_handler(
+    State(state): State<AppState>,
+) -> Json<SuccessResponse<Vec<Tournament>>> {
+    info!("📥 GET /tournaments");
+    
+    Json(SuccessResponse {
+        success: true,
+        data: state.list_tournaments().await,
+    })
+}
+
+/// GET /tournaments/{id} - Torneio com o chaveamento completo
+#[utoipa::path(
+    get,
+    path = "/tournaments/{id}",
+    params(("id" = String, Path, description = "ID do torneio")),
+    responses(
+        (status = 200, description = "Torneio e confrontos", body = SuccessResponse<Tournament>),
+        (status = 404, description = "Torneio não encontrado (tournament_not_found)", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn get_tournament_handler(
+    Path(tournament_id): Path<String>,
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<Tournament>>, ApiError> {
+    info!("📥 GET /tournaments/{}", tournament_id);
+    
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: state.get_tournament(&tournament_id).await?,
+    }))
+}
+
+/// GET /tournaments/{id}/standings - Classificação do torneio
+#[utoipa::path(
+    get,
+    path = "/tournaments/{id}/standings",
+    params(("id" = String, Path, description = "ID do torneio")),
+    responses(
+        (status = 200, description = "Classificação", body = SuccessResponse<Vec<Standing>>),
+        (status = 404, description = "Torneio não encontrado (tournament_not_found)", body = ErrorResponse),
+    )
+)]
+pub(crate) async fn tournament_standings_handler(
+    Path(tournament_id): Path<String>,
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<Vec<Standing>>>, ApiError> {
+    info!("📥 GET /tournaments/{}/standings", tournament_id);
+    
+    Ok(Json(SuccessResponse {
+        success: true,
+        data: state.tournament_standings(&tournament_id).await?,
+    }))
+}
+
+/// GET /bots - Lista bots registrados
+#[utoipa::path(
+    get,
+    path = "/bots",
+    responses((status = 200, description = "Bots registrados", body = SuccessResponse<Vec<Bot>>))
+)]
+pub(crate) async fn list_bots_handler(
+    State(state): State<AppState>,
+) -> Json<SuccessResponse<Vec<Bot>>> {
+    info!("📥 GET /bots");
+    
+    Json(SuccessResponse {
+        success: true,
+        data: state.list_bots().await,
+    })
+}
@@ -0,0 +1,245 @@
+//! Configurações de partida escolhidas na criação
+//!
+//! As configurações são aplicadas sobre o estado criado por `GameState::new`:
+//! mapa e tamanho do tabuleiro, unidades de cada lado e quem começa. `fog`
+//! não muda o estado: filtra o que cada cliente recebe (ver `fog`).
+//! `seed` só decide o sorteio de quem começa; o jogo em si não usa sorteios.
+//! O controle de tempo não entra no estado: vira prazo de turno da partida.
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use tatic_lib::{Coord, GameState, PlayerId, Unit};
+use utoipa::ToSchema;
+
+use crate::error::{ApiError, Reason};
+
+/// Mapas pré-definidos: nome, largura e altura
+///
+/// `default` mantém o tabuleiro de `GameState::new`.
+pub const MAPS: &[(&str, u32, u32)] = &[
+    ("skirmish", 8, 8),
+    ("arena", 12, 12),
+    ("corridor", 16, 6),
+    ("fortress", 20, 20),
+];
+
+/// Limites do tabuleiro
+const MIN_BOARD: u32 = 4;
+const MAX_BOARD: u32 = 32;
+
+/// Limites do controle de tempo
+const MIN_TURN_SECS: u32 = 5;
+const MAX_TURN_SECS: u32 = 3600;
+
+/// Configurações da partida
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GameSettings {
+    /// Um de `MAPS` ou `default`
+    pub map: Option<String>,
+    /// Tamanho do tabuleiro; tem precedência sobre o do mapa
+    pub board: Option<BoardSize>,
+    /// Unidades de cada lado; padrão as de `GameState::new`
+    pub roster: Option<Roster>,
+    #[serde(default)]
+    pub starting_player: StartingPlayer,
+    /// Semente do sorteio de `starting_player: random`; com o sorteio e sem
+    /// semente, uma é gerada na criação e guardada para a exportação repetir
+    /// o mesmo sorteio
+    pub seed: Option<u64>,
+    /// Tempo de cada turno em partidas ao vivo
+    pub time_control: Option<TimeControl>,
+    /// Neblina de guerra: cada jogador só vê as unidades inimigas próximas
+    #[serde(default)]
+    pub fog: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct BoardSize {
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Tipos das unidades de cada lado, em ordem de posicionamento
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Roster {
+    pub player1: Vec<String>,
+    pub player2: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StartingPlayer {
+    #[default]
+    Player1,
+    Player2,
+    /// Sorteado com `seed`
+    Random,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct TimeControl {
+    /// Sem jogar nesse tempo, a vez é passada
+    pub turn_secs: u32,
+}
+
+impl GameSettings {
+    /// Confere os limites que não dependem do estado do jogo
+    ///
+    /// Os tipos de unidade do `roster` só são conferidos ao montar o estado.
+    pub fn validate(&self) -> Result<(), ApiError> {
+        if let Some(map) = &self.map
+            && map != "default"
+            && !MAPS.iter().any(|(name, _, _)| name == map)
+        {
+            let names: Vec<_> = MAPS.iter().map(|(name, _, _)| *name).collect();
+            return Err(invalid(
+                Reason::new("reason.unknown_map")
+                    .arg("map", map)
+                    .arg("available", format!("default, {}", names.join(", "))),
+            ));
+        }
+        if let Some(board) = self.board
+            && ![board.width, board.height]
+                .iter()
+                .all(|side| (MIN_BOARD..=MAX_BOARD).contains(side))
+        {
+            return Err(invalid(
+                Reason::new("reason.board_size")
+                    .arg("min", MIN_BOARD)
+                    .arg("max", MAX_BOARD),
+            ));
+        }
+        if let Some(roster) = &self.roster
+            && (roster.player1.is_empty() || roster.player2.is_empty())
+        {
+            return Err(invalid(Reason::new("reason.roster_empty")));
+        }
+        if let Some(time) = self.time_control
+            && !(MIN_TURN_SECS..=MAX_TURN_SECS).contains(&time.turn_secs)
+        {
+            return Err(invalid(
+                Reason::new("reason.turn_secs")
+                    .arg("min", MIN_TURN_SECS)
+                    .arg("max", MAX_TURN_SECS),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Tempo de cada turno (`None` sem controle de tempo)
+    pub fn turn_time(&self) -> Option<chrono::Duration> {
+        Some(chrono::Duration::seconds(i64::from(self.time_control?.turn_secs)))
+    }
+
+    /// Tamanho do tabuleiro pedido (`board`, ou o do mapa)
+    fn board_size(&self) -> Option<BoardSize> {
+        self.board.or_else(|| {
+            let map = self.map.as_deref()?;
+            MAPS.iter()
+                .find(|(name, _, _)| *name == map)
+                .map(|&(_, width, height)| BoardSize { width, height })
+        })
+    }
+
+    /// Estado inicial com as configurações aplicadas
+    ///
+    /// Com `starting_player: random` e sem `seed`, uma é sorteada e guardada aqui.
+    pub fn initial_state(
+        &mut self,
+        player1: &PlayerId,
+        player2: &PlayerId,
+    ) -> Result<GameState, ApiError> {
+        self.validate()?;
+        let mut state = GameState::new(player1.clone(), player2.clone());
+
+        let size = self.board_size();
+        if let Some(size) = size {
+            state.width = size.width as i32;
+            state.height = size.height as i32;
+        }
+        if size.is_some() || self.roster.is_some() {
+            deploy(&mut state, [player1, player2], self.roster.as_ref())?;
+        }
+
+        state.turn = match self.starting_player {
+            StartingPlayer::Player1 => player1,
+            StartingPlayer::Player2 => player2,
+            StartingPlayer::Random => {
+                let seed = *self.seed.get_or_insert_with(rand::random);
+                if StdRng::seed_from_u64(seed).random_bool(0.5) {
+                    player2
+                } else {
+                    player1
+                }
+            }
+        }
+        .clone();
+        Ok(state)
+    }
+}
+
+/// Reposiciona as unidades: o primeiro jogador na linha de cima, o segundo
+/// na de baixo, centralizadas
+///
+/// Cada tipo do `roster` copia a primeira unidade daquele tipo no estado de
+/// `GameState::new`, com os atributos que ela tiver.
+fn deploy(
+    state: &mut GameState,
+    players: [&PlayerId; 2],
+    roster: Option<&Roster>,
+) -> Result<(), ApiError> {
+    let templates = std::mem::take(&mut state.units);
+    let sides: [Vec<String>; 2] = match roster {
+        Some(roster) => [roster.player1.clone(), roster.player2.clone()],
+        None => players.map(|player| {
+            templates
+                .iter()
+                .filter(|u| &u.owner == player)
+                .map(|u| u.kind.clone())
+                .collect()
+        }),
+    };
+
+    for (side, (player, kinds)) in players.iter().zip(&sides).enumerate() {
+        let count = kinds.len() as i32;
+        if count > state.width {
+            return Err(invalid(
+                Reason::new("reason.row_too_narrow")
+                    .arg("count", count)
+                    .arg("width", state.width),
+            ));
+        }
+        let y = if side == 0 { 0 } else { state.height - 1 };
+        let start = (state.width - count) / 2;
+        for (i, kind) in kinds.iter().enumerate() {
+            let template = templates.iter().find(|u| &u.kind == kind).ok_or_else(|| {
+                let mut known: Vec<_> = templates.iter().map(|u| u.kind.as_str()).collect();
+                known.sort_unstable();
+                known.dedup();
+                invalid(
+                    Reason::new("reason.unknown_unit")
+                        .arg("kind", kind)
+                        .arg("available", known.join(", ")),
+                )
+            })?;
+            state.units.push(Unit {
+                id: state.units.len() as u32 + 1,
+                owner: (*player).clone(),
+                pos: Coord {
+                    x: start + i as i32,
+                    y,
+                },
+                ..template.clone()
+            });
+        }
+    }
+    Ok(())
+}
+
+fn invalid(reason: Reason) -> ApiError {
+    ApiError::InvalidRequest { reason }
+}
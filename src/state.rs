@@ -1,20 +1,61 @@
-use tatic_lib::{GameState, PlayerId};
+use tatic_lib::{Action, GameState, Phase, PlayerId};
+use serde::{Deserialize, Serialize};
 use std::{
     collections::HashMap,
+    net::IpAddr,
     sync::Arc,
 };
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, mpsc, RwLock};
 // CORREÇÃO: Importar Uuid corretamente
 use uuid::Uuid;
 
+use crate::admin::constant_time_eq;
+use crate::audit::AuditLog;
+use crate::bots::{self, Bot, BotConfig, BotCredentials, BotRegistry};
+use crate::correspondence::{MatchMode, MatchStore, PendingTurn, StoreCommand};
+use crate::error::{ApiError, Reason};
+use crate::events::{MatchEvent, MatchEvents};
+use crate::fanout::{FanoutConfig, FanoutMetrics};
+use crate::fog;
+use crate::history::{HistoryQuery, MatchHistory, MatchHistoryPage};
+use crate::i18n::Lang;
+use crate::lobby::{self, CreatedLobby, LobbyEntry, LobbyListing, LOBBY_EVENTS_BUFFER};
+use crate::match_actor::MatchHandle;
+use crate::notation::MatchDocument;
+use crate::notifier::{Notifiers, TurnNotification};
+use crate::players::{Player, PlayerRegistry, ProfileUpdate};
+use crate::rate_limit::RateLimits;
+use crate::settings::GameSettings;
+use crate::tournament::{
+    Standing, Tournament, TournamentFormat, TournamentId, TOURNAMENT_EVENTS_BUFFER,
+};
+use crate::webhooks::{WebhookEvent, Webhooks};
+use crate::websocket::HeartbeatConfig;
+
 /// ID de uma partida
 pub type MatchId = String;
 
+/// Ação aplicada numa partida
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedAction {
+    pub player_id: PlayerId,
+    pub action: Action,
+}
+
 /// Estado de uma partida
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Match {
     pub id: MatchId,
     pub state: GameState,
+    /// Estado de onde `actions` parte (o inicial, ou o último definido pelo admin)
+    pub initial_state: GameState,
+    /// Ações aplicadas desde `initial_state`, em ordem
+    pub actions: Vec<RecordedAction>,
+    /// Configurações escolhidas na criação (ou no lobby)
+    pub settings: Option<GameSettings>,
+    pub mode: MatchMode,
+    /// Quando a vez passou para o jogador atual
+    pub turn_started_at: chrono::DateTime<chrono::Utc>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
@@ -22,36 +63,180 @@ pub struct Match {
 impl Match {
     /// Cria nova partida
     pub fn new(player1: PlayerId, player2: PlayerId) -> Self {
+        // CORREÇÃO: new_v4() é um método, não new_v4
+        Self::with_id(format!("match-{}", Uuid::new_v4()), player1, player2)
+    }
+    
+    /// Cria partida com ID já reservado (ex: vinda do lobby)
+    pub fn with_id(id: MatchId, player1: PlayerId, player2: PlayerId) -> Self {
+        Self::from_state(id, GameState::new(player1, player2))
+    }
+    
+    /// Cria partida com o estado inicial montado a partir das configurações
+    pub fn configured(
+        id: MatchId,
+        player1: PlayerId,
+        player2: PlayerId,
+        settings: Option<GameSettings>,
+    ) -> Result<Self, ApiError> {
+        let Some(mut settings) = settings else {
+            return Ok(Self::with_id(id, player1, player2));
+        };
+        let initial_state = settings.initial_state(&player1, &player2)?;
+        let mut match_data = Self::from_state(id, initial_state);
+        match_data.settings = Some(settings);
+        Ok(match_data)
+    }
+    
+    /// Cria partida a partir de um estado inicial qualquer (ex: importada)
+    pub fn from_state(id: MatchId, initial_state: GameState) -> Self {
         let now = chrono::Utc::now();
         Self {
-            // CORREÇÃO: new_v4() é um método, não new_v4
-            id: format!("match-{}", Uuid::new_v4()),
-            state: GameState::new(player1, player2),
+            id,
+            state: initial_state.clone(),
+            initial_state,
+            actions: Vec::new(),
+            settings: None,
+            mode: MatchMode::Live,
+            turn_started_at: now,
             created_at: now,
             updated_at: now,
         }
     }
+    
+    /// Indica se a partida já terminou
+    pub fn is_finished(&self) -> bool {
+        is_game_over(&self.state)
+    }
+    
+    /// Indica se a partida tem neblina de guerra (`settings.fog`)
+    pub fn has_fog(&self) -> bool {
+        self.settings.as_ref().is_some_and(|s| s.fog)
+    }
+    
+    /// Estado como `viewer` o vê (`None`: espectador); ver `fog`
+    pub fn view(&self, viewer: Option<&str>) -> GameState {
+        fog::view(&self.state, self.has_fog(), viewer)
+    }
+    
+    /// Quando a vez do jogador atual será passada (correspondência ou controle de tempo)
+    pub fn turn_deadline(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        if self.is_finished() {
+            return None;
+        }
+        let limit = self
+            .mode
+            .turn_deadline()
+            .or_else(|| self.settings.as_ref()?.turn_time())?;
+        Some(self.turn_started_at + limit)
+    }
+}
+
+/// Indica se a fase do jogo é de fim de partida
+pub fn is_game_over(state: &GameState) -> bool {
+    matches!(state.phase, Phase::GameOver { .. })
+}
+
+/// Vencedor de uma partida terminada (`None` em empate ou partida em andamento)
+pub fn winner(state: &GameState) -> Option<PlayerId> {
+    match &state.phase {
+        Phase::GameOver { winner } => winner.clone(),
+        _ => None,
+    }
 }
 
 /// Estado compartilhado da aplicação
 #[derive(Clone)]
 pub struct AppState {
-    /// Partidas ativas
-    pub matches: Arc<RwLock<HashMap<MatchId, Match>>>,
-    /// Observers conectados via WebSocket
-    pub observers: Arc<RwLock<HashMap<MatchId, Vec<tokio::sync::mpsc::Sender<String>>>>>,
+    /// Partidas ativas (cada uma em sua própria task)
+    pub matches: Arc<RwLock<HashMap<MatchId, MatchHandle>>>,
+    /// Partidas aguardando segundo jogador
+    pub lobby: Arc<RwLock<HashMap<MatchId, LobbyEntry>>>,
+    /// Eventos do lobby para `/ws/lobby`
+    pub lobby_events: broadcast::Sender<String>,
+    /// Torneios em andamento e encerrados
+    pub tournaments: Arc<RwLock<HashMap<TournamentId, Tournament>>>,
+    /// Atualizações de torneios para `/ws/tournament`
+    pub tournament_events: broadcast::Sender<(TournamentId, String)>,
+    /// Eventos de ciclo de vida das partidas
+    pub match_events: MatchEvents,
+    /// Bots externos registrados e conectados
+    pub bots: Arc<RwLock<BotRegistry>>,
+    /// Idioma preferido de cada jogador
+    pub languages: Arc<RwLock<HashMap<PlayerId, Lang>>>,
+    /// Limites de uso da API
+    pub limits: RateLimits,
+    /// Configuração do broadcast para observers
+    pub fanout: FanoutConfig,
+    /// Métricas de mensagens perdidas no broadcast
+    pub fanout_metrics: Arc<FanoutMetrics>,
+    /// Heartbeat das conexões WebSocket
+    pub heartbeat: HeartbeatConfig,
+    /// Token da API `/admin` (`ADMIN_TOKEN`); sem ele a API fica desligada
+    pub admin_token: Option<String>,
+    /// Registro de auditoria
+    pub audit: Arc<AuditLog>,
+    /// Webhooks de eventos de partidas
+    pub webhooks: Arc<Webhooks>,
+    /// Partidas por correspondência em disco
+    pub correspondence: Arc<MatchStore>,
+    /// Fila da task que grava as partidas por correspondência
+    pub store_commands: mpsc::UnboundedSender<StoreCommand>,
+    /// Canais de aviso de "sua vez"
+    pub notifiers: Arc<Notifiers>,
+    /// Perfis e estatísticas dos jogadores
+    pub players: Arc<RwLock<PlayerRegistry>>,
+    /// Partidas de cada jogador
+    pub history: Arc<RwLock<MatchHistory>>,
+}
+
+impl Default for AppState {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl AppState {
     /// Cria novo estado da aplicação
     pub fn new() -> Self {
+        Self::with_store(MatchStore::from_env())
+    }
+    
+    /// Como `new`, gravando as partidas por correspondência em `store`
+    ///
+    /// As tasks de fundo clonam o estado aqui; trocar o campo depois não as afeta.
+    pub fn with_store(store: MatchStore) -> Self {
+        let webhooks = Arc::new(Webhooks::from_env());
+        let (match_events, events) = mpsc::unbounded_channel();
+        let (store_commands, commands) = mpsc::unbounded_channel();
         let state = Self {
             matches: Arc::new(RwLock::new(HashMap::new())),
-            observers: Arc::new(RwLock::new(HashMap::new())),
+            lobby: Arc::new(RwLock::new(HashMap::new())),
+            lobby_events: broadcast::channel(LOBBY_EVENTS_BUFFER).0,
+            tournaments: Arc::new(RwLock::new(HashMap::new())),
+            tournament_events: broadcast::channel(TOURNAMENT_EVENTS_BUFFER).0,
+            match_events,
+            bots: Arc::new(RwLock::new(BotRegistry::new(BotConfig::from_env()))),
+            languages: Arc::new(RwLock::new(HashMap::new())),
+            limits: RateLimits::default(),
+            fanout: FanoutConfig::from_env(),
+            fanout_metrics: Arc::new(FanoutMetrics::default()),
+            heartbeat: HeartbeatConfig::from_env(),
+            admin_token: std::env::var("ADMIN_TOKEN").ok().filter(|t| !t.is_empty()),
+            audit: Arc::new(AuditLog::from_env()),
+            notifiers: Arc::new(Notifiers::from_env(&webhooks)),
+            webhooks,
+            correspondence: Arc::new(store),
+            store_commands,
+            players: Arc::new(RwLock::new(PlayerRegistry::default())),
+            history: Arc::new(RwLock::new(MatchHistory::default())),
         };
         
         // Inicializa com partidas de exemplo
         state.init_example_matches();
+        state.spawn_event_listener(events);
+        state.spawn_store_writer(commands);
+        state.spawn_lobby_expiry();
         
         state
     }
@@ -65,67 +250,672 @@ impl AppState {
         ];
         
         // Clona para evitar bloqueio durante o loop
-        let matches_lock = self.matches.clone();
+        let state = self.clone();
         
         tokio::spawn(async move {
-            let mut matches_map = matches_lock.write().await;
+            let mut matches_map = state.matches.write().await;
             for match_data in matches {
                 tracing::info!("📋 Criando partida exemplo: {}", match_data.id);
                 let id = match_data.id.clone();
-                matches_map.insert(id, match_data);
+                let handle = state.spawn_match(match_data);
+                matches_map.insert(id, handle.clone());
+                handle.announce().await;
             }
             tracing::info!("✅ {} partidas exemplo criadas", matches_map.len());
         });
     }
     
+    /// Recria as partidas por correspondência gravadas em disco
+    ///
+    /// Chamado antes de aceitar conexões. Os atores voltam sem emitir eventos
+    /// (nada de `match_created` ou "sua vez" repetidos); perfis e histórico,
+    /// que só existem em memória, são preenchidos aqui.
+    pub async fn restore_correspondence_matches(&self) {
+        let restored = self.correspondence.load().await;
+        if restored.is_empty() {
+            return;
+        }
+        let mut matches_map = self.matches.write().await;
+        let mut players = self.players.write().await;
+        let mut history = self.history.write().await;
+        let count = restored.len();
+        for match_data in restored {
+            tracing::info!("📬 Restaurando partida por correspondência: {}", match_data.id);
+            match_data.state.players.iter().for_each(|p| players.ensure(p));
+            history.created(&match_data.id, &match_data.state.players);
+            let id = match_data.id.clone();
+            // Sem `announce`: os eventos de início já saíram quando a partida começou
+            matches_map.insert(id, self.spawn_match(match_data));
+        }
+        tracing::info!("✅ {} partidas por correspondência restauradas", count);
+    }
+    
+    /// Inicia o ator de uma partida
+    fn spawn_match(&self, match_data: Match) -> MatchHandle {
+        MatchHandle::spawn(match_data, self.fanout.buffer, self.match_events.clone())
+    }
+    
+    /// Escuta eventos de partidas e repassa aos subsistemas interessados
+    ///
+    /// Nada aqui espera por disco: as gravações vão para `spawn_store_writer`.
+    fn spawn_event_listener(&self, mut events: mpsc::UnboundedReceiver<MatchEvent>) {
+        let state = self.clone();
+        
+        tokio::spawn(async move {
+            while let Some(event) = events.recv().await {
+                state.publish_webhook(&event).await;
+                state.persist_correspondence(&event);
+                match event {
+                    MatchEvent::TurnChanged { match_id, player } => {
+                        state.on_turn_changed(&match_id, player).await;
+                    }
+                    MatchEvent::Finished(match_data) => {
+                        state.players.write().await.record_finished(&match_data);
+                        state.history.write().await.finished(&match_data);
+                        state.on_match_finished(&match_data).await;
+                    }
+                    MatchEvent::Created { match_id, players } => {
+                        let mut registry = state.players.write().await;
+                        players.iter().for_each(|p| registry.ensure(p));
+                        state.history.write().await.created(&match_id, &players);
+                    }
+                    MatchEvent::ActionApplied {
+                        match_id,
+                        turn_count,
+                        ..
+                    } => {
+                        state.history.write().await.action_applied(&match_id, turn_count);
+                    }
+                }
+            }
+        });
+    }
+    
+    /// Grava e apaga as partidas por correspondência, uma operação por vez
+    ///
+    /// A partida é lida do ator na hora de gravar, então pedidos atrasados
+    /// gravam o estado mais novo; partidas terminadas ou removidas não são
+    /// gravadas de novo.
+    fn spawn_store_writer(&self, mut commands: mpsc::UnboundedReceiver<StoreCommand>) {
+        let state = self.clone();
+        
+        tokio::spawn(async move {
+            while let Some(command) = commands.recv().await {
+                match command {
+                    StoreCommand::Save(match_id) => {
+                        let Some(handle) = state.get_handle(&match_id).await else {
+                            continue;
+                        };
+                        if !handle.mode.is_correspondence() {
+                            continue;
+                        }
+                        if let Some(match_data) = handle.snapshot().await
+                            && !match_data.is_finished()
+                        {
+                            state.correspondence.save(&match_data).await;
+                        }
+                    }
+                    StoreCommand::Remove(match_id) => state.correspondence.remove(&match_id).await,
+                }
+            }
+        });
+    }
+    
+    /// Repassa o evento aos webhooks (os de torneio só veem partidas do torneio)
+    async fn publish_webhook(&self, event: &MatchEvent) {
+        let (kind, match_id, data) = match event {
+            MatchEvent::Created { match_id, players } => (
+                WebhookEvent::MatchCreated,
+                match_id,
+                serde_json::json!({ "match_id": match_id, "players": players }),
+            ),
+            MatchEvent::ActionApplied {
+                match_id,
+                player,
+                action,
+                turn_count,
+            } => (
+                WebhookEvent::ActionApplied,
+                match_id,
+                serde_json::json!({
+                    "match_id": match_id,
+                    "player_id": player,
+                    "action": action,
+                    "turn_count": turn_count,
+                }),
+            ),
+            MatchEvent::TurnChanged { match_id, player } => (
+                WebhookEvent::TurnChanged,
+                match_id,
+                serde_json::json!({ "match_id": match_id, "player_id": player }),
+            ),
+            MatchEvent::Finished(match_data) => (
+                WebhookEvent::MatchFinished,
+                &match_data.id,
+                serde_json::json!({
+                    "match_id": match_data.id,
+                    "winner": winner(&match_data.state),
+                    "turn_count": match_data.state.turn_count,
+                }),
+            ),
+        };
+        let tournament_id = self
+            .tournaments
+            .read()
+            .await
+            .values()
+            .find(|t| t.pairings.iter().any(|p| p.match_id.as_ref() == Some(match_id)))
+            .map(|t| t.id.clone());
+        self.webhooks.dispatch(kind, tournament_id.as_deref(), data);
+    }
+    
+    /// Pede a gravação da partida por correspondência após cada mudança; terminada, apaga
+    fn persist_correspondence(&self, event: &MatchEvent) {
+        let command = match event {
+            MatchEvent::Finished(match_data) if match_data.mode.is_correspondence() => {
+                StoreCommand::Remove(match_data.id.clone())
+            }
+            MatchEvent::Finished(_) => return,
+            _ => StoreCommand::Save(event.match_id().to_string()),
+        };
+        let _ = self.store_commands.send(command);
+    }
+    
+    /// Avisa o jogador da vez (correspondência) e joga o turno de bots externos
+    async fn on_turn_changed(&self, match_id: &str, player: PlayerId) {
+        let Some(handle) = self.get_handle(match_id).await else {
+            return;
+        };
+        if handle.mode.is_correspondence() {
+            self.notify_turn(&handle, &player).await;
+        }
+        if !self.bots.read().await.is_bot(&player) {
+            return;
+        }
+        let state = self.clone();
+        tokio::spawn(async move {
+            bots::play_turn(&state.bots, &state.audit, handle, player).await;
+        });
+    }
+    
+    /// Envia o aviso de "sua vez" pelos canais ativos
+    async fn notify_turn(&self, handle: &MatchHandle, player: &PlayerId) {
+        let Some(match_data) = handle.snapshot().await else {
+            return;
+        };
+        // O turno pode ter mudado de novo antes deste evento ser tratado
+        let Some(pending) = PendingTurn::of(&match_data, player) else {
+            return;
+        };
+        let Some(deadline) = pending.deadline else {
+            return;
+        };
+        self.notifiers.notify(&TurnNotification {
+            player_id: player.clone(),
+            match_id: pending.match_id,
+            opponents: pending.opponents,
+            turn_count: pending.turn_count,
+            deadline,
+        });
+    }
+    
+    /// Turnos aguardando o jogador, os de prazo mais curto primeiro
+    pub async fn pending_turns(&self, player_id: &str) -> Vec<PendingTurn> {
+        let mut pending: Vec<_> = self
+            .list_matches()
+            .await
+            .iter()
+            .filter_map(|m| PendingTurn::of(m, player_id))
+            .collect();
+        pending.sort_by_key(|p| (p.deadline.is_none(), p.deadline, p.turn_started_at));
+        pending
+    }
+    
+    /// Registra bot externo
+    pub async fn register_bot(&self, name: String) -> Result<BotCredentials, ApiError> {
+        self.bots.write().await.register(name)
+    }
+    
+    /// Lista bots registrados
+    pub async fn list_bots(&self) -> Vec<Bot> {
+        self.bots.read().await.list()
+    }
+    
+    /// Obtém o handle do ator de uma partida
+    pub async fn get_handle(&self, match_id: &str) -> Option<MatchHandle> {
+        self.matches.read().await.get(match_id).cloned()
+    }
+    
     /// Obtém uma partida
     pub async fn get_match(&self, match_id: &str) -> Option<Match> {
-        self.matches.read().await.get(match_id).cloned()
+        self.get_handle(match_id).await?.snapshot().await
     }
     
     /// Atualiza uma partida
-    pub async fn update_match(&self, match_id: &str, new_state: GameState) {
-        let mut matches = self.matches.write().await;
-        if let Some(match_data) = matches.get_mut(match_id) {
-            match_data.state = new_state;
-            match_data.updated_at = chrono::Utc::now();
+    pub async fn update_match(&self, match_id: &str, new_state: GameState) -> Result<(), ApiError> {
+        let handle = self.get_handle(match_id).await.ok_or_else(|| ApiError::MatchNotFound {
+            match_id: match_id.to_string(),
+        })?;
+        handle.set_state(new_state).await
+    }
+    
+    /// Remove a partida e encerra seu ator
+    ///
+    /// Partida de torneio ainda em andamento conta como empate no confronto.
+    pub async fn close_match(&self, match_id: &str) -> Result<(), ApiError> {
+        let handle = self.matches.write().await.remove(match_id).ok_or_else(|| {
+            ApiError::MatchNotFound {
+                match_id: match_id.to_string(),
+            }
+        })?;
+        handle.close().await;
+        self.history.write().await.closed(match_id, &handle.players);
+        if handle.mode.is_correspondence() {
+            let _ = self.store_commands.send(StoreCommand::Remove(match_id.to_string()));
+        }
+        if !handle.is_finished() {
+            self.record_tournament_result(match_id, None).await;
         }
+        Ok(())
     }
     
     /// Cria nova partida
-    pub async fn create_match(&self, player1: PlayerId, player2: PlayerId) -> MatchId {
-        let match_data = Match::new(player1, player2);
+    ///
+    /// Falha se o IP de `origin` já atingiu o limite de partidas em andamento
+    /// ou se as configurações não puderem ser aplicadas.
+    pub async fn create_match(
+        &self,
+        player1: PlayerId,
+        player2: PlayerId,
+        mode: MatchMode,
+        settings: Option<GameSettings>,
+        origin: Option<IpAddr>,
+    ) -> Result<MatchId, ApiError> {
+        mode.validate()?;
+        if mode.is_correspondence() && settings.as_ref().is_some_and(|s| s.time_control.is_some()) {
+            return Err(ApiError::InvalidRequest {
+                reason: Reason::new("reason.time_control_correspondence"),
+            });
+        }
+        let match_id = format!("match-{}", Uuid::new_v4());
+        let mut match_data = Match::configured(match_id, player1, player2, settings)?;
+        match_data.mode = mode;
+        self.start_match(match_data, origin).await
+    }
+    
+    /// Recria uma partida exportada, reaplicando as ações do documento
+    pub async fn import_match(
+        &self,
+        document: MatchDocument,
+        origin: Option<IpAddr>,
+    ) -> Result<MatchId, ApiError> {
+        let final_state = document.replay()?;
+        
+        let match_id = format!("match-{}", Uuid::new_v4());
+        let mut match_data = Match::from_state(match_id, document.initial_state);
+        match_data.settings = document.settings;
+        match_data.actions = document
+            .actions
+            .into_iter()
+            .map(|(player_id, action)| RecordedAction { player_id, action })
+            .collect();
+        match_data.state = final_state;
+        self.start_match(match_data, origin).await
+    }
+    
+    /// Inicia a partida, respeitando o limite de partidas em andamento do IP
+    ///
+    /// Partidas abertas pelo próprio servidor (torneios, exemplos) não têm
+    /// `origin` e não contam no limite.
+    async fn start_match(
+        &self,
+        match_data: Match,
+        origin: Option<IpAddr>,
+    ) -> Result<MatchId, ApiError> {
+        if let Some(ip) = origin {
+            self.check_active_limit(ip, 1).await?;
+        }
+        Ok(self.insert_match(match_data, origin).await)
+    }
+    
+    /// Inicia o ator e registra a partida, sem conferir limites
+    ///
+    /// Os eventos de início só saem com a partida já registrada e antes que
+    /// alguém consiga mandar uma ação, senão o listener não acharia o handle.
+    async fn insert_match(&self, match_data: Match, origin: Option<IpAddr>) -> MatchId {
         let match_id = match_data.id.clone();
+        let mut handle = self.spawn_match(match_data);
+        handle.origin = origin;
+        let mut matches = self.matches.write().await;
+        matches.insert(match_id.clone(), handle.clone());
+        handle.announce().await;
+        match_id
+    }
+    
+    /// Falha se mais `starting` partidas passariam do limite de partidas e lobbies em andamento do IP
+    async fn check_active_limit(&self, ip: IpAddr, starting: usize) -> Result<(), ApiError> {
+        let limit = self.limits.max_active_matches_per_ip;
+        let hosting = self
+            .lobby
+            .read()
+            .await
+            .values()
+            .filter(|e| e.origin == Some(ip))
+            .count();
+        let playing = self
+            .matches
+            .read()
+            .await
+            .values()
+            .filter(|h| h.origin == Some(ip) && !h.is_finished())
+            .count();
+        if hosting + playing + starting > limit {
+            tracing::warn!("🚫 {} já tem {} partidas em andamento", ip, limit);
+            return Err(ApiError::TooManyMatches { limit });
+        }
+        Ok(())
+    }
+    
+    /// Abre uma partida no lobby com um assento livre
+    ///
+    /// Lobbies abertos contam no limite de partidas do IP que os abriu.
+    pub async fn create_lobby(
+        &self,
+        host: PlayerId,
+        password: Option<String>,
+        settings: Option<GameSettings>,
+        origin: Option<IpAddr>,
+    ) -> Result<CreatedLobby, ApiError> {
+        if let Some(settings) = &settings {
+            settings.validate()?;
+        }
+        if let Some(ip) = origin {
+            self.check_active_limit(ip, 1).await?;
+        }
         
-        self.matches.write().await.insert(match_id.clone(), match_data);
+        let entry = LobbyEntry::new(host, password, settings, origin);
+        let created = CreatedLobby {
+            lobby: entry.listing(),
+            host_token: entry.host_token.clone(),
+        };
+        self.lobby.write().await.insert(entry.id.clone(), entry);
         
-        match_id
+        let _ = self.lobby_events.send(lobby::created_event(&created.lobby));
+        Ok(created)
+    }
+    
+    /// Cancela uma entrada do lobby; só o anfitrião tem o token
+    pub async fn cancel_lobby(&self, lobby_id: &str, host_token: &str) -> Result<(), ApiError> {
+        let mut lobby = self.lobby.write().await;
+        let entry = lobby.get(lobby_id).ok_or_else(|| ApiError::MatchNotFound {
+            match_id: lobby_id.to_string(),
+        })?;
+        if !constant_time_eq(&entry.host_token, host_token) {
+            return Err(ApiError::Unauthorized);
+        }
+        lobby.remove(lobby_id);
+        
+        let _ = self.lobby_events.send(lobby::closed_event(lobby_id, false));
+        Ok(())
+    }
+    
+    /// Remove as entradas do lobby que passaram de `LOBBY_TTL_MINUTES`
+    pub async fn expire_lobbies(&self, now: chrono::DateTime<chrono::Utc>) -> Vec<MatchId> {
+        let mut lobby = self.lobby.write().await;
+        let expired: Vec<_> = lobby
+            .values()
+            .filter(|e| e.is_expired(now))
+            .map(|e| e.id.clone())
+            .collect();
+        for id in &expired {
+            lobby.remove(id);
+            tracing::info!("⌛ Lobby {} expirou", id);
+            let _ = self.lobby_events.send(lobby::closed_event(id, false));
+        }
+        expired
+    }
+    
+    /// Confere o lobby a cada minuto, removendo as entradas expiradas
+    fn spawn_lobby_expiry(&self) {
+        let state = self.clone();
+        
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(std::time::Duration::from_secs(60));
+            loop {
+                ticker.tick().await;
+                state.expire_lobbies(chrono::Utc::now()).await;
+            }
+        });
+    }
+    
+    /// Lista partidas abertas no lobby, das mais antigas para as mais novas
+    pub async fn list_lobby(&self) -> Vec<LobbyListing> {
+        let mut listings: Vec<_> = self.lobby.read().await.values().map(|e| e.listing()).collect();
+        listings.sort_by_key(|l| l.created_at);
+        listings
+    }
+    
+    /// Ocupa o assento livre e inicia a partida
+    pub async fn join_lobby(
+        &self,
+        lobby_id: &str,
+        player_id: PlayerId,
+        password: Option<&str>,
+    ) -> Result<MatchId, ApiError> {
+        let mut lobby = self.lobby.write().await;
+        
+        let entry = lobby.get(lobby_id).ok_or_else(|| ApiError::MatchNotFound {
+            match_id: lobby_id.to_string(),
+        })?;
+        if !entry.accepts_password(password) {
+            return Err(ApiError::Unauthorized);
+        }
+        if entry.host == player_id {
+            return Err(ApiError::Conflict {
+                reason: Reason::new("reason.already_host").arg("player_id", player_id),
+            });
+        }
+        
+        let match_data = Match::configured(
+            entry.id.clone(),
+            entry.host.clone(),
+            player_id,
+            entry.settings.clone(),
+        )?;
+        // O lobby já contava no limite de quem o abriu
+        let match_id = self.insert_match(match_data, entry.origin).await;
+        lobby.remove(lobby_id);
+        
+        let _ = self.lobby_events.send(lobby::closed_event(&match_id, true));
+        Ok(match_id)
     }
     
     /// Lista todas as partidas
-    pub async fn list_matches(&self) -> Vec<MatchId> {
-        self.matches.read().await.keys().cloned().collect()
+    ///
+    /// Os atores são consultados em paralelo, fora do lock do mapa.
+    pub async fn list_matches(&self) -> Vec<Match> {
+        let handles: Vec<_> = self.matches.read().await.values().cloned().collect();
+        futures::future::join_all(handles.iter().map(|h| h.snapshot()))
+            .await
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+    
+    /// Cria torneio e as partidas da primeira rodada
+    ///
+    /// As partidas contam no limite do IP de `origin`, que precisa comportar
+    /// a primeira rodada inteira.
+    pub async fn create_tournament(
+        &self,
+        name: String,
+        format: TournamentFormat,
+        players: Vec<PlayerId>,
+        origin: Option<IpAddr>,
+    ) -> Result<Tournament, ApiError> {
+        let mut tournament = Tournament::new(name, format, players)
+            .map_err(|reason| ApiError::InvalidRequest { reason })?;
+        tournament.origin = origin;
+        let pending = tournament.start();
+        if let Some(ip) = origin {
+            let starting = pending
+                .iter()
+                .filter(|&&i| tournament.pairings[i].player2.is_some())
+                .count();
+            self.check_active_limit(ip, starting).await?;
+        }
+        self.launch_pairings(&mut tournament, pending).await;
+        
+        self.publish_tournament(&tournament);
+        self.tournaments
+            .write()
+            .await
+            .insert(tournament.id.clone(), tournament.clone());
+        Ok(tournament)
+    }
+    
+    /// Obtém um torneio
+    pub async fn get_tournament(&self, tournament_id: &str) -> Result<Tournament, ApiError> {
+        self.tournaments
+            .read()
+            .await
+            .get(tournament_id)
+            .cloned()
+            .ok_or_else(|| ApiError::TournamentNotFound {
+                tournament_id: tournament_id.to_string(),
+            })
+    }
+    
+    /// Lista todos os torneios
+    pub async fn list_tournaments(&self) -> Vec<Tournament> {
+        let mut list: Vec<_> = self.tournaments.read().await.values().cloned().collect();
+        list.sort_by_key(|t| t.created_at);
+        list
+    }
+    
+    /// Classificação de um torneio
+    pub async fn tournament_standings(&self, tournament_id: &str) -> Result<Vec<Standing>, ApiError> {
+        Ok(self.get_tournament(tournament_id).await?.standings())
+    }
+    
+    /// Retira jogador do torneio (W.O. nos confrontos em aberto)
+    pub async fn forfeit_tournament(
+        &self,
+        tournament_id: &str,
+        player_id: &str,
+    ) -> Result<Tournament, ApiError> {
+        let mut tournaments = self.tournaments.write().await;
+        let tournament = tournaments
+            .get_mut(tournament_id)
+            .ok_or_else(|| ApiError::TournamentNotFound {
+                tournament_id: tournament_id.to_string(),
+            })?;
+        if !tournament.players.iter().any(|p| p == player_id) {
+            return Err(ApiError::InvalidRequest {
+                reason: Reason::new("reason.not_in_tournament").arg("player_id", player_id),
+            });
+        }
+        
+        let pending = tournament.forfeit(player_id);
+        self.launch_pairings(tournament, pending).await;
+        self.publish_tournament(tournament);
+        Ok(tournament.clone())
+    }
+    
+    /// Registra o resultado de uma partida de torneio e avança o chaveamento
+    async fn on_match_finished(&self, match_data: &Match) {
+        self.record_tournament_result(&match_data.id, winner(&match_data.state)).await;
+    }
+    
+    /// Resultado do confronto da partida, se ela for de um torneio
+    ///
+    /// `None` é empate; em eliminação o confronto ganha uma nova partida.
+    async fn record_tournament_result(&self, match_id: &str, winner: Option<PlayerId>) {
+        let mut tournaments = self.tournaments.write().await;
+        let Some(tournament) = tournaments
+            .values_mut()
+            .find(|t| t.pairing_for_match(match_id).is_some())
+        else {
+            return;
+        };
+        
+        tracing::info!(
+            "🏆 Torneio {}: partida {} terminou, vencedor {:?}",
+            tournament.id, match_id, winner
+        );
+        let pending = tournament.record_result(match_id, winner);
+        self.launch_pairings(tournament, pending).await;
+        self.publish_tournament(tournament);
+    }
+    
+    /// Cria as partidas dos confrontos pendentes
+    ///
+    /// Contam no limite de quem criou o torneio, mas só a primeira rodada é
+    /// conferida: um confronto sem partida deixaria o torneio parado.
+    async fn launch_pairings(&self, tournament: &mut Tournament, pending: Vec<usize>) {
+        for index in pending {
+            let pairing = &mut tournament.pairings[index];
+            let Some(player2) = pairing.player2.clone() else {
+                continue;
+            };
+            let match_data = Match::new(pairing.player1.clone(), player2);
+            pairing.match_id = Some(self.insert_match(match_data, tournament.origin).await);
+        }
+    }
+    
+    /// Envia o torneio atualizado para `/ws/tournament`
+    fn publish_tournament(&self, tournament: &Tournament) {
+        let event = serde_json::json!({
+            "type": "tournament_update",
+            "tournament": tournament,
+            "standings": tournament.standings(),
+        });
+        let _ = self
+            .tournament_events
+            .send((tournament.id.clone(), event.to_string()));
+    }
+    
+    /// Perfil e estatísticas do jogador
+    pub async fn get_player(&self, player_id: &str) -> Result<Player, ApiError> {
+        self.players.read().await.get(player_id)
+    }
+    
+    /// Página do histórico de partidas do jogador
+    pub async fn player_matches(&self, player_id: &str, query: &HistoryQuery) -> MatchHistoryPage {
+        self.history.read().await.page(player_id, query)
+    }
+    
+    /// Cria ou altera o perfil do jogador
+    pub async fn update_player(
+        &self,
+        player_id: &str,
+        update: ProfileUpdate,
+    ) -> Result<Player, ApiError> {
+        self.players.write().await.update(player_id, update)
+    }
+    
+    /// Obtém idioma preferido do jogador
+    pub async fn get_language(&self, player_id: &str) -> Option<Lang> {
+        self.languages.read().await.get(player_id).copied()
+    }
+    
+    /// Define idioma preferido do jogador
+    pub async fn set_language(&self, player_id: PlayerId, lang: Lang) {
+        self.languages.write().await.insert(player_id, lang);
     }
     
     /// Notifica observers via WebSocket
     pub async fn notify_observers(&self, match_id: &str, message: String) {
-        let observers = self.observers.read().await;
-        
-        if let Some(senders) = observers.get(match_id) {
-            // Envia para todos os observers
-            for sender in senders {
-                let _ = sender.send(message.clone()).await;
-            }
+        if let Some(handle) = self.get_handle(match_id).await {
+            handle.broadcast(message).await;
         }
     }
     
-    /// Adiciona observer
+    /// Adiciona observer, retornando o estado atual e o receptor de updates
     pub async fn add_observer(
         &self,
-        match_id: String,
-        sender: tokio::sync::mpsc::Sender<String>,
-    ) {
-        let mut observers = self.observers.write().await;
-        observers.entry(match_id).or_insert_with(Vec::new).push(sender);
+        match_id: &str,
+    ) -> Option<(Match, tokio::sync::broadcast::Receiver<String>)> {
+        self.get_handle(match_id).await?.subscribe().await
     }
 }
@@ -0,0 +1,478 @@
+//! Torneios: eliminação simples, eliminação dupla, suíço e todos contra todos
+//!
+//! Esta parte é só a lógica do chaveamento. Quem cria as partidas e registra
+//! os resultados é o `AppState`, a partir dos eventos `MatchEvent::Finished`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::net::IpAddr;
+use tatic_lib::PlayerId;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::Reason;
+use crate::state::MatchId;
+
+/// ID de um torneio
+pub type TournamentId = String;
+
+/// Capacidade do canal de eventos de torneios
+pub const TOURNAMENT_EVENTS_BUFFER: usize = 100;
+
+/// Máximo de jogadores por torneio
+pub const MAX_TOURNAMENT_PLAYERS: usize = 64;
+
+/// Formato do torneio
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TournamentFormat {
+    SingleElimination,
+    DoubleElimination,
+    /// `rounds` padrão: log2 do número de jogadores, arredondado para cima
+    Swiss { rounds: Option<u32> },
+    RoundRobin,
+}
+
+/// Situação do torneio
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TournamentStatus {
+    Running,
+    Finished,
+}
+
+/// Resultado de um confronto
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PairingResult {
+    /// `forfeit` indica W.O. (desistência)
+    Win {
+        #[schema(value_type = String)]
+        winner: PlayerId,
+        forfeit: bool,
+    },
+    Draw,
+}
+
+/// Confronto de uma rodada
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Pairing {
+    pub round: u32,
+    #[schema(value_type = String)]
+    pub player1: PlayerId,
+    /// `None` é folga (bye): `player1` avança sem jogar
+    #[schema(value_type = Option<String>)]
+    pub player2: Option<PlayerId>,
+    pub match_id: Option<MatchId>,
+    pub result: Option<PairingResult>,
+}
+
+impl Pairing {
+    fn involves(&self, player: &str) -> bool {
+        self.player1 == player || self.player2.as_deref() == Some(player)
+    }
+
+    fn opponent(&self, player: &str) -> Option<&PlayerId> {
+        if self.player1 == player {
+            self.player2.as_ref()
+        } else {
+            Some(&self.player1)
+        }
+    }
+
+    fn lost_by(&self, player: &str) -> bool {
+        matches!(&self.result, Some(PairingResult::Win { winner, .. }) if winner != player)
+            && self.involves(player)
+    }
+}
+
+/// Classificação de um jogador
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Standing {
+    #[schema(value_type = String)]
+    pub player: PlayerId,
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+    pub byes: u32,
+    /// Vitória vale 1, empate 0.5
+    pub points: f32,
+    pub withdrawn: bool,
+}
+
+/// Torneio com todo o chaveamento (serializado como o bracket)
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Tournament {
+    pub id: TournamentId,
+    pub name: String,
+    pub format: TournamentFormat,
+    /// Na ordem de seed
+    #[schema(value_type = Vec<String>)]
+    pub players: Vec<PlayerId>,
+    pub status: TournamentStatus,
+    pub round: u32,
+    pub pairings: Vec<Pairing>,
+    #[schema(value_type = Vec<String>)]
+    pub withdrawn: Vec<PlayerId>,
+    #[schema(value_type = Option<String>)]
+    pub champion: Option<PlayerId>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// IP de quem criou o torneio; as partidas contam no limite dele
+    #[serde(skip)]
+    pub origin: Option<IpAddr>,
+}
+
+impl Tournament {
+    /// Cria torneio; precisa de 2 a `MAX_TOURNAMENT_PLAYERS` jogadores distintos
+    pub fn new(
+        name: String,
+        format: TournamentFormat,
+        players: Vec<PlayerId>,
+    ) -> Result<Self, Reason> {
+        let mut seen = HashSet::new();
+        if let Some(dup) = players.iter().find(|p| !seen.insert(p.as_str())) {
+            return Err(Reason::new("reason.duplicate_player").arg("player_id", dup));
+        }
+        if players.len() < 2 {
+            return Err(Reason::new("reason.tournament_min_players"));
+        }
+        if players.len() > MAX_TOURNAMENT_PLAYERS {
+            return Err(Reason::new("reason.tournament_max_players").arg("max", MAX_TOURNAMENT_PLAYERS));
+        }
+        if let TournamentFormat::Swiss { rounds: Some(0) } = format {
+            return Err(Reason::new("reason.swiss_rounds"));
+        }
+
+        Ok(Self {
+            id: format!("tournament-{}", Uuid::new_v4()),
+            name,
+            format,
+            players,
+            status: TournamentStatus::Running,
+            round: 0,
+            pairings: Vec::new(),
+            withdrawn: Vec::new(),
+            champion: None,
+            created_at: chrono::Utc::now(),
+            origin: None,
+        })
+    }
+
+    /// Gera a primeira rodada
+    ///
+    /// Retorna os índices dos confrontos que precisam de partida.
+    pub fn start(&mut self) -> Vec<usize> {
+        self.advance()
+    }
+
+    /// Índice do confronto em aberto ligado à partida
+    pub fn pairing_for_match(&self, match_id: &str) -> Option<usize> {
+        self.pairings
+            .iter()
+            .position(|p| p.result.is_none() && p.match_id.as_deref() == Some(match_id))
+    }
+
+    /// Registra o resultado da partida de um confronto
+    ///
+    /// Empate em eliminação gera uma nova partida entre os mesmos jogadores.
+    /// Retorna os índices dos confrontos que precisam de partida.
+    pub fn record_result(&mut self, match_id: &str, winner: Option<PlayerId>) -> Vec<usize> {
+        let Some(index) = self.pairing_for_match(match_id) else {
+            return Vec::new();
+        };
+        let pairing = &mut self.pairings[index];
+        let winner = winner.filter(|w| pairing.involves(w));
+
+        match winner {
+            Some(winner) => {
+                pairing.result = Some(PairingResult::Win {
+                    winner,
+                    forfeit: false,
+                });
+            }
+            None => {
+                pairing.result = Some(PairingResult::Draw);
+                if self.is_elimination() {
+                    let replay = Pairing {
+                        match_id: None,
+                        result: None,
+                        ..self.pairings[index].clone()
+                    };
+                    self.pairings.push(replay);
+                    return vec![self.pairings.len() - 1];
+                }
+            }
+        }
+
+        self.advance_if_round_complete()
+    }
+
+    /// Retira o jogador: confrontos em aberto viram W.O. para o adversário
+    ///
+    /// Retorna os índices dos confrontos que precisam de partida.
+    pub fn forfeit(&mut self, player: &str) -> Vec<usize> {
+        if self.withdrawn.iter().any(|w| w == player) {
+            return Vec::new();
+        }
+        self.withdrawn.push(player.to_string());
+
+        for pairing in self.pairings.iter_mut() {
+            if pairing.result.is_none() && pairing.involves(player) {
+                pairing.result = Some(match pairing.opponent(player) {
+                    Some(opponent) => PairingResult::Win {
+                        winner: opponent.clone(),
+                        forfeit: true,
+                    },
+                    None => PairingResult::Draw,
+                });
+            }
+        }
+
+        self.advance_if_round_complete()
+    }
+
+    /// Classificação atual, da melhor para a pior
+    pub fn standings(&self) -> Vec<Standing> {
+        let mut standings: Vec<Standing> = self
+            .players
+            .iter()
+            .map(|player| {
+                let mut standing = Standing {
+                    player: player.clone(),
+                    wins: 0,
+                    losses: 0,
+                    draws: 0,
+                    byes: 0,
+                    points: 0.0,
+                    withdrawn: self.withdrawn.contains(player),
+                };
+                for pairing in self.pairings.iter().filter(|p| p.involves(player)) {
+                    match &pairing.result {
+                        Some(PairingResult::Win { winner, .. }) if winner == player => {
+                            standing.wins += 1;
+                            if pairing.player2.is_none() {
+                                standing.byes += 1;
+                            }
+                        }
+                        Some(PairingResult::Win { .. }) => standing.losses += 1,
+                        Some(PairingResult::Draw) => standing.draws += 1,
+                        None => {}
+                    }
+                }
+                standing.points = standing.wins as f32 + standing.draws as f32 * 0.5;
+                standing
+            })
+            .collect();
+
+        // Ordenação estável: empates mantêm a ordem de seed
+        standings.sort_by(|a, b| {
+            b.points
+                .total_cmp(&a.points)
+                .then(b.wins.cmp(&a.wins))
+                .then(a.losses.cmp(&b.losses))
+        });
+        standings
+    }
+
+    fn is_elimination(&self) -> bool {
+        matches!(
+            self.format,
+            TournamentFormat::SingleElimination | TournamentFormat::DoubleElimination
+        )
+    }
+
+    fn losses(&self, player: &str) -> usize {
+        self.pairings.iter().filter(|p| p.lost_by(player)).count()
+    }
+
+    fn byes(&self, player: &str) -> usize {
+        self.pairings
+            .iter()
+            .filter(|p| p.player2.is_none() && p.player1 == player)
+            .count()
+    }
+
+    fn played(&self, a: &str, b: &str) -> bool {
+        self.pairings
+            .iter()
+            .any(|p| p.involves(a) && p.involves(b) && p.player2.is_some())
+    }
+
+    /// Jogadores ainda na disputa, na ordem de seed
+    fn active_players(&self) -> Vec<PlayerId> {
+        let max_losses = match self.format {
+            TournamentFormat::SingleElimination => 1,
+            TournamentFormat::DoubleElimination => 2,
+            TournamentFormat::Swiss { .. } | TournamentFormat::RoundRobin => usize::MAX,
+        };
+        self.players
+            .iter()
+            .filter(|p| !self.withdrawn.contains(p) && self.losses(p) < max_losses)
+            .cloned()
+            .collect()
+    }
+
+    fn total_rounds(&self) -> Option<u32> {
+        match self.format {
+            TournamentFormat::Swiss { rounds } => Some(rounds.unwrap_or_else(|| {
+                self.players.len().next_power_of_two().trailing_zeros().max(1)
+            })),
+            TournamentFormat::RoundRobin => {
+                let n = self.players.len() + self.players.len() % 2;
+                Some(n as u32 - 1)
+            }
+            _ => None,
+        }
+    }
+
+    fn advance_if_round_complete(&mut self) -> Vec<usize> {
+        let open = self
+            .pairings
+            .iter()
+            .any(|p| p.round == self.round && p.result.is_none());
+        if open || self.status == TournamentStatus::Finished {
+            return Vec::new();
+        }
+        self.advance()
+    }
+
+    /// Encerra o torneio ou gera a próxima rodada
+    ///
+    /// Rodadas resolvidas só com folgas e W.O. são puladas.
+    fn advance(&mut self) -> Vec<usize> {
+        loop {
+            let active = self.active_players();
+            let rounds_over = self.total_rounds().is_some_and(|total| self.round >= total);
+            if active.len() < 2 || rounds_over {
+                self.status = TournamentStatus::Finished;
+                self.champion = if self.is_elimination() {
+                    active.into_iter().next()
+                } else {
+                    self.standings()
+                        .into_iter()
+                        .find(|s| !s.withdrawn)
+                        .map(|s| s.player)
+                };
+                return Vec::new();
+            }
+
+            self.round += 1;
+            let first_new = self.pairings.len();
+            for (player1, player2) in self.pair_round(active) {
+                let result = match (&player2, self.withdrawn.contains(&player1)) {
+                    (None, _) => Some(PairingResult::Win {
+                        winner: player1.clone(),
+                        forfeit: false,
+                    }),
+                    (Some(p2), true) if self.withdrawn.contains(p2) => Some(PairingResult::Draw),
+                    (Some(p2), true) => Some(PairingResult::Win {
+                        winner: p2.clone(),
+                        forfeit: true,
+                    }),
+                    (Some(p2), false) if self.withdrawn.contains(p2) => Some(PairingResult::Win {
+                        winner: player1.clone(),
+                        forfeit: true,
+                    }),
+                    _ => None,
+                };
+                self.pairings.push(Pairing {
+                    round: self.round,
+                    player1,
+                    player2,
+                    match_id: None,
+                    result,
+                });
+            }
+
+            let needs_match: Vec<usize> = (first_new..self.pairings.len())
+                .filter(|&i| self.pairings[i].result.is_none())
+                .collect();
+            if !needs_match.is_empty() {
+                return needs_match;
+            }
+        }
+    }
+
+    /// Confrontos da próxima rodada conforme o formato
+    fn pair_round(&self, active: Vec<PlayerId>) -> Vec<(PlayerId, Option<PlayerId>)> {
+        match self.format {
+            TournamentFormat::SingleElimination => self.pair_in_order(active),
+            TournamentFormat::DoubleElimination => {
+                let (winners, losers): (Vec<_>, Vec<_>) =
+                    active.into_iter().partition(|p| self.losses(p) == 0);
+                if winners.len() == 1 && losers.len() == 1 {
+                    // Grande final
+                    return vec![(winners[0].clone(), Some(losers[0].clone()))];
+                }
+                let mut pairs = self.pair_in_order(winners);
+                pairs.extend(self.pair_in_order(losers));
+                pairs
+            }
+            TournamentFormat::Swiss { .. } => self.pair_swiss(),
+            TournamentFormat::RoundRobin => self.pair_round_robin(),
+        }
+    }
+
+    /// Pares consecutivos; com número ímpar, folga para quem teve menos folgas
+    fn pair_in_order(&self, mut players: Vec<PlayerId>) -> Vec<(PlayerId, Option<PlayerId>)> {
+        let bye = self.take_bye(&mut players);
+        let mut pairs: Vec<_> = players
+            .chunks(2)
+            .map(|pair| (pair[0].clone(), Some(pair[1].clone())))
+            .collect();
+        pairs.extend(bye.map(|p| (p, None)));
+        pairs
+    }
+
+    /// Remove o jogador que fica de folga (o último com menos folgas)
+    fn take_bye(&self, players: &mut Vec<PlayerId>) -> Option<PlayerId> {
+        if players.len().is_multiple_of(2) {
+            return None;
+        }
+        let index = (0..players.len())
+            .rev()
+            .min_by_key(|&i| self.byes(&players[i]))?;
+        Some(players.remove(index))
+    }
+
+    /// Suíço: ordena por pontos e evita revanches quando possível
+    fn pair_swiss(&self) -> Vec<(PlayerId, Option<PlayerId>)> {
+        let mut ranked: Vec<PlayerId> = self
+            .standings()
+            .into_iter()
+            .filter(|s| !s.withdrawn)
+            .map(|s| s.player)
+            .collect();
+        let bye = self.take_bye(&mut ranked);
+
+        let mut pairs = Vec::new();
+        while !ranked.is_empty() {
+            let player = ranked.remove(0);
+            let opponent = ranked
+                .iter()
+                .position(|o| !self.played(&player, o))
+                .unwrap_or(0);
+            pairs.push((player, Some(ranked.remove(opponent))));
+        }
+        pairs.extend(bye.map(|p| (p, None)));
+        pairs
+    }
+
+    /// Todos contra todos pelo método do círculo
+    fn pair_round_robin(&self) -> Vec<(PlayerId, Option<PlayerId>)> {
+        let mut seats: Vec<Option<PlayerId>> = self.players.iter().cloned().map(Some).collect();
+        if seats.len() % 2 == 1 {
+            seats.push(None);
+        }
+        let n = seats.len();
+        seats[1..].rotate_right((self.round as usize - 1) % (n - 1));
+
+        (0..n / 2)
+            .filter_map(|i| match (seats[i].clone(), seats[n - 1 - i].clone()) {
+                (Some(a), b) => Some((a, b)),
+                (None, Some(b)) => Some((b, None)),
+                (None, None) => None,
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,482 @@
+//! Webhooks de eventos de partidas
+//!
+//! Cada webhook recebe um `POST` com o evento em JSON para os eventos que
+//! assinou (todos, se a lista for vazia). Webhooks de torneio só recebem
+//! eventos das partidas do torneio. O corpo é assinado com HMAC-SHA256 do
+//! segredo do webhook no header `X-Tatic-Signature: sha256=<hex>`.
+//!
+//! Entregas que falham são repetidas com espera exponencial; esgotadas as
+//! tentativas, vão para a fila de falhas (dead letters), de onde podem ser
+//! reenviadas. Toda tentativa fica no registro de entregas. Há no máximo
+//! `MAX_PENDING_DELIVERIES` entregas em andamento; as que passam disso vão
+//! direto para a fila de falhas.
+
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio::sync::Semaphore;
+use tracing::{info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{ApiError, Reason};
+use crate::tournament::TournamentId;
+
+/// Header com o nome do evento
+pub const EVENT_HEADER: &str = "x-tatic-event";
+/// Header com o ID da entrega (o mesmo em todas as tentativas)
+pub const DELIVERY_HEADER: &str = "x-tatic-delivery";
+/// Header com a assinatura do corpo
+pub const SIGNATURE_HEADER: &str = "x-tatic-signature";
+
+/// Tentativas guardadas no registro de entregas
+const MAX_LOG_ENTRIES: usize = 1000;
+/// Entregas guardadas na fila de falhas
+const MAX_DEAD_LETTERS: usize = 1000;
+/// Entregas em andamento (tentando ou esperando a próxima tentativa)
+const MAX_PENDING_DELIVERIES: usize = 256;
+/// Limite de `WEBHOOK_MAX_ATTEMPTS`
+const MAX_ATTEMPTS: u32 = 20;
+/// Espera máxima entre duas tentativas
+const MAX_BACKOFF: Duration = Duration::from_secs(3600);
+
+/// Eventos que podem ser assinados
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    MatchCreated,
+    ActionApplied,
+    TurnChanged,
+    MatchFinished,
+    /// Vez do jogador numa partida por correspondência
+    YourTurn,
+}
+
+/// Tentativas e prazos das entregas
+#[derive(Debug, Clone, Copy)]
+pub struct WebhookConfig {
+    pub max_attempts: u32,
+    /// Espera antes da segunda tentativa; dobra a cada nova falha, até `MAX_BACKOFF`
+    pub backoff: Duration,
+    /// Prazo de cada requisição
+    pub timeout: Duration,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            backoff: Duration::from_secs(1),
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+impl WebhookConfig {
+    /// Lê `WEBHOOK_MAX_ATTEMPTS` (até `MAX_ATTEMPTS`), `WEBHOOK_BACKOFF_MS` e `WEBHOOK_TIMEOUT_SECS`
+    pub fn from_env() -> Self {
+        let default = Self::default();
+        let env = |name| std::env::var(name).ok().and_then(|v| v.parse::<u64>().ok());
+        Self {
+            max_attempts: env("WEBHOOK_MAX_ATTEMPTS")
+                .filter(|&n| n > 0)
+                .map(|n| n.min(MAX_ATTEMPTS.into()) as u32)
+                .unwrap_or(default.max_attempts),
+            backoff: env("WEBHOOK_BACKOFF_MS")
+                .map(Duration::from_millis)
+                .unwrap_or(default.backoff),
+            timeout: env("WEBHOOK_TIMEOUT_SECS")
+                .filter(|&secs| secs > 0)
+                .map(Duration::from_secs)
+                .unwrap_or(default.timeout),
+        }
+    }
+
+    /// Espera antes da tentativa `attempt` (a partir de 2)
+    pub fn backoff_before(&self, attempt: u32) -> Duration {
+        2u32.checked_pow(attempt.saturating_sub(2))
+            .and_then(|factor| self.backoff.checked_mul(factor))
+            .map_or(MAX_BACKOFF, |wait| wait.min(MAX_BACKOFF))
+    }
+}
+
+/// Webhook cadastrado
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Webhook {
+    /// `hook-<uuid>`
+    pub id: String,
+    pub url: String,
+    /// Vazia: todos os eventos
+    pub events: Vec<WebhookEvent>,
+    /// Só eventos das partidas deste torneio
+    pub tournament_id: Option<TournamentId>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl Webhook {
+    fn wants(&self, event: WebhookEvent, tournament_id: Option<&str>) -> bool {
+        (self.events.is_empty() || self.events.contains(&event))
+            && self
+                .tournament_id
+                .as_deref()
+                .is_none_or(|id| Some(id) == tournament_id)
+    }
+}
+
+/// Webhook recém-cadastrado; o segredo só aparece aqui
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct WebhookCredentials {
+    #[serde(flatten)]
+    pub webhook: Webhook,
+    pub secret: String,
+}
+
+/// Uma tentativa de entrega
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DeliveryAttempt {
+    pub delivery_id: String,
+    pub webhook_id: String,
+    pub event: WebhookEvent,
+    /// A partir de 1
+    pub attempt: u32,
+    pub at: chrono::DateTime<chrono::Utc>,
+    /// Status HTTP da resposta (ausente em erro de conexão ou prazo)
+    pub status: Option<u16>,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+/// Entrega que esgotou as tentativas
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DeadLetter {
+    pub delivery_id: String,
+    pub webhook_id: String,
+    pub event: WebhookEvent,
+    #[schema(value_type = Object)]
+    pub payload: Value,
+    pub attempts: u32,
+    pub last_error: String,
+    pub failed_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Uma entrega a fazer
+#[derive(Clone)]
+struct Job {
+    delivery_id: String,
+    webhook_id: String,
+    event: WebhookEvent,
+    payload: Value,
+}
+
+/// Webhooks cadastrados, registro de entregas e fila de falhas
+pub struct Webhooks {
+    config: WebhookConfig,
+    client: reqwest::Client,
+    hooks: Mutex<HashMap<String, WebhookCredentials>>,
+    log: Mutex<VecDeque<DeliveryAttempt>>,
+    dead_letters: Mutex<VecDeque<DeadLetter>>,
+    /// Vagas para entregas em andamento; sem vaga, a entrega vai direto para a fila de falhas
+    pending: Arc<Semaphore>,
+}
+
+impl Webhooks {
+    pub fn new(config: WebhookConfig) -> Self {
+        Self {
+            config,
+            client: reqwest::Client::new(),
+            hooks: Mutex::new(HashMap::new()),
+            log: Mutex::new(VecDeque::new()),
+            dead_letters: Mutex::new(VecDeque::new()),
+            pending: Arc::new(Semaphore::new(MAX_PENDING_DELIVERIES)),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(WebhookConfig::from_env())
+    }
+
+    /// Cadastra webhook; sem `secret`, um é gerado
+    pub fn register(
+        &self,
+        url: String,
+        secret: Option<String>,
+        events: Vec<WebhookEvent>,
+        tournament_id: Option<TournamentId>,
+    ) -> Result<WebhookCredentials, ApiError> {
+        let valid = reqwest::Url::parse(&url)
+            .is_ok_and(|u| matches!(u.scheme(), "http" | "https") && u.has_host());
+        if !valid {
+            return Err(ApiError::InvalidRequest {
+                reason: Reason::new("reason.webhook_url").arg("url", &url),
+            });
+        }
+        let secret = match secret {
+            Some(secret) if secret.is_empty() => {
+                return Err(ApiError::InvalidRequest {
+                    reason: Reason::new("reason.webhook_secret"),
+                });
+            }
+            Some(secret) => secret,
+            None => Uuid::new_v4().simple().to_string(),
+        };
+
+        let credentials = WebhookCredentials {
+            webhook: Webhook {
+                id: format!("hook-{}", Uuid::new_v4()),
+                url,
+                events,
+                tournament_id,
+                created_at: chrono::Utc::now(),
+            },
+            secret,
+        };
+        info!(
+            "🪝 Webhook cadastrado: {} -> {}",
+            credentials.webhook.id, credentials.webhook.url
+        );
+        self.hooks
+            .lock()
+            .unwrap()
+            .insert(credentials.webhook.id.clone(), credentials.clone());
+        Ok(credentials)
+    }
+
+    /// Webhooks cadastrados, dos mais antigos para os mais novos
+    pub fn list(&self) -> Vec<Webhook> {
+        let mut hooks: Vec<_> = self
+            .hooks
+            .lock()
+            .unwrap()
+            .values()
+            .map(|c| c.webhook.clone())
+            .collect();
+        hooks.sort_by_key(|h| h.created_at);
+        hooks
+    }
+
+    /// Remove o webhook; entregas em andamento param na próxima tentativa
+    pub fn remove(&self, webhook_id: &str) -> Result<(), ApiError> {
+        self.hooks
+            .lock()
+            .unwrap()
+            .remove(webhook_id)
+            .map(|_| ())
+            .ok_or_else(|| ApiError::WebhookNotFound {
+                webhook_id: webhook_id.to_string(),
+            })
+    }
+
+    /// Tentativas do webhook, das mais antigas para as mais novas
+    pub fn deliveries(&self, webhook_id: &str) -> Result<Vec<DeliveryAttempt>, ApiError> {
+        if !self.hooks.lock().unwrap().contains_key(webhook_id) {
+            return Err(ApiError::WebhookNotFound {
+                webhook_id: webhook_id.to_string(),
+            });
+        }
+        Ok(self
+            .log
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|a| a.webhook_id == webhook_id)
+            .cloned()
+            .collect())
+    }
+
+    pub fn dead_letters(&self) -> Vec<DeadLetter> {
+        self.dead_letters.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// Tira a entrega da fila de falhas e tenta de novo
+    pub fn retry_dead_letter(self: &Arc<Self>, delivery_id: &str) -> Result<(), ApiError> {
+        let letter = {
+            let mut dead_letters = self.dead_letters.lock().unwrap();
+            let index = dead_letters
+                .iter()
+                .position(|d| d.delivery_id == delivery_id)
+                .ok_or_else(|| ApiError::Conflict {
+                    reason: Reason::new("reason.dead_letter_missing")
+                        .arg("delivery_id", delivery_id),
+                })?;
+            dead_letters.remove(index).expect("índice encontrado acima")
+        };
+        if !self.hooks.lock().unwrap().contains_key(&letter.webhook_id) {
+            return Err(ApiError::WebhookNotFound {
+                webhook_id: letter.webhook_id,
+            });
+        }
+        self.spawn_delivery(Job {
+            delivery_id: letter.delivery_id,
+            webhook_id: letter.webhook_id,
+            event: letter.event,
+            payload: letter.payload,
+        });
+        Ok(())
+    }
+
+    /// Envia o evento a todos os webhooks interessados, em segundo plano
+    pub fn dispatch(
+        self: &Arc<Self>,
+        event: WebhookEvent,
+        tournament_id: Option<&str>,
+        data: Value,
+    ) {
+        let targets: Vec<String> = self
+            .hooks
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|c| c.webhook.wants(event, tournament_id))
+            .map(|c| c.webhook.id.clone())
+            .collect();
+
+        for webhook_id in targets {
+            let delivery_id = Uuid::new_v4().to_string();
+            let payload = serde_json::json!({
+                "id": delivery_id,
+                "event": event,
+                "at": chrono::Utc::now(),
+                "data": data,
+            });
+            self.spawn_delivery(Job {
+                delivery_id,
+                webhook_id,
+                event,
+                payload,
+            });
+        }
+    }
+
+    /// Inicia a entrega se houver vaga
+    fn spawn_delivery(self: &Arc<Self>, job: Job) {
+        let Ok(permit) = self.pending.clone().try_acquire_owned() else {
+            warn!("🪝 Entregas demais em andamento; {} vai para a fila de falhas", job.delivery_id);
+            self.bury(job, 0, "entregas demais em andamento".to_string());
+            return;
+        };
+        let webhooks = self.clone();
+        tokio::spawn(async move {
+            webhooks.deliver(job).await;
+            drop(permit);
+        });
+    }
+
+    /// Tenta entregar até esgotar as tentativas
+    async fn deliver(&self, job: Job) {
+        let body = job.payload.to_string();
+        let mut last_error = String::new();
+
+        for attempt in 1..=self.config.max_attempts {
+            if attempt > 1 {
+                tokio::time::sleep(self.config.backoff_before(attempt)).await;
+            }
+            // URL e segredo lidos a cada tentativa: o webhook pode ter sido removido
+            let Some((url, secret)) = self
+                .hooks
+                .lock()
+                .unwrap()
+                .get(&job.webhook_id)
+                .map(|c| (c.webhook.url.clone(), c.secret.clone()))
+            else {
+                return;
+            };
+
+            let signature = format!("sha256={}", sign(secret.as_bytes(), body.as_bytes()));
+            let started = Instant::now();
+            let result = self
+                .client
+                .post(&url)
+                .timeout(self.config.timeout)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(EVENT_HEADER, event_name(job.event))
+                .header(DELIVERY_HEADER, &job.delivery_id)
+                .header(SIGNATURE_HEADER, signature)
+                .body(body.clone())
+                .send()
+                .await;
+
+            let (status, error) = match &result {
+                Ok(response) if response.status().is_success() => (Some(response.status()), None),
+                Ok(response) => {
+                    (Some(response.status()), Some(format!("HTTP {}", response.status())))
+                }
+                Err(e) => (None, Some(e.to_string())),
+            };
+            self.log_attempt(DeliveryAttempt {
+                delivery_id: job.delivery_id.clone(),
+                webhook_id: job.webhook_id.clone(),
+                event: job.event,
+                attempt,
+                at: chrono::Utc::now(),
+                status: status.map(|s| s.as_u16()),
+                error: error.clone(),
+                duration_ms: started.elapsed().as_millis() as u64,
+            });
+
+            match error {
+                None => return,
+                Some(error) => {
+                    warn!(
+                        "🪝 Entrega {} falhou (tentativa {}): {}",
+                        job.delivery_id, attempt, error
+                    );
+                    last_error = error;
+                }
+            }
+        }
+
+        warn!("🪦 Entrega {} foi para a fila de falhas", job.delivery_id);
+        self.bury(job, self.config.max_attempts, last_error);
+    }
+
+    /// Guarda a entrega na fila de falhas
+    fn bury(&self, job: Job, attempts: u32, last_error: String) {
+        let mut dead_letters = self.dead_letters.lock().unwrap();
+        if dead_letters.len() == MAX_DEAD_LETTERS {
+            dead_letters.pop_front();
+        }
+        dead_letters.push_back(DeadLetter {
+            delivery_id: job.delivery_id,
+            webhook_id: job.webhook_id,
+            event: job.event,
+            payload: job.payload,
+            attempts,
+            last_error,
+            failed_at: chrono::Utc::now(),
+        });
+    }
+
+    fn log_attempt(&self, attempt: DeliveryAttempt) {
+        let mut log = self.log.lock().unwrap();
+        if log.len() == MAX_LOG_ENTRIES {
+            log.pop_front();
+        }
+        log.push_back(attempt);
+    }
+}
+
+/// Nome do evento como aparece no JSON
+fn event_name(event: WebhookEvent) -> String {
+    serde_json::to_value(event)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+/// HMAC-SHA256 em hexadecimal
+pub fn sign(secret: &[u8], body: &[u8]) -> String {
+    // HMAC aceita chave de qualquer tamanho
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC aceita qualquer chave");
+    mac.update(body);
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
@@ -1,28 +1,202 @@
 use axum::{
+    body::Bytes,
     extract::{
         ws::{Message, WebSocket, WebSocketUpgrade},
-        Query, State,
+        ConnectInfo, Query, RawQuery, State,
     },
+    http::HeaderMap,
     response::Response,
     routing::get,
-    Router,
+    Extension, Router,
 };
 use serde::Deserialize;
-use tracing::{error, info};
-// IMPORTANTE: Importar StreamExt e SinkExt
-use futures_util::{SinkExt, StreamExt};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+use tatic_lib::{Action, GameState};
+use tokio::sync::{broadcast::error::RecvError, mpsc, oneshot};
+use tracing::{error, info, warn};
 
-use crate::state::AppState;
+use crate::audit::Who;
+use crate::bots::{BotAction, BOT_QUEUE_SIZE};
+use crate::error::{ApiError, ErrorResponse, Reason};
+use crate::fanout::LagPolicy;
+use crate::fog;
+use crate::i18n::{self, translate, Lang};
+use crate::replay::{Playback, ReplayControl};
+use crate::state::{AppState, MatchId};
+use crate::tournament::Tournament;
 
 #[derive(Deserialize)]
 struct WsQuery {
     match_id: String,
+    /// Jogador que observa; com `fog`, define o que é visível
+    player_id: Option<String>,
 }
 
+#[derive(Deserialize)]
+struct ReplayWsQuery {
+    match_id: String,
+    /// Estados por segundo (padrão 1)
+    speed: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct TournamentWsQuery {
+    tournament_id: String,
+}
+
+#[derive(Deserialize)]
+struct BotWsQuery {
+    token: String,
+}
+
+/// Mensagens enviadas pelos bots
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum BotMessage {
+    /// Resposta a um `your_turn`
+    Action { match_id: MatchId, action: Action },
+}
+
+/// Configuração do heartbeat das conexões WebSocket
+#[derive(Debug, Clone, Copy)]
+pub struct HeartbeatConfig {
+    /// Intervalo entre pings do servidor
+    pub interval: Duration,
+    /// Pings sem resposta antes de desconectar
+    pub max_missed: u32,
+}
+
+impl Default for HeartbeatConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(15),
+            max_missed: 3,
+        }
+    }
+}
+
+impl HeartbeatConfig {
+    /// Lê `WS_PING_INTERVAL_SECS` e `WS_MAX_MISSED_PINGS`
+    pub fn from_env() -> Self {
+        let default = Self::default();
+        let env = |name| std::env::var(name).ok().and_then(|v| v.parse::<u64>().ok());
+        Self {
+            interval: env("WS_PING_INTERVAL_SECS")
+                .filter(|&secs| secs > 0)
+                .map(Duration::from_secs)
+                .unwrap_or(default.interval),
+            max_missed: env("WS_MAX_MISSED_PINGS")
+                .and_then(|n| u32::try_from(n).ok())
+                .unwrap_or(default.max_missed),
+        }
+    }
+
+    /// Tempo sem nenhuma mensagem do cliente até a desconexão
+    fn timeout(&self) -> Duration {
+        self.interval * self.max_missed.max(1)
+    }
+}
+
+/// Conexão WebSocket com heartbeat
+///
+/// Usada por todos os sockets: `recv` envia os pings enquanto espera o
+/// cliente e encerra a conexão de quem parou de responder. Os pongs aos pings
+/// do cliente são enviados pelo próprio tungstenite.
+struct Connection {
+    socket: WebSocket,
+    heartbeat: HeartbeatConfig,
+    ticker: tokio::time::Interval,
+    last_seen: Instant,
+}
+
+impl Connection {
+    fn new(socket: WebSocket, heartbeat: HeartbeatConfig) -> Self {
+        let mut ticker = tokio::time::interval(heartbeat.interval);
+        // O primeiro tick seria imediato
+        ticker.reset();
+        Self {
+            socket,
+            heartbeat,
+            ticker,
+            last_seen: Instant::now(),
+        }
+    }
+
+    /// Envia a mensagem; `false` se a conexão caiu
+    async fn send(&mut self, message: Message) -> bool {
+        self.socket.send(message).await.is_ok()
+    }
+
+    /// Próxima mensagem de texto ou binária do cliente
+    ///
+    /// `None` quando o cliente fechou a conexão ou deixou de responder a
+    /// `max_missed` pings. Pode ser usado dentro de `select!`.
+    async fn recv(&mut self) -> Option<Message> {
+        loop {
+            tokio::select! {
+                incoming = self.socket.recv() => {
+                    self.last_seen = Instant::now();
+                    match incoming {
+                        Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return None,
+                        Some(Ok(Message::Ping(_) | Message::Pong(_))) => {}
+                        Some(Ok(message)) => return Some(message),
+                    }
+                }
+                _ = self.ticker.tick() => {
+                    if self.last_seen.elapsed() > self.heartbeat.timeout() {
+                        warn!(
+                            "💀 Sem resposta a {} pings, desconectando",
+                            self.heartbeat.max_missed
+                        );
+                        let _ = self.socket.send(Message::Close(None)).await;
+                        return None;
+                    }
+                    if !self.send(Message::Ping(Bytes::new())).await {
+                        return None;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Comandos aceitos em `/ws`
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClientMessage {
+    /// Equivalente a `POST /action?dry_run=true`
+    Preview {
+        player_id: tatic_lib::PlayerId,
+        action: Action,
+        /// Repetido na resposta para o cliente casar pedido e resposta
+        request_id: Option<String>,
+    },
+}
+
+/// Endpoints WebSocket, com a query esperada, para a listagem de `GET /`
+///
+/// O OpenAPI não descreve WebSockets; mantenha em sincronia com `websocket_routes`.
+pub const WS_ENDPOINTS: &[(&str, &str)] = &[
+    ("/ws?match_id={id}", "WebSocket para observar partida"),
+    ("/ws/lobby", "WebSocket com as alterações do lobby"),
+    ("/ws/tournament?tournament_id={id}", "WebSocket com a classificação de um torneio"),
+    (
+        "/ws/replay?match_id={id}&speed={x}",
+        "WebSocket com o replay de uma partida terminada",
+    ),
+    ("/ws/bot?token={token}", "WebSocket de um bot registrado"),
+];
+
 /// Cria rotas WebSocket
 pub fn websocket_routes(state: AppState) -> Router {
     Router::new()
         .route("/ws", get(websocket_handler))
+        .route("/ws/lobby", get(lobby_websocket_handler))
+        .route("/ws/tournament", get(tournament_websocket_handler))
+        .route("/ws/replay", get(replay_websocket_handler))
+        .route("/ws/bot", get(bot_websocket_handler))
         .with_state(state)
 }
 
@@ -30,76 +204,530 @@ pub fn websocket_routes(state: AppState) -> Router {
 async fn websocket_handler(
     ws: WebSocketUpgrade,
     Query(params): Query<WsQuery>,
+    RawQuery(query): RawQuery,
+    headers: HeaderMap,
     State(state): State<AppState>,
 ) -> Response {
     info!("🔌 WebSocket connection request for match: {}", params.match_id);
-    ws.on_upgrade(move |socket| handle_websocket(socket, params.match_id, state))
+    let lang = i18n::resolve_lang(&state, query.as_deref(), &headers).await;
+    ws.on_upgrade(move |socket| {
+        handle_websocket(socket, params.match_id, params.player_id, lang, state)
+    })
+}
+
+/// Troca o estado do evento pelo que `viewer` vê (partidas com `fog`)
+fn hide_fogged(msg: String, viewer: Option<&str>) -> String {
+    let Ok(mut event) = serde_json::from_str::<serde_json::Value>(&msg) else {
+        return msg;
+    };
+    let Ok(state) = serde_json::from_value::<GameState>(event["state"].take()) else {
+        return msg;
+    };
+    event["state"] = serde_json::to_value(fog::view(&state, true, viewer)).unwrap_or_default();
+    event.to_string()
+}
+
+/// Adiciona a mensagem traduzida aos eventos de estado
+fn localize_event(msg: String, lang: Lang) -> String {
+    let Ok(mut event) = serde_json::from_str::<serde_json::Value>(&msg) else {
+        return msg;
+    };
+    if event["type"] != "state_update" {
+        return msg;
+    }
+    let turn = event["state"]["turn"].as_str().unwrap_or_default().to_string();
+    let turn_count = event["state"]["turn_count"].to_string();
+    event["message"] = translate(
+        lang,
+        "ws.state_updated",
+        &[("turn", &turn), ("turn_count", &turn_count)],
+    )
+    .into();
+    event.to_string()
 }
 
 /// Gerencia conexão WebSocket
-async fn handle_websocket(socket: WebSocket, match_id: String, state: AppState) {
+async fn handle_websocket(
+    socket: WebSocket,
+    match_id: String,
+    viewer: Option<String>,
+    lang: Lang,
+    state: AppState,
+) {
     info!("✅ WebSocket connected for match: {}", match_id);
+    let mut conn = Connection::new(socket, state.heartbeat);
     
-    // Split socket em sender e receiver
-    let (mut sender, mut receiver) = socket.split();
-    
-    // Canal para receber broadcasts
-    let (tx, mut rx) = tokio::sync::mpsc::channel(100);
-    
-    // Registra observer
-    state.add_observer(match_id.clone(), tx).await;
-    
-    // Envia estado inicial
-    if let Some(match_data) = state.get_match(&match_id).await {
-        let initial_state = serde_json::json!({
-            "type": "initial_state",
-            "match_id": match_id,
-            "state": match_data.state,
-        });
-        
-        // CORREÇÃO para Axum 0.8: Converter String para Utf8Bytes usando .into()
-        if let Err(e) = sender
-            .send(Message::Text(initial_state.to_string().into()))
-            .await
-        {
-            error!("Erro ao enviar estado inicial: {}", e);
-            return;
+    // Registra observer e envia o estado do momento da inscrição
+    let Some((match_data, mut rx)) = state.add_observer(&match_id).await else {
+        warn!("❌ Partida não encontrada: {}", match_id);
+        let err = ApiError::MatchNotFound {
+            match_id: match_id.clone(),
+        };
+        conn.send(error_event(&err, lang)).await;
+        return;
+    };
+    let fog = match_data.has_fog();
+    
+    let initial_state = serde_json::json!({
+        "type": "initial_state",
+        "match_id": match_id,
+        "state": match_data.view(viewer.as_deref()),
+        "message": translate(lang, "ws.observing", &[("match_id", &match_id)]),
+    });
+    
+    // CORREÇÃO para Axum 0.8: Converter String para Utf8Bytes usando .into()
+    if !conn.send(Message::Text(initial_state.to_string().into())).await {
+        error!("Erro ao enviar estado inicial");
+        return;
+    }
+    
+    loop {
+        let outgoing = tokio::select! {
+            result = rx.recv() => {
+                match next_broadcast(result, &state, &match_id).await {
+                    Outgoing::Send(msg) if fog => {
+                        localize_event(hide_fogged(msg, viewer.as_deref()), lang)
+                    }
+                    Outgoing::Send(msg) => localize_event(msg, lang),
+                    Outgoing::Skip => continue,
+                    Outgoing::Close => break,
+                }
+            }
+            incoming = conn.recv() => {
+                match incoming {
+                    Some(Message::Text(text)) => {
+                        handle_client_message(&text, &state, &match_id, lang).await
+                    }
+                    Some(_) => continue,
+                    None => break,
+                }
+            }
+        };
+        if !conn.send(Message::Text(outgoing.into())).await {
+            break;
         }
     }
     
-    // Task para enviar broadcasts
-    let mut send_task = tokio::spawn(async move {
-        while let Some(msg) = rx.recv().await {
-            // CORREÇÃO para Axum 0.8: Converter String para Utf8Bytes
-            if sender.send(Message::Text(msg.into())).await.is_err() {
-                break;
+    // O receptor do broadcast é descartado aqui: o observer sai da partida
+    info!("🔌 WebSocket disconnected for match: {}", match_id);
+}
+
+/// Executa um comando do observer e monta a resposta
+async fn handle_client_message(text: &str, state: &AppState, match_id: &str, lang: Lang) -> String {
+    let message = match serde_json::from_str::<ClientMessage>(text) {
+        Ok(message) => message,
+        Err(e) => {
+            let err = ApiError::InvalidRequest {
+                reason: e.to_string().into(),
+            };
+            return error_json(&err, lang).to_string();
+        }
+    };
+    
+    match message {
+        ClientMessage::Preview {
+            player_id,
+            action,
+            request_id,
+        } => {
+            let result = match state.get_handle(match_id).await {
+                Some(handle) => handle.preview(&player_id, action).await,
+                None => Err(ApiError::MatchNotFound {
+                    match_id: match_id.to_string(),
+                }),
+            };
+            let mut event = match result {
+                Ok(preview) => serde_json::json!({
+                    "type": "preview_result",
+                    "match_id": match_id,
+                    "state": preview.state,
+                    "changes": preview.changes,
+                }),
+                Err(err) => error_json(&err, lang),
+            };
+            event["request_id"] = request_id.into();
+            event.to_string()
+        }
+    }
+}
+
+/// O que a task de escrita deve fazer com o resultado do broadcast
+enum Outgoing {
+    Send(String),
+    Skip,
+    Close,
+}
+
+/// Trata o resultado do broadcast conforme a `LagPolicy`
+async fn next_broadcast(
+    result: Result<String, RecvError>,
+    state: &AppState,
+    match_id: &str,
+) -> Outgoing {
+    let policy = state.fanout.lag_policy;
+    match result {
+        Ok(msg) => Outgoing::Send(msg),
+        Err(RecvError::Closed) => Outgoing::Close,
+        Err(RecvError::Lagged(skipped)) => {
+            warn!("🐢 Observer atrasado perdeu {} mensagens ({:?})", skipped, policy);
+            state.fanout_metrics.record_lag(skipped, policy);
+            match policy {
+                LagPolicy::DropOldest => Outgoing::Skip,
+                LagPolicy::Disconnect => Outgoing::Close,
+                LagPolicy::Resync => match state.get_match(match_id).await {
+                    Some(match_data) => {
+                        let event = serde_json::json!({
+                            "type": "resync",
+                            "match_id": match_id,
+                            "state": match_data.state,
+                        });
+                        Outgoing::Send(event.to_string())
+                    }
+                    None => Outgoing::Close,
+                },
             }
         }
+    }
+}
+
+/// Handler para upgrade WebSocket do lobby
+async fn lobby_websocket_handler(ws: WebSocketUpgrade, State(state): State<AppState>) -> Response {
+    info!("🔌 WebSocket connection request for lobby");
+    ws.on_upgrade(move |socket| handle_lobby_websocket(socket, state))
+}
+
+/// Envia a lista inicial do lobby e depois cada alteração
+async fn handle_lobby_websocket(socket: WebSocket, state: AppState) {
+    let mut conn = Connection::new(socket, state.heartbeat);
+    // Inscreve antes de listar para não perder eventos entre as duas etapas
+    let mut rx = state.lobby_events.subscribe();
+    let snapshot = serde_json::json!({
+        "type": "lobby_snapshot",
+        "lobby": state.list_lobby().await,
     });
+    if !conn.send(Message::Text(snapshot.to_string().into())).await {
+        return;
+    }
+    
+    loop {
+        tokio::select! {
+            result = rx.recv() => {
+                let msg = match result {
+                    Ok(msg) => msg,
+                    // Atrasado: reenvia a lista completa
+                    Err(RecvError::Lagged(_)) => serde_json::json!({
+                        "type": "lobby_snapshot",
+                        "lobby": state.list_lobby().await,
+                    })
+                    .to_string(),
+                    Err(RecvError::Closed) => break,
+                };
+                if !conn.send(Message::Text(msg.into())).await {
+                    break;
+                }
+            }
+            incoming = conn.recv() => {
+                if incoming.is_none() {
+                    break;
+                }
+            }
+        }
+    }
+    
+    info!("🔌 WebSocket disconnected for lobby");
+}
+
+/// Handler para upgrade WebSocket de um torneio
+async fn tournament_websocket_handler(
+    ws: WebSocketUpgrade,
+    Query(params): Query<TournamentWsQuery>,
+    RawQuery(query): RawQuery,
+    headers: HeaderMap,
+    State(state): State<AppState>,
+) -> Response {
+    info!("🔌 WebSocket connection request for tournament: {}", params.tournament_id);
+    let lang = i18n::resolve_lang(&state, query.as_deref(), &headers).await;
+    ws.on_upgrade(move |socket| handle_tournament_websocket(socket, params.tournament_id, lang, state))
+}
+
+/// Envia o torneio atual e depois cada atualização dele
+async fn handle_tournament_websocket(
+    socket: WebSocket,
+    tournament_id: String,
+    lang: Lang,
+    state: AppState,
+) {
+    let mut conn = Connection::new(socket, state.heartbeat);
+    let mut rx = state.tournament_events.subscribe();
+    let snapshot = |tournament: Tournament| {
+        serde_json::json!({
+            "type": "tournament_update",
+            "standings": tournament.standings(),
+            "tournament": tournament,
+        })
+        .to_string()
+    };
     
-    // Task para receber mensagens (ping/pong)
-    let mut recv_task = tokio::spawn(async move {
-        while let Some(Ok(msg)) = receiver.next().await {
-            match msg {
-                Message::Ping(bytes) => {
-                    // Para ping/pong, precisaríamos de uma referência mutável ao sender
-                    // Por simplicidade, vamos apenas logar
-                    info!("Recebido ping");
+    let initial = match state.get_tournament(&tournament_id).await {
+        Ok(tournament) => snapshot(tournament),
+        Err(err) => {
+            conn.send(error_event(&err, lang)).await;
+            return;
+        }
+    };
+    if !conn.send(Message::Text(initial.into())).await {
+        return;
+    }
+    
+    loop {
+        tokio::select! {
+            result = rx.recv() => {
+                let msg = match result {
+                    Ok((id, msg)) if id == tournament_id => msg,
+                    Ok(_) => continue,
+                    // Atrasado: reenvia o torneio completo
+                    Err(RecvError::Lagged(_)) => match state.get_tournament(&tournament_id).await {
+                        Ok(tournament) => snapshot(tournament),
+                        Err(_) => break,
+                    },
+                    Err(RecvError::Closed) => break,
+                };
+                if !conn.send(Message::Text(msg.into())).await {
+                    break;
                 }
-                Message::Close(_) => {
-                    info!("WebSocket fechado pelo cliente");
+            }
+            incoming = conn.recv() => {
+                if incoming.is_none() {
                     break;
                 }
-                _ => {}
             }
         }
+    }
+    
+    info!("🔌 WebSocket disconnected for tournament: {}", tournament_id);
+}
+
+/// Handler para upgrade WebSocket de replay
+async fn replay_websocket_handler(
+    ws: WebSocketUpgrade,
+    Query(params): Query<ReplayWsQuery>,
+    RawQuery(query): RawQuery,
+    headers: HeaderMap,
+    State(state): State<AppState>,
+) -> Response {
+    info!("🔌 WebSocket replay request for match: {}", params.match_id);
+    let lang = i18n::resolve_lang(&state, query.as_deref(), &headers).await;
+    let speed = params.speed.unwrap_or(1.0);
+    ws.on_upgrade(move |socket| handle_replay_websocket(socket, params.match_id, speed, lang, state))
+}
+
+/// Envia os estados de uma partida terminada com as mesmas mensagens de `/ws`
+///
+/// `initial_state` e `state_update` ganham o campo `replay` com a posição;
+/// cada comando do cliente é respondido com `replay_status`.
+async fn handle_replay_websocket(
+    socket: WebSocket,
+    match_id: String,
+    speed: f64,
+    lang: Lang,
+    state: AppState,
+) {
+    let mut conn = Connection::new(socket, state.heartbeat);
+    let playback = match state.get_match(&match_id).await {
+        Some(match_data) => Playback::new(&match_data, speed),
+        None => Err(ApiError::MatchNotFound {
+            match_id: match_id.clone(),
+        }),
+    };
+    let mut playback = match playback {
+        Ok(playback) => playback,
+        Err(err) => {
+            warn!("❌ Replay indisponível para {}: {}", match_id, err);
+            conn.send(error_event(&err, lang)).await;
+            return;
+        }
+    };
+    info!("▶️ Replay da partida {} a {}x", match_id, speed);
+    
+    let initial_state = serde_json::json!({
+        "type": "initial_state",
+        "match_id": match_id,
+        "state": playback.current(),
+        "message": translate(lang, "ws.replaying", &[("match_id", &match_id)]),
+        "replay": playback.status(),
+    });
+    if !conn.send(Message::Text(initial_state.to_string().into())).await {
+        return;
+    }
+    
+    let mut next_frame = tokio::time::Instant::now() + playback.interval();
+    'replay: loop {
+        let playing = playback.is_playing();
+        let replies = tokio::select! {
+            _ = tokio::time::sleep_until(next_frame), if playing => {
+                playback.advance();
+                next_frame = tokio::time::Instant::now() + playback.interval();
+                vec![replay_frame(&match_id, &playback, lang)]
+            }
+            incoming = conn.recv() => {
+                let text = match incoming {
+                    Some(Message::Text(text)) => text,
+                    Some(_) => continue,
+                    None => break,
+                };
+                let result = serde_json::from_str::<ReplayControl>(&text)
+                    .map_err(|e| ApiError::InvalidRequest {
+                        reason: e.to_string().into(),
+                    })
+                    .and_then(|control| playback.apply(control));
+                match result {
+                    Ok(changed) => {
+                        next_frame = tokio::time::Instant::now() + playback.interval();
+                        let status = serde_json::json!({
+                            "type": "replay_status",
+                            "match_id": match_id,
+                            "replay": playback.status(),
+                        });
+                        let status = Message::Text(status.to_string().into());
+                        if changed {
+                            vec![replay_frame(&match_id, &playback, lang), status]
+                        } else {
+                            vec![status]
+                        }
+                    }
+                    Err(err) => vec![error_event(&err, lang)],
+                }
+            }
+        };
+        for reply in replies {
+            if !conn.send(reply).await {
+                break 'replay;
+            }
+        }
+    }
+    
+    info!("🔌 Replay encerrado: {}", match_id);
+}
+
+/// `state_update` do estado atual do replay
+fn replay_frame(match_id: &str, playback: &Playback, lang: Lang) -> Message {
+    let event = serde_json::json!({
+        "type": "state_update",
+        "match_id": match_id,
+        "state": playback.current(),
+        "replay": playback.status(),
+    });
+    Message::Text(localize_event(event.to_string(), lang).into())
+}
+
+/// Evento `error` com a mensagem no idioma pedido
+fn error_json(err: &ApiError, lang: Lang) -> serde_json::Value {
+    serde_json::json!({
+        "type": "error",
+        "error": ErrorResponse::localized(err, lang).error,
+    })
+}
+
+fn error_event(err: &ApiError, lang: Lang) -> Message {
+    Message::Text(error_json(err, lang).to_string().into())
+}
+
+/// Handler para upgrade WebSocket de um bot externo
+async fn bot_websocket_handler(
+    ws: WebSocketUpgrade,
+    Query(params): Query<BotWsQuery>,
+    RawQuery(query): RawQuery,
+    headers: HeaderMap,
+    connect_info: Option<Extension<ConnectInfo<SocketAddr>>>,
+    State(state): State<AppState>,
+) -> Response {
+    info!("🔌 WebSocket connection request for bot");
+    // Cada conexão é uma sessão na auditoria
+    let mut origin = Who::from_parts(&headers, connect_info.map(|Extension(ConnectInfo(addr))| addr));
+    origin.session.get_or_insert_with(|| format!("ws-{}", uuid::Uuid::new_v4()));
+    let lang = i18n::resolve_lang(&state, query.as_deref(), &headers).await;
+    ws.on_upgrade(move |socket| handle_bot_websocket(socket, params.token, origin, lang, state))
+}
+
+/// Envia `your_turn` ao bot e repassa as ações que ele responde
+async fn handle_bot_websocket(
+    socket: WebSocket,
+    token: String,
+    origin: Who,
+    lang: Lang,
+    state: AppState,
+) {
+    let mut conn = Connection::new(socket, state.heartbeat);
+    let Some(bot) = state.bots.read().await.authenticate(&token).cloned() else {
+        warn!("❌ Token de bot inválido");
+        conn.send(error_event(&ApiError::Unauthorized, lang)).await;
+        return;
+    };
+    
+    let (tx, mut turns) = mpsc::channel(BOT_QUEUE_SIZE);
+    let deadline_ms = {
+        let mut bots = state.bots.write().await;
+        bots.connect(bot.id.clone(), tx.clone());
+        bots.config.turn_timeout.as_millis() as u64
+    };
+    info!("🤖 Bot conectado: {}", bot.id);
+    
+    let connected = serde_json::json!({
+        "type": "bot_connected",
+        "player_id": bot.id,
     });
+    let mut pending: HashMap<MatchId, oneshot::Sender<BotAction>> = HashMap::new();
     
-    // Aguarda alguma task terminar
-    tokio::select! {
-        _ = (&mut send_task) => recv_task.abort(),
-        _ = (&mut recv_task) => send_task.abort(),
+    if conn.send(Message::Text(connected.to_string().into())).await {
+        loop {
+            tokio::select! {
+                Some(turn) = turns.recv() => {
+                    let event = serde_json::json!({
+                        "type": "your_turn",
+                        "match_id": turn.match_id,
+                        "player_id": bot.id,
+                        "state": turn.state,
+                        "deadline_ms": deadline_ms,
+                        "last_error": turn.last_error,
+                    });
+                    pending.insert(turn.match_id, turn.reply);
+                    if !conn.send(Message::Text(event.to_string().into())).await {
+                        break;
+                    }
+                }
+                incoming = conn.recv() => {
+                    let text = match incoming {
+                        Some(Message::Text(text)) => text,
+                        Some(_) => continue,
+                        None => break,
+                    };
+                    let err = match serde_json::from_str::<BotMessage>(&text) {
+                        Ok(BotMessage::Action { match_id, action }) => {
+                            // Fora do prazo o servidor já jogou no lugar do bot
+                            let delivered = pending
+                                .remove(&match_id)
+                                .is_some_and(|reply| {
+                                    let origin = origin.clone();
+                                    reply.send(BotAction { action, origin }).is_ok()
+                                });
+                            (!delivered).then(|| ApiError::Conflict {
+                                reason: Reason::new("reason.no_pending_turn").arg("match_id", &match_id),
+                            })
+                        }
+                        Err(e) => Some(ApiError::InvalidRequest {
+                            reason: e.to_string().into(),
+                        }),
+                    };
+                    if let Some(err) = err
+                        && !conn.send(error_event(&err, lang)).await
+                    {
+                        break;
+                    }
+                }
+            }
+        }
     }
     
-    info!("🔌 WebSocket disconnected for match: {}", match_id);
+    state.bots.write().await.disconnect(&bot.id, &tx);
+    info!("🔌 Bot desconectado: {}", bot.id);
 }
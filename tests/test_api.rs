@@ -1,9 +1,9 @@
 #[cfg(test)]
 mod tests {
-    use super::*;
+    use axum::Router;
     use axum::http::StatusCode;
     use axum_test::TestServer;
-    use tatic_lib::{Action, Coord};
+    use tatic_lib::Action;
     
     #[tokio::test]
     async fn test_root_endpoint() {
@@ -66,7 +66,7 @@ mod tests {
             }))
             .await;
         
-        let match_id = create_response.json()["data"].as_str().unwrap();
+        let match_id = create_response.json::<serde_json::Value>()["data"].as_str().unwrap().to_string();
         
         // Então obtém o estado
         let response = server
@@ -94,7 +94,7 @@ mod tests {
             }))
             .await;
         
-        let match_id = create_response.json()["data"].as_str().unwrap();
+        let match_id = create_response.json::<serde_json::Value>()["data"].as_str().unwrap().to_string();
         
         // Envia ação
         let response = server
@@ -115,8 +115,2407 @@ mod tests {
         assert_eq!(json["data"]["turn"], "test2");
     }
     
+    #[tokio::test]
+    async fn test_post_action_dry_run() {
+        let app = create_test_app().await;
+        let server = TestServer::new(app).unwrap();
+        
+        let create_response = server
+            .post("/match/create")
+            .json(&serde_json::json!({
+                "player1": "dry1",
+                "player2": "dry2"
+            }))
+            .await;
+        let match_id = create_response.json::<serde_json::Value>()["data"].as_str().unwrap().to_string();
+        
+        let response = server
+            .post("/action?dry_run=true")
+            .json(&serde_json::json!({
+                "match_id": match_id,
+                "player_id": "dry1",
+                "action": {
+                    "type": "EndTurn"
+                }
+            }))
+            .await;
+        
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let json: serde_json::Value = response.json();
+        assert_eq!(json["data"]["state"]["turn"], "dry2");
+        let changes = json["data"]["changes"].as_array().unwrap();
+        assert!(changes.iter().any(|c| c["path"] == "/turn" && c["after"] == "dry2"));
+        
+        // A partida não mudou
+        let state: serde_json::Value = server
+            .get(&format!("/state?match_id={}", match_id))
+            .await
+            .json();
+        assert_eq!(state["data"]["turn"], "dry1");
+        
+        // Erros são os mesmos da ação real
+        let response = server
+            .post("/action?dry_run=true")
+            .json(&serde_json::json!({
+                "match_id": match_id,
+                "player_id": "dry2",
+                "action": {
+                    "type": "EndTurn"
+                }
+            }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::CONFLICT);
+    }
+    
+    #[tokio::test]
+    async fn test_post_actions_batch_is_atomic() {
+        let app = create_test_app().await;
+        let server = TestServer::new(app).unwrap();
+        
+        let create_response = server
+            .post("/match/create")
+            .json(&serde_json::json!({
+                "player1": "batch1",
+                "player2": "batch2"
+            }))
+            .await;
+        let match_id = create_response.json::<serde_json::Value>()["data"].as_str().unwrap().to_string();
+        
+        // A segunda ação já é fora do turno: nada é aplicado
+        let response = server
+            .post("/actions")
+            .json(&serde_json::json!({
+                "match_id": match_id,
+                "player_id": "batch1",
+                "actions": [{ "type": "EndTurn" }, { "type": "EndTurn" }]
+            }))
+            .await;
+        
+        assert_eq!(response.status_code(), StatusCode::CONFLICT);
+        let json: serde_json::Value = response.json();
+        assert_eq!(json["error"]["code"], "batch_failed");
+        assert_eq!(json["error"]["details"]["index"], 1);
+        assert_eq!(json["error"]["details"]["cause"]["code"], "not_your_turn");
+        
+        let state: serde_json::Value = server
+            .get(&format!("/state?match_id={}", match_id))
+            .await
+            .json();
+        assert_eq!(state["data"]["turn"], "batch1");
+        
+        let response = server
+            .post("/actions")
+            .json(&serde_json::json!({
+                "match_id": match_id,
+                "player_id": "batch1",
+                "actions": [{ "type": "EndTurn" }]
+            }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert_eq!(response.json::<serde_json::Value>()["data"]["turn"], "batch2");
+    }
+    
+    #[tokio::test]
+    async fn test_match_export_import() {
+        let app = create_test_app().await;
+        let server = TestServer::new(app).unwrap();
+        
+        let create_response = server
+            .post("/match/create")
+            .json(&serde_json::json!({
+                "player1": "exp1",
+                "player2": "exp2"
+            }))
+            .await;
+        let match_id = create_response.json::<serde_json::Value>()["data"].as_str().unwrap().to_string();
+        for player in ["exp1", "exp2"] {
+            server
+                .post("/action")
+                .json(&serde_json::json!({
+                    "match_id": match_id,
+                    "player_id": player,
+                    "action": { "type": "EndTurn" }
+                }))
+                .await;
+        }
+        
+        let response = server.get(&format!("/match/{}/export", match_id)).await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let document = response.text();
+        assert!(document.starts_with("tatic-match 1\n"));
+        assert!(document.contains("actions: 2\n"));
+        assert!(document.contains("2. \"exp2\" {\"type\":\"EndTurn\"}"));
+        
+        // Importada, a partida chega ao mesmo estado
+        let response = server.post("/match/import").text(document.clone()).await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let imported = response.json::<serde_json::Value>()["data"].as_str().unwrap().to_string();
+        assert_ne!(imported, match_id);
+        let original: serde_json::Value = server
+            .get(&format!("/state?match_id={}", match_id))
+            .await
+            .json();
+        let copy: serde_json::Value = server
+            .get(&format!("/state?match_id={}", imported))
+            .await
+            .json();
+        assert_eq!(copy["data"], original["data"]);
+        
+        // A primeira ação ilegal é apontada
+        let tampered = document.replace("2. \"exp2\"", "2. \"exp1\"");
+        let response = server.post("/match/import").text(tampered).await;
+        assert_eq!(response.status_code(), StatusCode::UNPROCESSABLE_ENTITY);
+        let json: serde_json::Value = response.json();
+        assert_eq!(json["error"]["code"], "import_failed");
+        assert_eq!(json["error"]["details"]["number"], 2);
+        assert_eq!(json["error"]["details"]["cause"]["code"], "not_your_turn");
+        
+        let response = server
+            .post("/match/import")
+            .text(document.replace("tatic-match 1", "tatic-match 99"))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+        
+        // Posição inicial inventada
+        let invented: String = document
+            .lines()
+            .map(|line| match line.strip_prefix("initial_state: ") {
+                Some(state) => format!(
+                    "initial_state: {}\n",
+                    state.replace("\"turn_count\":0", "\"turn_count\":7")
+                ),
+                None => format!("{}\n", line),
+            })
+            .collect();
+        assert_ne!(invented, document);
+        let response = server.post("/match/import").text(invented).await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+        assert_eq!(response.json::<serde_json::Value>()["error"]["code"], "invalid_request");
+    }
+    
+    #[tokio::test]
+    async fn test_get_state_not_found_error_code() {
+        let app = create_test_app().await;
+        let server = TestServer::new(app).unwrap();
+        
+        let response = server.get("/state?match_id=inexistente").await;
+        
+        assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+        
+        let json: serde_json::Value = response.json();
+        assert!(!json["success"].as_bool().unwrap());
+        assert_eq!(json["error"]["code"], "match_not_found");
+        assert_eq!(json["error"]["details"]["match_id"], "inexistente");
+    }
+
+    #[tokio::test]
+    async fn test_malformed_body_error_code() {
+        let app = create_test_app().await;
+        let server = TestServer::new(app).unwrap();
+
+        let response = server
+            .post("/match/create")
+            .json(&serde_json::json!({ "player1": "test1" }))
+            .await;
+
+        assert!(response.status_code().is_client_error());
+
+        let json: serde_json::Value = response.json();
+        assert!(!json["success"].as_bool().unwrap());
+        assert_eq!(json["error"]["code"], "invalid_request");
+    }
+
+    #[tokio::test]
+    async fn test_post_action_not_your_turn() {
+        let app = create_test_app().await;
+        let server = TestServer::new(app).unwrap();
+        
+        let create_response = server
+            .post("/match/create")
+            .json(&serde_json::json!({
+                "player1": "test1",
+                "player2": "test2"
+            }))
+            .await;
+        
+        let match_id = create_response.json::<serde_json::Value>()["data"].as_str().unwrap().to_string();
+        
+        let response = server
+            .post("/action")
+            .json(&serde_json::json!({
+                "match_id": match_id,
+                "player_id": "test2",
+                "action": {
+                    "type": "EndTurn"
+                }
+            }))
+            .await;
+        
+        assert_eq!(response.status_code(), StatusCode::CONFLICT);
+        
+        let json: serde_json::Value = response.json();
+        assert_eq!(json["error"]["code"], "not_your_turn");
+        assert_eq!(json["error"]["details"]["current_turn"], "test1");
+    }
+    
+    #[tokio::test]
+    async fn test_error_message_localized() {
+        let app = create_test_app().await;
+        let server = TestServer::new(app).unwrap();
+        
+        let response = server
+            .get("/state?match_id=inexistente")
+            .add_header("Accept-Language", "en-US,en;q=0.9,pt-BR;q=0.5")
+            .await;
+        
+        let json: serde_json::Value = response.json();
+        assert_eq!(json["error"]["code"], "match_not_found");
+        assert_eq!(json["error"]["message"], "Match inexistente not found");
+        
+        // Sem header, pt-BR é o padrão
+        let response = server.get("/state?match_id=inexistente").await;
+        let json: serde_json::Value = response.json();
+        assert_eq!(json["error"]["message"], "Partida inexistente não encontrada");
+
+        // q=0 recusa o idioma
+        let response = server
+            .get("/state?match_id=inexistente")
+            .add_header("Accept-Language", "en;q=0")
+            .await;
+        let json: serde_json::Value = response.json();
+        assert_eq!(json["error"]["message"], "Partida inexistente não encontrada");
+    }
+
+    #[tokio::test]
+    async fn test_error_reason_localized() {
+        let app = create_test_app().await;
+        let server = TestServer::new(app).unwrap();
+
+        let response = server
+            .post("/match/import")
+            .add_header("Accept-Language", "en")
+            .text("tatic-match 1\nisto não é um campo")
+            .await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+        let json = response.json::<serde_json::Value>();
+        assert_eq!(json["error"]["details"]["reason"], "line 2: expected \"field: value\"");
+        assert_eq!(
+            json["error"]["message"],
+            "Invalid request: line 2: expected \"field: value\""
+        );
+
+        let response = server.post("/match/import").text("tatic-match 1\nisto não é um campo").await;
+        let json = response.json::<serde_json::Value>();
+        assert_eq!(json["error"]["details"]["reason"], "linha 2: esperado \"campo: valor\"");
+    }
+
+    #[tokio::test]
+    async fn test_board_legend_localized() {
+        let app = create_test_app().await;
+        let server = TestServer::new(app).unwrap();
+
+        let response = server
+            .post("/match/create")
+            .json(&serde_json::json!({ "player1": "alice", "player2": "anna" }))
+            .await;
+        let match_id = response.json::<serde_json::Value>()["data"].as_str().unwrap().to_string();
+
+        let response = server.get(&format!("/match/{}/board?lang=en", match_id)).await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let board = response.text();
+        let lines: Vec<&str> = board.lines().collect();
+        assert_eq!(lines[0], "   01234567");
+        assert_eq!(lines[1], " 0 A.......");
+        // Avatares iguais: o segundo jogador aparece em minúscula
+        assert_eq!(lines[8], " 7 .......a");
+        assert!(lines.contains(&"Legend:"));
+        assert!(lines.contains(&"  a anna"));
+        assert!(lines.contains(&"      #1 warrior at (0, 0), 10 HP"));
+        assert!(lines.contains(&"alice's turn (round 0)"));
+
+        let board = server.get(&format!("/match/{}/board", match_id)).await.text();
+        assert!(board.contains("Legenda:"));
+        assert!(board.contains("#2 warrior em (7, 7), 10 PV"));
+        assert!(board.contains("Turno de alice (rodada 0)"));
+    }
+
+    #[tokio::test]
+    async fn test_create_match_limit_per_ip() {
+        let mut state = server::state::AppState::new();
+        state.limits.max_active_matches_per_ip = 2;
+        let server = TestServer::new(app_from(state.clone(), [10, 0, 0, 1])).unwrap();
+        
+        for i in 0..2 {
+            let response = server
+                .post("/match/create")
+                .json(&serde_json::json!({
+                    "player1": "spammer",
+                    "player2": format!("rival{}", i)
+                }))
+                .await;
+            assert_eq!(response.status_code(), StatusCode::OK);
+        }
+        
+        let response = server
+            .post("/match/create")
+            .json(&serde_json::json!({
+                "player1": "spammer",
+                "player2": "rival2"
+            }))
+            .await;
+        
+        assert_eq!(response.status_code(), StatusCode::TOO_MANY_REQUESTS);
+        
+        let json: serde_json::Value = response.json();
+        assert_eq!(json["error"]["code"], "too_many_matches");
+        
+        // O limite é do IP: outro IP ainda cria partidas para o mesmo jogador
+        let other = TestServer::new(app_from(state, [10, 0, 0, 2])).unwrap();
+        let response = other
+            .post("/match/create")
+            .json(&serde_json::json!({
+                "player1": "spammer",
+                "player2": "rival3"
+            }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+    }
+    
+    #[tokio::test]
+    async fn test_rate_limit_retry_after() {
+        let state = server::state::AppState::new();
+        let server = TestServer::new(app_from(state, [10, 0, 0, 1])).unwrap();
+        
+        // Rajada de criação é 5 por IP; X-Player-Id não muda a chave
+        for i in 0..5 {
+            server
+                .post("/match/create")
+                .add_header("X-Player-Id", format!("flooder{}", i))
+                .json(&serde_json::json!({
+                    "player1": format!("a{}", i),
+                    "player2": format!("b{}", i)
+                }))
+                .await;
+        }
+        
+        let response = server
+            .post("/match/create")
+            .add_header("X-Player-Id", "flooder5")
+            .json(&serde_json::json!({
+                "player1": "a5",
+                "player2": "b5"
+            }))
+            .await;
+        
+        assert_eq!(response.status_code(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().contains_key("retry-after"));
+        
+        let json: serde_json::Value = response.json();
+        assert_eq!(json["error"]["code"], "rate_limited");
+    }
+    
+    #[tokio::test]
+    async fn test_rate_limit_without_client_ip() {
+        // Sem ConnectInfo as requisições dividem um bucket, em vez de passar sem limite
+        let app = create_test_app().await;
+        let server = TestServer::new(app).unwrap();
+
+        let create = |i: usize| {
+            server.post("/match/create").json(&serde_json::json!({
+                "player1": format!("anon{}", i),
+                "player2": format!("rival{}", i)
+            }))
+        };
+        for i in 0..5 {
+            assert_eq!(create(i).await.status_code(), StatusCode::OK);
+        }
+        assert_eq!(create(5).await.status_code(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[tokio::test]
+    async fn test_idempotency_key() {
+        let app = create_test_app().await;
+        let server = TestServer::new(app).unwrap();
+        
+        let body = serde_json::json!({
+            "player1": "idem1",
+            "player2": "idem2"
+        });
+        
+        let first = server
+            .post("/match/create")
+            .add_header("X-Player-Id", "idem1")
+            .add_header("Idempotency-Key", "chave-1")
+            .json(&body)
+            .await;
+        assert_eq!(first.status_code(), StatusCode::OK);
+        
+        // Repetição devolve a mesma partida
+        let retry = server
+            .post("/match/create")
+            .add_header("X-Player-Id", "idem1")
+            .add_header("Idempotency-Key", "chave-1")
+            .json(&body)
+            .await;
+        assert_eq!(retry.status_code(), StatusCode::OK);
+        assert_eq!(retry.json::<serde_json::Value>()["data"], first.json::<serde_json::Value>()["data"]);
+        assert!(retry.headers().contains_key("idempotency-replayed"));
+        
+        // Mesma chave, outro corpo
+        let response = server
+            .post("/match/create")
+            .add_header("X-Player-Id", "idem1")
+            .add_header("Idempotency-Key", "chave-1")
+            .json(&serde_json::json!({
+                "player1": "idem1",
+                "player2": "idem3"
+            }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(response.json::<serde_json::Value>()["error"]["code"], "idempotency_key_reused");
+        
+        // A chave é por jogador
+        let other = server
+            .post("/match/create")
+            .add_header("X-Player-Id", "idem2")
+            .add_header("Idempotency-Key", "chave-1")
+            .json(&body)
+            .await;
+        assert_eq!(other.status_code(), StatusCode::OK);
+        assert_ne!(other.json::<serde_json::Value>()["data"], first.json::<serde_json::Value>()["data"]);
+    }
+    
+    #[tokio::test]
+    async fn test_idempotency_key_bound_to_ip() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use tower::ServiceExt;
+        
+        let runs = std::sync::Arc::new(AtomicUsize::new(0));
+        let store = std::sync::Arc::new(server::idempotency::IdempotencyStore::new(
+            std::time::Duration::from_secs(60),
+        ));
+        let counter = runs.clone();
+        let app = Router::new()
+            .route(
+                "/contar",
+                axum::routing::post(move || {
+                    let runs = counter.clone();
+                    async move { runs.fetch_add(1, Ordering::SeqCst).to_string() }
+                }),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                store,
+                server::idempotency::idempotency,
+            ));
+        let request = |ip: [u8; 4]| {
+            axum::http::Request::post("/contar")
+                .header("X-Player-Id", "vitima")
+                .header("Idempotency-Key", "chave-1")
+                .extension(axum::extract::ConnectInfo(std::net::SocketAddr::from((ip, 40000))))
+                .body(axum::body::Body::empty())
+                .unwrap()
+        };
+        
+        app.clone().oneshot(request([10, 0, 0, 1])).await.unwrap();
+        let retry = app.clone().oneshot(request([10, 0, 0, 1])).await.unwrap();
+        assert!(retry.headers().contains_key("idempotency-replayed"));
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        
+        // Outro IP com o mesmo X-Player-Id não recebe a resposta guardada
+        let spoofed = app.oneshot(request([10, 0, 0, 2])).await.unwrap();
+        assert!(!spoofed.headers().contains_key("idempotency-replayed"));
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+    }
+    
+    #[tokio::test]
+    async fn test_idempotency_key_released_on_disconnect() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use tower::ServiceExt;
+        
+        // A primeira requisição fica presa até o cliente desistir
+        let first = std::sync::Arc::new(AtomicBool::new(true));
+        let store = std::sync::Arc::new(server::idempotency::IdempotencyStore::new(
+            std::time::Duration::from_secs(60),
+        ));
+        let app = Router::new()
+            .route(
+                "/lento",
+                axum::routing::post(move || {
+                    let first = first.clone();
+                    async move {
+                        if first.swap(false, Ordering::SeqCst) {
+                            tokio::time::sleep(std::time::Duration::from_secs(60)).await;
+                        }
+                        "ok"
+                    }
+                }),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                store,
+                server::idempotency::idempotency,
+            ));
+        let request = || {
+            axum::http::Request::post("/lento")
+                .header("Idempotency-Key", "chave-presa")
+                .body(axum::body::Body::empty())
+                .unwrap()
+        };
+        
+        let dropped = tokio::time::timeout(
+            std::time::Duration::from_millis(50),
+            app.clone().oneshot(request()),
+        )
+        .await;
+        assert!(dropped.is_err());
+        
+        // Sem o guard, a nova tentativa receberia 409 "em andamento"
+        let retry = app.oneshot(request()).await.unwrap();
+        assert_eq!(retry.status(), StatusCode::OK);
+    }
+    
+    #[tokio::test]
+    async fn test_metrics() {
+        let app = create_test_app().await;
+        let server = TestServer::new(app).unwrap();
+        
+        let response = server.get("/metrics").await;
+        
+        assert_eq!(response.status_code(), StatusCode::OK);
+        
+        let json: serde_json::Value = response.json();
+        assert_eq!(json["data"]["dropped_messages"], 0);
+        assert_eq!(json["data"]["resyncs"], 0);
+    }
+    
+    #[tokio::test]
+    async fn test_lobby_create_and_join() {
+        let app = create_test_app().await;
+        let server = TestServer::new(app).unwrap();
+        
+        let create_response = server
+            .post("/lobby/create")
+            .json(&serde_json::json!({
+                "host": "host1",
+                "password": "segredo"
+            }))
+            .await;
+        
+        assert_eq!(create_response.status_code(), StatusCode::OK);
+        let lobby_id = create_response.json::<serde_json::Value>()["data"]["id"].as_str().unwrap().to_string();
+        
+        let list: serde_json::Value = server.get("/lobby").await.json();
+        assert_eq!(list["data"][0]["id"], lobby_id.as_str());
+        assert_eq!(list["data"][0]["has_password"], true);
+        assert!(list["data"][0].get("password").is_none());
+        
+        // Senha errada
+        let response = server
+            .post(&format!("/lobby/{}/join", lobby_id))
+            .json(&serde_json::json!({
+                "player_id": "guest1",
+                "password": "errada"
+            }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::UNAUTHORIZED);
+        
+        let response = server
+            .post(&format!("/lobby/{}/join", lobby_id))
+            .json(&serde_json::json!({
+                "player_id": "guest1",
+                "password": "segredo"
+            }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert_eq!(response.json::<serde_json::Value>()["data"], lobby_id.as_str());
+        
+        // Partida iniciada com o anfitrião no primeiro turno
+        let state: serde_json::Value = server
+            .get(&format!("/state?match_id={}", lobby_id))
+            .await
+            .json();
+        assert_eq!(state["data"]["turn"], "host1");
+        
+        let list: serde_json::Value = server.get("/lobby").await.json();
+        assert!(list["data"].as_array().unwrap().is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_lobby_cancel_and_expiry() {
+        let state = server::state::AppState::new();
+        let server = TestServer::new(server::routes::create_routes(state.clone())).unwrap();
+        let mut events = state.lobby_events.subscribe();
+        
+        let created: serde_json::Value = server
+            .post("/lobby/create")
+            .json(&serde_json::json!({ "host": "host2" }))
+            .await
+            .json();
+        let lobby_id = created["data"]["id"].as_str().unwrap().to_string();
+        let token = created["data"]["host_token"].as_str().unwrap().to_string();
+        
+        // O token não aparece na listagem
+        let list: serde_json::Value = server.get("/lobby").await.json();
+        assert!(list["data"][0].get("host_token").is_none());
+        
+        let response = server.delete(&format!("/lobby/{}", lobby_id)).await;
+        assert_eq!(response.status_code(), StatusCode::UNAUTHORIZED);
+        let response = server
+            .delete(&format!("/lobby/{}", lobby_id))
+            .add_header("Authorization", "Bearer outro")
+            .await;
+        assert_eq!(response.status_code(), StatusCode::UNAUTHORIZED);
+        
+        let response = server
+            .delete(&format!("/lobby/{}", lobby_id))
+            .add_header("Authorization", format!("Bearer {}", token))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let list: serde_json::Value = server.get("/lobby").await.json();
+        assert!(list["data"].as_array().unwrap().is_empty());
+        
+        let _created = events.recv().await.unwrap();
+        let closed: serde_json::Value = serde_json::from_str(&events.recv().await.unwrap()).unwrap();
+        assert_eq!(closed["type"], "lobby_closed");
+        assert_eq!(closed["started"], false);
+        
+        // Entrada esquecida expira
+        let created: serde_json::Value = server
+            .post("/lobby/create")
+            .json(&serde_json::json!({ "host": "host3" }))
+            .await
+            .json();
+        let lobby_id = created["data"]["id"].as_str().unwrap().to_string();
+        let later = chrono::Utc::now()
+            + chrono::Duration::minutes(server::lobby::LOBBY_TTL_MINUTES + 1);
+        assert_eq!(state.expire_lobbies(later).await, vec![lobby_id]);
+        let list: serde_json::Value = server.get("/lobby").await.json();
+        assert!(list["data"].as_array().unwrap().is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_tournament_create_and_standings() {
+        let app = create_test_app().await;
+        let server = TestServer::new(app).unwrap();
+        
+        let create_response = server
+            .post("/tournaments/create")
+            .json(&serde_json::json!({
+                "name": "Copa",
+                "format": { "type": "single_elimination" },
+                "players": ["t1", "t2", "t3"]
+            }))
+            .await;
+        
+        assert_eq!(create_response.status_code(), StatusCode::OK);
+        let json: serde_json::Value = create_response.json();
+        let tournament_id = json["data"]["id"].as_str().unwrap().to_string();
+        
+        // Três jogadores: um confronto e uma folga na primeira rodada
+        let pairings = json["data"]["pairings"].as_array().unwrap();
+        assert_eq!(pairings.len(), 2);
+        assert!(pairings.iter().any(|p| p["player2"].is_null()));
+        assert!(pairings.iter().any(|p| p["match_id"].is_string()));
+        
+        let response = server
+            .get(&format!("/tournaments/{}/standings", tournament_id))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert_eq!(response.json::<serde_json::Value>()["data"].as_array().unwrap().len(), 3);
+        
+        let response = server.get("/tournaments/inexistente").await;
+        assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+        assert_eq!(response.json::<serde_json::Value>()["error"]["code"], "tournament_not_found");
+    }
+    
+    #[tokio::test]
+    async fn test_tournament_forfeit_requires_admin() {
+        let mut state = server::state::AppState::new();
+        state.admin_token = Some("segredo".to_string());
+        let server = TestServer::new(server::routes::create_routes(state)).unwrap();
+        
+        let response = server
+            .post("/tournaments/create")
+            .json(&serde_json::json!({
+                "name": "Copa",
+                "format": { "type": "single_elimination" },
+                "players": ["w1", "w2"]
+            }))
+            .await;
+        let tournament_id = response.json::<serde_json::Value>()["data"]["id"].as_str().unwrap().to_string();
+        let path = format!("/admin/tournaments/{}/forfeit", tournament_id);
+        
+        // Qualquer um poderia retirar outro jogador sem o token
+        let response = server.post(&path).json(&serde_json::json!({ "player_id": "w1" })).await;
+        assert_eq!(response.status_code(), StatusCode::UNAUTHORIZED);
+        
+        let response = server
+            .post(&path)
+            .add_header("Authorization", "Bearer segredo")
+            .json(&serde_json::json!({ "player_id": "w1" }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let json: serde_json::Value = response.json();
+        assert_eq!(json["data"]["champion"], "w2");
+        assert_eq!(json["data"]["withdrawn"], serde_json::json!(["w1"]));
+    }
+    
+    #[tokio::test]
+    async fn test_closing_tournament_match_is_a_draw() {
+        let mut state = server::state::AppState::new();
+        state.admin_token = Some("segredo".to_string());
+        let server = TestServer::new(server::routes::create_routes(state)).unwrap();
+        
+        let response = server
+            .post("/tournaments/create")
+            .json(&serde_json::json!({
+                "name": "Copa",
+                "format": { "type": "single_elimination" },
+                "players": ["d1", "d2"]
+            }))
+            .await;
+        let json: serde_json::Value = response.json();
+        let tournament_id = json["data"]["id"].as_str().unwrap().to_string();
+        let match_id = json["data"]["pairings"][0]["match_id"].as_str().unwrap().to_string();
+        
+        let response = server
+            .delete(&format!("/admin/matches/{}", match_id))
+            .add_header("Authorization", "Bearer segredo")
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        
+        // Empate em eliminação: o confronto é jogado de novo, sem travar o torneio
+        let json: serde_json::Value = server.get(&format!("/tournaments/{}", tournament_id)).await.json();
+        let pairings = json["data"]["pairings"].as_array().unwrap();
+        assert_eq!(pairings.len(), 2);
+        assert_eq!(pairings[0]["result"]["type"], "draw");
+        let rematch = pairings[1]["match_id"].as_str().unwrap();
+        assert_ne!(rematch, match_id);
+        let response = server.get(&format!("/state?match_id={}", rematch)).await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+    }
+    
+    #[tokio::test]
+    async fn test_admin_set_state_checks() {
+        let mut state = server::state::AppState::new();
+        state.admin_token = Some("segredo".to_string());
+        let server = TestServer::new(server::routes::create_routes(state)).unwrap();
+
+        let response = server
+            .post("/match/create")
+            .json(&serde_json::json!({ "player1": "set1", "player2": "set2" }))
+            .await;
+        let match_id = response.json::<serde_json::Value>()["data"].as_str().unwrap().to_string();
+        let json: serde_json::Value = server.get(&format!("/state?match_id={}", match_id)).await.json();
+        let current = json["data"].clone();
+        let put = |new_state: serde_json::Value| {
+            server
+                .put(&format!("/admin/matches/{}/state", match_id))
+                .add_header("Authorization", "Bearer segredo")
+                .json(&new_state)
+        };
+
+        let mut swapped = current.clone();
+        swapped["players"] = serde_json::json!(["set1", "intruso"]);
+        let response = put(swapped).await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+        assert_eq!(response.json::<serde_json::Value>()["error"]["code"], "invalid_request");
+
+        let mut finished = current.clone();
+        finished["phase"] = serde_json::json!({ "GameOver": { "winner": "set1" } });
+        assert_eq!(put(finished).await.status_code(), StatusCode::OK);
+        let mut games_played = serde_json::Value::Null;
+        for _ in 0..100 {
+            let json: serde_json::Value = server.get("/players/set1").await.json();
+            games_played = json["data"]["stats"]["games_played"].clone();
+            if games_played == 1 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        assert_eq!(games_played, 1);
+
+        // Reabrir e terminar de novo contaria o resultado duas vezes
+        let response = put(current).await;
+        assert_eq!(response.status_code(), StatusCode::CONFLICT);
+        let json: serde_json::Value = server.get("/players/set1").await.json();
+        assert_eq!(json["data"]["stats"]["games_played"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_tournament_counts_toward_match_limit() {
+        let mut state = server::state::AppState::new();
+        state.limits.max_active_matches_per_ip = 3;
+        let server = TestServer::new(app_from(state, [10, 0, 0, 3])).unwrap();
+        
+        let response = server
+            .post("/match/create")
+            .json(&serde_json::json!({ "player1": "c1", "player2": "c2" }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        
+        // A primeira rodada de 6 jogadores são 3 partidas: não cabe com a que já existe
+        let players: Vec<String> = (1..=6).map(|i| format!("c{}", i)).collect();
+        let response = server
+            .post("/tournaments/create")
+            .json(&serde_json::json!({
+                "name": "Copa cheia",
+                "format": { "type": "single_elimination" },
+                "players": players
+            }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.json::<serde_json::Value>()["error"]["code"], "too_many_matches");
+        
+        // Quatro jogadores são 2 partidas, que passam a contar no limite do IP
+        let response = server
+            .post("/tournaments/create")
+            .json(&serde_json::json!({
+                "name": "Copa",
+                "format": { "type": "single_elimination" },
+                "players": ["c1", "c2", "c3", "c4"]
+            }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let response = server
+            .post("/match/create")
+            .json(&serde_json::json!({ "player1": "c5", "player2": "c6" }))
+            .await;
+        assert_eq!(response.json::<serde_json::Value>()["error"]["code"], "too_many_matches");
+        
+        let players: Vec<String> = (0..=server::tournament::MAX_TOURNAMENT_PLAYERS)
+            .map(|i| format!("p{}", i))
+            .collect();
+        let response = server
+            .post("/tournaments/create")
+            .json(&serde_json::json!({
+                "name": "Copa enorme",
+                "format": { "type": "round_robin" },
+                "players": players
+            }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+    }
+    
+    #[tokio::test]
+    async fn test_arena_report() {
+        let mut state = server::state::AppState::new();
+        state.admin_token = Some("segredo".to_string());
+        let server = TestServer::new(server::routes::create_routes(state)).unwrap();
+        
+        let config = serde_json::json!({
+            "matches": 4,
+            "seed": 42,
+            "max_turns": 10
+        });
+        
+        // Só o administrador roda a arena
+        let response = server.post("/admin/arena/run").json(&config).await;
+        assert_eq!(response.status_code(), StatusCode::UNAUTHORIZED);
+        
+        let response = server
+            .post("/admin/arena/run")
+            .add_header("Authorization", "Bearer segredo")
+            .json(&config)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        
+        let json: serde_json::Value = response.json();
+        assert_eq!(json["data"]["matches"], 4);
+        assert_eq!(json["data"]["results"].as_array().unwrap().len(), 4);
+        assert_eq!(json["data"]["results"][1]["first_player"], "bot-b");
+        
+        // Partidas da arena não aparecem na lista
+        let list: serde_json::Value = server.get("/matches").await.json();
+        assert!(list["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|m| !m["state"]["players"].to_string().contains("bot-a")));
+        
+        let response = server
+            .post("/admin/arena/run?format=csv")
+            .add_header("Authorization", "Bearer segredo")
+            .json(&config)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let csv = response.text();
+        assert!(csv.starts_with("index,first_player,outcome"));
+        assert_eq!(csv.lines().count(), 5);
+        
+        let response = server
+            .post("/admin/arena/run")
+            .add_header("Authorization", "Bearer segredo")
+            .json(&serde_json::json!({ "matches": 0 }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+    }
+    
+    #[tokio::test]
+    async fn test_arena_seed_repeatable() {
+        let mut state = server::state::AppState::new();
+        state.admin_token = Some("segredo".to_string());
+        let server = TestServer::new(server::routes::create_routes(state)).unwrap();
+        
+        let config = serde_json::json!({
+            "matches": 6,
+            "seed": 7,
+            "randomness": 0.2,
+            "max_turns": 30,
+            "bot_a": { "type": "greedy" },
+            "bot_b": { "type": "random" }
+        });
+        
+        let mut reports = Vec::new();
+        for _ in 0..2 {
+            let response = server
+                .post("/admin/arena/run")
+                .add_header("Authorization", "Bearer segredo")
+                .json(&config)
+                .await;
+            assert_eq!(response.status_code(), StatusCode::OK);
+            let json: serde_json::Value = response.json();
+            reports.push(json["data"].clone());
+        }
+        
+        // Mesma semente com greedy/random: mesmo relatório
+        assert_eq!(reports[0], reports[1]);
+    }
+    
+    #[tokio::test]
+    async fn test_register_bot() {
+        let mut state = server::state::AppState::new();
+        state.admin_token = Some("segredo".to_string());
+        let server = TestServer::new(server::routes::create_routes(state)).unwrap();
+        
+        // Só o administrador registra bots
+        let response = server
+            .post("/admin/bots")
+            .json(&serde_json::json!({ "name": "alphabot" }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::UNAUTHORIZED);
+        
+        let response = server
+            .post("/admin/bots")
+            .add_header("Authorization", "Bearer segredo")
+            .json(&serde_json::json!({ "name": "alphabot" }))
+            .await;
+        
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let json: serde_json::Value = response.json();
+        assert_eq!(json["data"]["id"], "bot-alphabot");
+        assert!(!json["data"]["token"].as_str().unwrap().is_empty());
+        
+        // Nome repetido
+        let response = server
+            .post("/admin/bots")
+            .add_header("Authorization", "Bearer segredo")
+            .json(&serde_json::json!({ "name": "alphabot" }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::CONFLICT);
+        
+        let list: serde_json::Value = server.get("/bots").await.json();
+        assert_eq!(list["data"][0]["id"], "bot-alphabot");
+        assert!(list["data"][0].get("token").is_none());
+    }
+    
+    #[tokio::test]
+    async fn test_bot_websocket_turn() {
+        let mut state = server::state::AppState::new();
+        state.admin_token = Some("segredo".to_string());
+        state.bots.write().await.config.turn_timeout = std::time::Duration::from_secs(5);
+        let server = full_server(state);
+        
+        let token = register_bot(&server, "alfa").await;
+        let mut ws = server
+            .get_websocket(&format!("/ws/bot?token={}", token))
+            .await
+            .into_websocket()
+            .await;
+        let connected: serde_json::Value = ws.receive_json().await;
+        assert_eq!(connected["type"], "bot_connected");
+        assert_eq!(connected["player_id"], "bot-alfa");
+        
+        let response = server
+            .post("/match/create")
+            .json(&serde_json::json!({
+                "player1": "bot-alfa",
+                "player2": "humano"
+            }))
+            .await;
+        let match_id = response.json::<serde_json::Value>()["data"].as_str().unwrap().to_string();
+        
+        let turn: serde_json::Value = ws.receive_json().await;
+        assert_eq!(turn["type"], "your_turn");
+        assert_eq!(turn["match_id"], match_id);
+        assert_eq!(turn["deadline_ms"], 5000);
+        
+        // A resposta dentro do prazo é aplicada
+        ws.send_json(&serde_json::json!({
+            "type": "action",
+            "match_id": match_id,
+            "action": { "type": "EndTurn" }
+        }))
+        .await;
+        
+        assert_eq!(wait_for_turn(&server, &match_id, "humano").await, "humano");
+    }
+
+    #[tokio::test]
+    async fn test_bot_turn_played_once() {
+        let mut state = server::state::AppState::new();
+        state.admin_token = Some("segredo".to_string());
+        state.bots.write().await.config.turn_timeout = std::time::Duration::from_secs(5);
+        let server = full_server(state.clone());
+
+        let token = register_bot(&server, "beta").await;
+        let mut ws = server
+            .get_websocket(&format!("/ws/bot?token={}", token))
+            .await
+            .into_websocket()
+            .await;
+        let connected: serde_json::Value = ws.receive_json().await;
+        assert_eq!(connected["type"], "bot_connected");
+
+        let response = server
+            .post("/match/create")
+            .json(&serde_json::json!({
+                "player1": "bot-beta",
+                "player2": "humano"
+            }))
+            .await;
+        let match_id = response.json::<serde_json::Value>()["data"].as_str().unwrap().to_string();
+        let turn: serde_json::Value = ws.receive_json().await;
+        assert_eq!(turn["type"], "your_turn");
+
+        // Tasks repetidas para o mesmo turno não pedem a ação de novo
+        let handle = state.matches.read().await[&match_id].clone();
+        for _ in 0..2 {
+            let state = state.clone();
+            let handle = handle.clone();
+            tokio::spawn(async move {
+                server::bots::play_turn(&state.bots, &state.audit, handle, "bot-beta".to_string()).await;
+            });
+        }
+        let repeated = tokio::time::timeout(
+            std::time::Duration::from_millis(300),
+            ws.receive_json::<serde_json::Value>(),
+        )
+        .await;
+        assert!(repeated.is_err());
+
+        ws.send_json(&serde_json::json!({
+            "type": "action",
+            "match_id": match_id,
+            "action": { "type": "EndTurn" }
+        }))
+        .await;
+        assert_eq!(wait_for_turn(&server, &match_id, "humano").await, "humano");
+    }
+
+    #[tokio::test]
+    async fn test_bot_websocket_timeout_fallback() {
+        let mut state = server::state::AppState::new();
+        state.admin_token = Some("segredo".to_string());
+        {
+            let mut bots = state.bots.write().await;
+            bots.config.turn_timeout = std::time::Duration::from_millis(100);
+            bots.config.max_actions_per_turn = 3;
+        }
+        let audit = state.audit.clone();
+        let server = full_server(state);
+        
+        let token = register_bot(&server, "lento").await;
+        let mut ws = server
+            .get_websocket(&format!("/ws/bot?token={}", token))
+            .await
+            .into_websocket()
+            .await;
+        let connected: serde_json::Value = ws.receive_json().await;
+        assert_eq!(connected["type"], "bot_connected");
+        
+        let response = server
+            .post("/match/create")
+            .json(&serde_json::json!({
+                "player1": "bot-lento",
+                "player2": "humano"
+            }))
+            .await;
+        let match_id = response.json::<serde_json::Value>()["data"].as_str().unwrap().to_string();
+        
+        let turn: serde_json::Value = ws.receive_json().await;
+        assert_eq!(turn["type"], "your_turn");
+        assert_eq!(turn["deadline_ms"], 100);
+        
+        // Sem resposta, a IA joga no lugar do bot até a vez passar
+        assert_eq!(wait_for_turn(&server, &match_id, "humano").await, "humano");
+        assert!(audit
+            .entries()
+            .iter()
+            .any(|e| e.record.route == "BOT ai_fallback"
+                && e.record.match_id.as_deref() == Some(match_id.as_str())));
+    }
+    
+    #[tokio::test]
+    async fn test_ai_action_strategies() {
+        let app = create_test_app().await;
+        let server = TestServer::new(app).unwrap();
+        
+        let create_response = server
+            .post("/match/create")
+            .json(&serde_json::json!({
+                "player1": "ai1",
+                "player2": "ai2"
+            }))
+            .await;
+        let match_id = create_response.json::<serde_json::Value>()["data"].as_str().unwrap().to_string();
+        
+        let response = server
+            .post("/ai/action")
+            .json(&serde_json::json!({
+                "match_id": match_id,
+                "ai_player": "ai1",
+                "strategy": { "type": "greedy" },
+                "top_k": 3
+            }))
+            .await;
+        
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let json: serde_json::Value = response.json();
+        assert!(json["data"]["type"].is_string());
+        let candidates = json["data"]["candidates"].as_array().unwrap();
+        assert!(!candidates.is_empty() && candidates.len() <= 3);
+        assert!(candidates[0]["score"].is_number());
+        
+        let response = server
+            .post("/ai/action")
+            .json(&serde_json::json!({
+                "match_id": match_id,
+                "ai_player": "ai1",
+                "strategy": { "type": "lookahead", "depth": 99 }
+            }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+    }
+    
+    #[tokio::test]
+    async fn test_legal_actions() {
+        let app = create_test_app().await;
+        let server = TestServer::new(app).unwrap();
+        
+        let create_response = server
+            .post("/match/create")
+            .json(&serde_json::json!({
+                "player1": "legal1",
+                "player2": "legal2"
+            }))
+            .await;
+        let match_id = create_response.json::<serde_json::Value>()["data"].as_str().unwrap().to_string();
+        
+        let response = server
+            .get(&format!("/match/{}/legal_actions?player_id=legal1", match_id))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let json: serde_json::Value = response.json();
+        assert_eq!(json["data"]["your_turn"], true);
+        assert_eq!(json["data"]["can_end_turn"], true);
+        assert!(json["data"]["units"].is_array());
+        
+        // Fora da vez: nada é legal
+        let json: serde_json::Value = server
+            .get(&format!("/match/{}/legal_actions?player_id=legal2", match_id))
+            .await
+            .json();
+        assert_eq!(json["data"]["your_turn"], false);
+        assert_eq!(json["data"]["can_end_turn"], false);
+        
+        let response = server
+            .get(&format!("/match/{}/legal_actions?player_id=intruso", match_id))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+    }
+    
+    #[tokio::test]
+    async fn test_admin_api() {
+        let mut state = server::state::AppState::new();
+        state.admin_token = Some("segredo".to_string());
+        let server = TestServer::new(server::routes::create_routes(state)).unwrap();
+        
+        let response = server
+            .post("/match/create")
+            .json(&serde_json::json!({
+                "player1": "adm1",
+                "player2": "adm2"
+            }))
+            .await;
+        let match_id = response.json::<serde_json::Value>()["data"].as_str().unwrap().to_string();
+        
+        // Sem token, ou com o token errado
+        let response = server.get("/admin/matches").await;
+        assert_eq!(response.status_code(), StatusCode::UNAUTHORIZED);
+        let response = server
+            .get("/admin/matches")
+            .add_header("Authorization", "Bearer errado")
+            .await;
+        assert_eq!(response.status_code(), StatusCode::UNAUTHORIZED);
+        
+        let response = server
+            .get("/admin/matches")
+            .add_header("Authorization", "Bearer segredo")
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let listed = response.json::<serde_json::Value>();
+        // As partidas de exemplo também aparecem
+        assert!(listed["data"].as_array().unwrap().iter().any(|m| m["id"] == match_id.as_str()));
+        
+        // Força o fim do turno de adm1
+        let response = server
+            .post(&format!("/admin/matches/{}/end_turn", match_id))
+            .add_header("Authorization", "Bearer segredo")
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert_eq!(response.json::<serde_json::Value>()["data"]["turn"], "adm2");
+        
+        let response = server
+            .post("/admin/notice")
+            .add_header("Authorization", "Bearer segredo")
+            .json(&serde_json::json!({ "message": "Manutenção em 5 minutos" }))
+            .await;
+        // Todas as partidas ativas, incluindo as de exemplo
+        let notified = listed["data"].as_array().unwrap().len();
+        assert_eq!(response.json::<serde_json::Value>()["data"], notified);
+        
+        let response = server
+            .delete(&format!("/admin/matches/{}", match_id))
+            .add_header("Authorization", "Bearer segredo")
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let response = server.get(&format!("/state?match_id={}", match_id)).await;
+        assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+        
+        let json: serde_json::Value = server
+            .get("/admin/audit")
+            .add_header("Authorization", "Bearer segredo")
+            .await
+            .json();
+        let routes: Vec<&str> = json["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["route"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            routes,
+            [
+                "POST /match/create".to_string(),
+                format!("POST /admin/matches/{}/end_turn", match_id),
+                "POST /admin/notice".to_string(),
+                format!("DELETE /admin/matches/{}", match_id),
+            ]
+        );
+        let notified = json["data"][2]["details"]["notified"].as_array().unwrap();
+        assert!(notified.iter().any(|id| id == match_id.as_str()));
+    }
+    
+    #[tokio::test]
+    async fn test_audit_log_records_mutations() {
+        let mut state = server::state::AppState::new();
+        state.admin_token = Some("segredo".to_string());
+        let server = TestServer::new(server::routes::create_routes(state)).unwrap();
+        
+        let response = server
+            .post("/match/create")
+            .json(&serde_json::json!({
+                "player1": "aud1",
+                "player2": "aud2"
+            }))
+            .await;
+        let match_id = response.json::<serde_json::Value>()["data"].as_str().unwrap().to_string();
+        
+        for player in ["aud2", "aud1"] {
+            server
+                .post("/action")
+                .add_header("User-Agent", "cliente-teste")
+                .add_header("X-Session-Id", "sessao-1")
+                .json(&serde_json::json!({
+                    "match_id": match_id,
+                    "player_id": player,
+                    "action": { "type": "EndTurn" }
+                }))
+                .await;
+        }
+        
+        let json: serde_json::Value = server
+            .get(&format!("/admin/audit?match_id={}&route=/action", match_id))
+            .add_header("Authorization", "Bearer segredo")
+            .await
+            .json();
+        let entries = json["data"].as_array().unwrap();
+        assert_eq!(entries.len(), 2);
+        
+        // Fora da vez: recusada, nada mudou
+        assert_eq!(entries[0]["who"]["player_id"], "aud2");
+        assert_eq!(entries[0]["who"]["user_agent"], "cliente-teste");
+        assert_eq!(entries[0]["who"]["session"], "sessao-1");
+        assert_eq!(entries[0]["action"]["type"], "EndTurn");
+        assert_eq!(entries[0]["outcome"]["status"], 409);
+        assert_eq!(entries[0]["outcome"]["error"], "not_your_turn");
+        assert_eq!(entries[0]["before"], entries[0]["after"]);
+        
+        assert_eq!(entries[1]["outcome"]["status"], 200);
+        assert!(entries[1]["outcome"]["error"].is_null());
+        assert_ne!(entries[1]["before"]["turn_count"], serde_json::Value::Null);
+        
+        // Exportação em JSON Lines
+        let response = server
+            .get("/admin/audit?format=jsonl&player_id=aud1")
+            .add_header("Authorization", "Bearer segredo")
+            .await;
+        assert_eq!(response.header("content-type"), "application/x-ndjson");
+        let lines: Vec<serde_json::Value> = response
+            .text()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0]["route"], "POST /action");
+    }
+    
+    #[tokio::test]
+    async fn test_audit_log_memory_window() {
+        use server::audit::{AuditLog, AuditRecord, Outcome, Who};
+        
+        let log = AuditLog::with_capacity(3);
+        for n in 0..5 {
+            log.record(AuditRecord {
+                who: Who::default(),
+                route: format!("POST /teste/{}", n),
+                match_id: None,
+                action: None,
+                before: None,
+                after: None,
+                outcome: Outcome::of(&Ok::<(), server::error::ApiError>(())),
+                details: serde_json::json!({}),
+            });
+        }
+        
+        // Só as mais recentes ficam em memória; os IDs continuam contando
+        let entries = log.entries();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].id, 3);
+        assert_eq!(entries[2].id, 5);
+        assert_eq!(entries[2].record.route, "POST /teste/4");
+    }
+    
+    /// Servidor HTTP local que guarda as requisições recebidas
+    async fn webhook_stand_in(
+        status: StatusCode,
+    ) -> (String, std::sync::Arc<std::sync::Mutex<Vec<(axum::http::HeaderMap, String)>>>) {
+        let received = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let store = received.clone();
+        let app = axum::Router::new().route(
+            "/hook",
+            axum::routing::post(move |headers: axum::http::HeaderMap, body: String| {
+                let store = store.clone();
+                async move {
+                    store.lock().unwrap().push((headers, body));
+                    status
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (url, received)
+    }
+    
+    #[tokio::test]
+    async fn test_webhooks() {
+        let mut state = server::state::AppState::new();
+        state.admin_token = Some("segredo".to_string());
+        let server = TestServer::new(server::routes::create_routes(state)).unwrap();
+        let (url, received) = webhook_stand_in(StatusCode::OK).await;
+        
+        let response = server
+            .post("/admin/webhooks")
+            .add_header("Authorization", "Bearer segredo")
+            .json(&serde_json::json!({
+                "url": url,
+                "secret": "s3cr3t",
+                "events": ["match_created"]
+            }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let hook_id = response.json::<serde_json::Value>()["data"]["id"].as_str().unwrap().to_string();
+        
+        let response = server
+            .post("/match/create")
+            .json(&serde_json::json!({
+                "player1": "hook1",
+                "player2": "hook2"
+            }))
+            .await;
+        let match_id = response.json::<serde_json::Value>()["data"].as_str().unwrap().to_string();
+        
+        // As entregas são assíncronas
+        for _ in 0..100 {
+            if !received.lock().unwrap().is_empty() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        
+        // Assinatura confere com o corpo
+        let (headers, body) = received.lock().unwrap()[0].clone();
+        assert_eq!(headers["x-tatic-event"], "match_created");
+        let signature = format!("sha256={}", server::webhooks::sign(b"s3cr3t", body.as_bytes()));
+        assert_eq!(headers["x-tatic-signature"].to_str().unwrap(), signature);
+        let payload: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(payload["event"], "match_created");
+        assert_eq!(payload["data"]["match_id"], match_id.as_str());
+        
+        let json: serde_json::Value = server
+            .get(&format!("/admin/webhooks/{}/deliveries", hook_id))
+            .add_header("Authorization", "Bearer segredo")
+            .await
+            .json();
+        assert_eq!(json["data"][0]["status"], 200);
+        
+        // Sem token
+        let response = server.get("/admin/webhooks").await;
+        assert_eq!(response.status_code(), StatusCode::UNAUTHORIZED);
+    }
+    
+    #[tokio::test]
+    async fn test_webhook_dead_letters() {
+        use server::webhooks::{WebhookConfig, WebhookEvent, Webhooks};
+        
+        let webhooks = std::sync::Arc::new(Webhooks::new(WebhookConfig {
+            max_attempts: 2,
+            backoff: std::time::Duration::from_millis(10),
+            timeout: std::time::Duration::from_secs(1),
+        }));
+        let (url, received) = webhook_stand_in(StatusCode::INTERNAL_SERVER_ERROR).await;
+        let hook = webhooks
+            .register(url, None, vec![WebhookEvent::MatchFinished], None)
+            .unwrap();
+        
+        // Evento não assinado pelo webhook é ignorado
+        webhooks.dispatch(WebhookEvent::TurnChanged, None, serde_json::json!({}));
+        webhooks.dispatch(WebhookEvent::MatchFinished, None, serde_json::json!({}));
+        
+        for _ in 0..100 {
+            if !webhooks.dead_letters().is_empty() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        let dead_letters = webhooks.dead_letters();
+        assert_eq!(dead_letters.len(), 1);
+        assert_eq!(dead_letters[0].webhook_id, hook.webhook.id);
+        assert_eq!(dead_letters[0].attempts, 2);
+        assert_eq!(received.lock().unwrap().len(), 2);
+        
+        let deliveries = webhooks.deliveries(&hook.webhook.id).unwrap();
+        assert_eq!(deliveries.len(), 2);
+        assert_eq!(deliveries[1].status, Some(500));
+        
+        // Reenvio tira da fila de falhas
+        webhooks.retry_dead_letter(&dead_letters[0].delivery_id).unwrap();
+        assert!(webhooks.dead_letters().is_empty());
+    }
+    
+    #[test]
+    fn test_webhook_backoff_is_capped() {
+        let config = server::webhooks::WebhookConfig {
+            max_attempts: u32::MAX,
+            backoff: std::time::Duration::from_secs(1),
+            timeout: std::time::Duration::from_secs(1),
+        };
+        assert_eq!(config.backoff_before(2), std::time::Duration::from_secs(1));
+        assert_eq!(config.backoff_before(4), std::time::Duration::from_secs(4));
+        // 2^32 estouraria o u32 e a multiplicação da Duration
+        assert_eq!(config.backoff_before(34), std::time::Duration::from_secs(3600));
+        assert_eq!(config.backoff_before(u32::MAX), std::time::Duration::from_secs(3600));
+    }
+    
+    #[test]
+    fn test_webhook_signature_rfc4231() {
+        // Casos 1, 2 e 6 da RFC 4231 (o 6 tem chave maior que o bloco)
+        let vectors: [(&[u8], &[u8], &str); 3] = [
+            (
+                &[0x0b; 20],
+                b"Hi There",
+                "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7",
+            ),
+            (
+                b"Jefe",
+                b"what do ya want for nothing?",
+                "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+            ),
+            (
+                &[0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First",
+                "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54",
+            ),
+        ];
+        for (key, data, expected) in vectors {
+            assert_eq!(server::webhooks::sign(key, data), expected);
+        }
+    }
+    
+    /// Canal de aviso que só guarda o que recebeu
+    struct RecordingNotifier(std::sync::Mutex<Vec<server::notifier::TurnNotification>>);
+    
+    impl server::notifier::Notifier for RecordingNotifier {
+        fn name(&self) -> &str {
+            "test"
+        }
+        
+        fn notify(&self, notification: &server::notifier::TurnNotification) {
+            self.0.lock().unwrap().push(notification.clone());
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_correspondence_match() {
+        let state = server::state::AppState::new();
+        let notifier = std::sync::Arc::new(RecordingNotifier(Default::default()));
+        state.notifiers.add(notifier.clone());
+        let server = TestServer::new(server::routes::create_routes(state)).unwrap();
+        
+        let response = server
+            .post("/match/create")
+            .json(&serde_json::json!({
+                "player1": "corr1",
+                "player2": "corr2",
+                "mode": { "type": "correspondence", "turn_deadline_hours": 0 }
+            }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+        
+        let response = server
+            .post("/match/create")
+            .json(&serde_json::json!({
+                "player1": "corr1",
+                "player2": "corr2",
+                "mode": { "type": "correspondence", "turn_deadline_hours": 48 }
+            }))
+            .await;
+        let match_id = response.json::<serde_json::Value>()["data"].as_str().unwrap().to_string();
+        
+        let json: serde_json::Value = server.get("/players/corr1/my_turn").await.json();
+        let pending = json["data"].as_array().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0]["match_id"], match_id.as_str());
+        assert_eq!(pending[0]["opponents"], serde_json::json!(["corr2"]));
+        assert_eq!(pending[0]["mode"]["turn_deadline_hours"], 48);
+        let started: chrono::DateTime<chrono::Utc> =
+            serde_json::from_value(pending[0]["turn_started_at"].clone()).unwrap();
+        let deadline: chrono::DateTime<chrono::Utc> =
+            serde_json::from_value(pending[0]["deadline"].clone()).unwrap();
+        assert_eq!(deadline - started, chrono::Duration::hours(48));
+        
+        let json: serde_json::Value = server.get("/players/corr2/my_turn").await.json();
+        assert!(json["data"].as_array().unwrap().is_empty());
+        
+        // Os avisos saem do listener de eventos
+        let wait_for = |count: usize| {
+            let notifier = notifier.clone();
+            async move {
+                for _ in 0..100 {
+                    if notifier.0.lock().unwrap().len() >= count {
+                        break;
+                    }
+                    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+                }
+            }
+        };
+        wait_for(1).await;
+        
+        server
+            .post("/action")
+            .json(&serde_json::json!({
+                "match_id": match_id,
+                "player_id": "corr1",
+                "action": { "type": "EndTurn" }
+            }))
+            .await;
+        let json: serde_json::Value = server.get("/players/corr2/my_turn").await.json();
+        assert_eq!(json["data"][0]["match_id"], match_id.as_str());
+        
+        wait_for(2).await;
+        let sent = notifier.0.lock().unwrap().clone();
+        let players: Vec<_> = sent.iter().map(|n| n.player_id.as_str()).collect();
+        assert_eq!(players, ["corr1", "corr2"]);
+        assert!(sent.iter().all(|n| n.match_id == match_id));
+    }
+    
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_new_match_events_find_registered_match() {
+        use server::correspondence::MatchStore;
+        
+        // Com várias threads o listener pode tratar os eventos de início antes
+        // de `create` voltar; a partida já precisa estar registrada
+        let dir = std::env::temp_dir().join(format!("tatic-store-{}", uuid::Uuid::new_v4()));
+        let mut state = server::state::AppState::with_store(MatchStore::new(Some(dir.clone())));
+        state.limits.create.burst = 10;
+        let notifier = std::sync::Arc::new(RecordingNotifier(Default::default()));
+        state.notifiers.add(notifier.clone());
+        let store = state.correspondence.clone();
+        let server = TestServer::new(server::routes::create_routes(state)).unwrap();
+        
+        let mut created = Vec::new();
+        for i in 0..10 {
+            let response = server
+                .post("/match/create")
+                .json(&serde_json::json!({
+                    "player1": format!("race{}", i),
+                    "player2": "rival",
+                    "mode": { "type": "correspondence", "turn_deadline_hours": 24 }
+                }))
+                .await;
+            created.push(response.json::<serde_json::Value>()["data"].as_str().unwrap().to_string());
+        }
+        
+        let mut saved = Vec::new();
+        for _ in 0..100 {
+            saved = store.load().await.into_iter().map(|m| m.id).collect();
+            if saved.len() == created.len() && notifier.0.lock().unwrap().len() == created.len() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        saved.sort();
+        created.sort();
+        assert_eq!(saved, created);
+        let mut notified: Vec<_> = notifier.0.lock().unwrap().iter().map(|n| n.match_id.clone()).collect();
+        notified.sort();
+        assert_eq!(notified, created);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+    
+    #[tokio::test]
+    async fn test_correspondence_turn_deadline_expires() {
+        use server::correspondence::MatchMode;
+        
+        let mut match_data = server::state::Match::new("late1".to_string(), "late2".to_string());
+        match_data.mode = MatchMode::Correspondence { turn_deadline_hours: 24 };
+        match_data.turn_started_at = chrono::Utc::now() - chrono::Duration::hours(25);
+        let (events, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let handle = server::match_actor::MatchHandle::spawn(match_data, 16, events);
+        
+        let snapshot = handle.snapshot().await.unwrap();
+        assert_eq!(snapshot.state.turn, "late2");
+        assert_eq!(snapshot.actions.len(), 1);
+        assert_eq!(snapshot.actions[0].player_id, "late1");
+        // O prazo do próximo jogador começa agora
+        let deadline = snapshot.turn_deadline().unwrap();
+        assert!(deadline > chrono::Utc::now() + chrono::Duration::hours(23));
+    }
+    
+    #[tokio::test]
+    async fn test_correspondence_store_round_trip() {
+        use server::correspondence::{MatchMode, MatchStore};
+        
+        let dir = std::env::temp_dir().join(format!("tatic-store-{}", uuid::Uuid::new_v4()));
+        let store = MatchStore::new(Some(dir.clone()));
+        assert!(store.load().await.is_empty());
+        
+        let mut match_data = server::state::Match::new("disk1".to_string(), "disk2".to_string());
+        match_data.mode = MatchMode::Correspondence { turn_deadline_hours: 72 };
+        store.save(&match_data).await;
+        
+        let loaded = store.load().await;
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].id, match_data.id);
+        assert_eq!(loaded[0].mode, match_data.mode);
+        assert_eq!(loaded[0].turn_deadline(), match_data.turn_deadline());
+        
+        store.remove(&match_data.id).await;
+        assert!(store.load().await.is_empty());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+    
+    #[tokio::test]
+    async fn test_correspondence_restore_before_serving() {
+        use server::correspondence::{MatchMode, MatchStore};
+        
+        let dir = std::env::temp_dir().join(format!("tatic-store-{}", uuid::Uuid::new_v4()));
+        let store = MatchStore::new(Some(dir.clone()));
+        let mut match_data = server::state::Match::new("back1".to_string(), "back2".to_string());
+        match_data.mode = MatchMode::Correspondence { turn_deadline_hours: 72 };
+        store.save(&match_data).await;
+        
+        let mut state = server::state::AppState::new();
+        state.correspondence = std::sync::Arc::new(store);
+        state.restore_correspondence_matches().await;
+        let server = TestServer::new(server::routes::create_routes(state)).unwrap();
+        
+        // Disponível na primeira requisição, sem esperar nenhuma task
+        let response = server.get(&format!("/state?match_id={}", match_data.id)).await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let json: serde_json::Value = server.get("/players/back1/matches").await.json();
+        assert_eq!(json["data"]["total"], 1);
+        assert_eq!(json["data"]["items"][0]["match_id"], match_data.id.as_str());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+    
+    #[tokio::test]
+    async fn test_player_profiles() {
+        let mut state = server::state::AppState::new();
+        state.admin_token = Some("segredo".to_string());
+        let server = TestServer::new(server::routes::create_routes(state)).unwrap();
+        
+        let response = server.get("/players/prof1").await;
+        assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+        assert_eq!(response.json::<serde_json::Value>()["error"]["code"], "player_not_found");
+        
+        // Jogadores ganham perfil ao entrar numa partida
+        server
+            .post("/match/create")
+            .json(&serde_json::json!({
+                "player1": "prof1",
+                "player2": "prof2"
+            }))
+            .await;
+        let mut json = serde_json::Value::Null;
+        for _ in 0..100 {
+            let response = server.get("/players/prof1").await;
+            if response.status_code() == StatusCode::OK {
+                json = response.json();
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        assert_eq!(json["data"]["display_name"], "prof1");
+        assert_eq!(json["data"]["avatar"], "P");
+        assert_eq!(json["data"]["stats"]["games_played"], 0);
+        
+        // Só o administrador edita perfis
+        let update = serde_json::json!({ "display_name": "  Ana  ", "avatar": "&" });
+        let response = server.post("/admin/players/prof1").json(&update).await;
+        assert_eq!(response.status_code(), StatusCode::UNAUTHORIZED);
+        
+        let response = server
+            .post("/admin/players/prof1")
+            .add_header("Authorization", "Bearer segredo")
+            .json(&update)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let json: serde_json::Value = server.get("/players/prof1").await.json();
+        assert_eq!(json["data"]["display_name"], "Ana");
+        assert_eq!(json["data"]["avatar"], "&");
+        
+        for invalid in [
+            serde_json::json!({ "avatar": " " }),
+            serde_json::json!({ "avatar": "é" }),
+            serde_json::json!({ "display_name": "" }),
+        ] {
+            let response = server
+                .post("/admin/players/prof1")
+                .add_header("Authorization", "Bearer segredo")
+                .json(&invalid)
+                .await;
+            assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+        }
+        
+        // Idioma preferido também só pelo administrador
+        let response = server
+            .post("/admin/players/prof1/language")
+            .json(&serde_json::json!({ "lang": "en" }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::UNAUTHORIZED);
+        let response = server
+            .post("/admin/players/prof1/language")
+            .add_header("Authorization", "Bearer segredo")
+            .json(&serde_json::json!({ "lang": "en" }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let json: serde_json::Value = server
+            .get("/state?match_id=inexistente")
+            .add_header("X-Player-Id", "prof1")
+            .await
+            .json();
+        assert_eq!(json["error"]["message"], "Match inexistente not found");
+    }
+    
+    #[tokio::test]
+    async fn test_player_match_history() {
+        let mut state = server::state::AppState::new();
+        state.admin_token = Some("segredo".to_string());
+        let server = TestServer::new(server::routes::create_routes(state)).unwrap();
+        
+        let mut match_ids = Vec::new();
+        for opponent in ["hist2", "hist3", "hist4"] {
+            let response = server
+                .post("/match/create")
+                .json(&serde_json::json!({
+                    "player1": "hist1",
+                    "player2": opponent
+                }))
+                .await;
+            match_ids.push(response.json::<serde_json::Value>()["data"].as_str().unwrap().to_string());
+        }
+        server
+            .post("/action")
+            .json(&serde_json::json!({
+                "match_id": match_ids[0],
+                "player_id": "hist1",
+                "action": { "type": "EndTurn" }
+            }))
+            .await;
+        server
+            .delete(&format!("/admin/matches/{}", match_ids[2]))
+            .add_header("Authorization", "Bearer segredo")
+            .await;
+        
+        // O índice é atualizado pelo listener de eventos
+        let mut json = serde_json::Value::Null;
+        for _ in 0..100 {
+            json = server.get("/players/hist1/matches").await.json();
+            if json["data"]["total"] == 3 && json["data"]["items"][2]["turn_count"] != 0 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        let items = json["data"]["items"].as_array().unwrap();
+        assert_eq!(items.len(), 3);
+        // Mais novas primeiro
+        assert_eq!(items[0]["match_id"], match_ids[2].as_str());
+        assert_eq!(items[0]["status"], "closed");
+        assert!(items[0]["links"]["export"].is_null());
+        assert_eq!(items[2]["match_id"], match_ids[0].as_str());
+        assert_eq!(items[2]["status"], "active");
+        assert_eq!(items[2]["opponents"], serde_json::json!(["hist2"]));
+        assert!(items[2]["outcome"].is_null());
+        assert_eq!(
+            items[2]["links"]["export"],
+            format!("/match/{}/export", match_ids[0]).as_str()
+        );
+        
+        let json: serde_json::Value = server
+            .get("/players/hist1/matches?page=2&per_page=2&status=active")
+            .await
+            .json();
+        assert_eq!(json["data"]["total"], 2);
+        assert_eq!(json["data"]["page"], 2);
+        assert_eq!(json["data"]["items"].as_array().unwrap().len(), 0);
+        
+        let json: serde_json::Value = server.get("/players/hist3/matches").await.json();
+        assert_eq!(json["data"]["total"], 1);
+        assert_eq!(json["data"]["items"][0]["opponents"], serde_json::json!(["hist1"]));
+    }
+    
+    #[tokio::test]
+    async fn test_create_match_with_settings() {
+        // Sem ConnectInfo, todas as requisições dividem o mesmo bucket
+        let mut state = server::state::AppState::new();
+        state.limits.create.burst = 20;
+        let server = TestServer::new(server::routes::create_routes(state)).unwrap();
+        
+        let response = server
+            .post("/match/create")
+            .json(&serde_json::json!({
+                "player1": "conf1",
+                "player2": "conf2",
+                "settings": {
+                    "starting_player": "player2",
+                    "time_control": { "turn_secs": 60 }
+                }
+            }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let match_id = response.json::<serde_json::Value>()["data"].as_str().unwrap().to_string();
+        
+        let json: serde_json::Value = server
+            .get(&format!("/state?match_id={}", match_id))
+            .await
+            .json();
+        assert_eq!(json["data"]["turn"], "conf2");
+        
+        // Configurações guardadas na partida; sem sorteio, sem semente
+        let json: serde_json::Value = server.get("/matches").await.json();
+        let listed = |json: &serde_json::Value, id: &str| {
+            json["data"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|m| m["id"] == id)
+                .unwrap()
+                .clone()
+        };
+        let fixed = listed(&json, &match_id);
+        assert_eq!(fixed["settings"]["starting_player"], "player2");
+        assert_eq!(fixed["settings"]["time_control"]["turn_secs"], 60);
+        assert!(fixed["settings"]["seed"].is_null());
+        assert!(fixed["turn_deadline"].is_string());
+        
+        // Com sorteio, a semente gerada fica guardada
+        let response = server
+            .post("/match/create")
+            .json(&serde_json::json!({
+                "player1": "conf1",
+                "player2": "conf2",
+                "settings": { "starting_player": "random" }
+            }))
+            .await;
+        let random_id = response.json::<serde_json::Value>()["data"].as_str().unwrap().to_string();
+        let json: serde_json::Value = server.get("/matches").await.json();
+        assert!(listed(&json, &random_id)["settings"]["seed"].is_u64());
+        
+        // O mapa define o tamanho do tabuleiro
+        let response = server
+            .post("/match/create")
+            .json(&serde_json::json!({
+                "player1": "conf1",
+                "player2": "conf2",
+                "settings": { "map": "corridor" }
+            }))
+            .await;
+        let match_id = response.json::<serde_json::Value>()["data"].as_str().unwrap().to_string();
+        let json: serde_json::Value = server
+            .get(&format!("/state?match_id={}", match_id))
+            .await
+            .json();
+        assert_eq!(json["data"]["width"], 16);
+        assert_eq!(json["data"]["height"], 6);
+        
+        // Nem o lobby aceita configurações inválidas
+        let response = server
+            .post("/lobby/create")
+            .json(&serde_json::json!({ "host": "conf5", "settings": { "map": "lua" } }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+        
+        for (settings, mode) in [
+            (serde_json::json!({ "map": "lua" }), serde_json::json!({ "type": "live" })),
+            (
+                serde_json::json!({ "board": { "width": 2, "height": 2 } }),
+                serde_json::json!({ "type": "live" }),
+            ),
+            (
+                serde_json::json!({ "time_control": { "turn_secs": 1 } }),
+                serde_json::json!({ "type": "live" }),
+            ),
+            (
+                serde_json::json!({ "time_control": { "turn_secs": 60 } }),
+                serde_json::json!({ "type": "correspondence", "turn_deadline_hours": 24 }),
+            ),
+        ] {
+            let response = server
+                .post("/match/create")
+                .json(&serde_json::json!({
+                    "player1": "conf3",
+                    "player2": "conf4",
+                    "mode": mode,
+                    "settings": settings
+                }))
+                .await;
+            assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+            assert_eq!(
+                response.json::<serde_json::Value>()["error"]["code"],
+                "invalid_request"
+            );
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_fog_hides_distant_units() {
+        let state = server::state::AppState::new();
+        let server = TestServer::new(server::routes::create_routes(state.clone())).unwrap();
+
+        // Tabuleiro 8x8: fog1 começa em (3, 0) e fog2 em (3, 7)
+        let response = server
+            .post("/match/create")
+            .json(&serde_json::json!({
+                "player1": "fog1",
+                "player2": "fog2",
+                "settings": { "fog": true, "board": { "width": 8, "height": 8 } }
+            }))
+            .await;
+        let match_id = response.json::<serde_json::Value>()["data"].as_str().unwrap().to_string();
+        let units = |json: &serde_json::Value| json["data"]["units"].as_array().unwrap().len();
+
+        let json = server
+            .get(&format!("/state?match_id={}&player_id=fog1", match_id))
+            .await
+            .json::<serde_json::Value>();
+        assert_eq!(units(&json), 1);
+        assert_eq!(json["data"]["units"][0]["owner"], "fog1");
+        // Espectador não vê unidade nenhuma
+        let json = server.get(&format!("/state?match_id={}", match_id)).await.json::<serde_json::Value>();
+        assert_eq!(units(&json), 0);
+
+        // A 4 casas, fog2 continua escondido
+        let action = |player: &str, action: serde_json::Value| {
+            serde_json::json!({ "match_id": match_id, "player_id": player, "action": action })
+        };
+        let step = |unit: u32, y: i32| {
+            serde_json::json!({ "type": "Move", "unit_id": unit, "to": { "x": 3, "y": y } })
+        };
+        let json = server
+            .post("/action")
+            .json(&action("fog1", step(1, 3)))
+            .await
+            .json::<serde_json::Value>();
+        assert_eq!(units(&json), 1);
+        server.post("/action").json(&action("fog1", serde_json::json!({ "type": "EndTurn" }))).await;
+
+        // A 3 casas, as duas se veem
+        let json = server
+            .post("/action")
+            .json(&action("fog2", step(2, 6)))
+            .await
+            .json::<serde_json::Value>();
+        assert_eq!(units(&json), 2);
+
+        // O documento traz todas as jogadas: só depois do fim
+        let response = server.get(&format!("/match/{}/export", match_id)).await;
+        assert_eq!(response.status_code(), StatusCode::CONFLICT);
+
+        let ws = ws_server(state);
+        let mut socket = ws
+            .get_websocket(&format!("/ws?match_id={}&player_id=fog1", match_id))
+            .await
+            .into_websocket()
+            .await;
+        let initial: serde_json::Value = socket.receive_json().await;
+        assert_eq!(initial["state"]["units"].as_array().unwrap().len(), 2);
+        server.post("/action").json(&action("fog2", step(2, 7))).await;
+        let update: serde_json::Value = socket.receive_json().await;
+        assert_eq!(update["type"], "state_update");
+        assert_eq!(update["state"]["units"].as_array().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_replay_refuses_unfinished_match() {
+        let state = server::state::AppState::new();
+        let match_data = server::state::Match::new("rep1".to_string(), "rep2".to_string());
+        let match_id = match_data.id.clone();
+        let handle = server::match_actor::MatchHandle::spawn(match_data, 16, state.match_events.clone());
+        state.matches.write().await.insert(match_id.clone(), handle);
+        let server = ws_server(state);
+        
+        let mut ws = server
+            .get_websocket(&format!("/ws/replay?match_id={}", match_id))
+            .await
+            .into_websocket()
+            .await;
+        let error: serde_json::Value = ws.receive_json().await;
+        assert_eq!(error["type"], "error");
+        assert_eq!(error["error"]["code"], "conflict");
+        
+        let mut ws = server
+            .get_websocket("/ws/replay?match_id=inexistente")
+            .await
+            .into_websocket()
+            .await;
+        let error: serde_json::Value = ws.receive_json().await;
+        assert_eq!(error["error"]["code"], "match_not_found");
+    }
+    
+    #[tokio::test]
+    async fn test_websocket_errors_use_connection_language() {
+        let server = ws_server(server::state::AppState::new());
+        
+        let mut ws = server
+            .get_websocket("/ws/tournament?tournament_id=inexistente&lang=en")
+            .await
+            .into_websocket()
+            .await;
+        let error: serde_json::Value = ws.receive_json().await;
+        assert_eq!(error["error"]["code"], "tournament_not_found");
+        assert_eq!(error["error"]["message"], "Tournament inexistente not found");
+        
+        let mut ws = server
+            .get_websocket("/ws/bot?token=errado")
+            .add_header("Accept-Language", "en-US")
+            .await
+            .into_websocket()
+            .await;
+        let error: serde_json::Value = ws.receive_json().await;
+        assert_eq!(error["error"]["code"], "unauthorized");
+        assert_eq!(error["error"]["message"], "Unauthorized");
+    }
+    
+    #[tokio::test]
+    async fn test_replay_seek_and_step() {
+        let state = server::state::AppState::new();
+        let match_id = finished_match(&state, 3).await;
+        let server = ws_server(state);
+        
+        // Na velocidade mínima nenhum estado avança sozinho durante o teste
+        let mut ws = server
+            .get_websocket(&format!("/ws/replay?match_id={}&speed=0.1", match_id))
+            .await
+            .into_websocket()
+            .await;
+        let initial: serde_json::Value = ws.receive_json().await;
+        assert_eq!(initial["type"], "initial_state");
+        assert_eq!(initial["replay"]["frame"], 0);
+        assert_eq!(initial["replay"]["frames"], 4);
+        
+        ws.send_json(&serde_json::json!({ "type": "step_forward" })).await;
+        let frame: serde_json::Value = ws.receive_json().await;
+        assert_eq!(frame["type"], "state_update");
+        assert_eq!(frame["replay"]["frame"], 1);
+        let status: serde_json::Value = ws.receive_json().await;
+        assert_eq!(status["type"], "replay_status");
+        assert_eq!(status["replay"]["paused"], true);
+        
+        // Além do último turno, para no último estado
+        ws.send_json(&serde_json::json!({ "type": "seek", "turn": 1000 })).await;
+        let frame: serde_json::Value = ws.receive_json().await;
+        assert_eq!(frame["replay"]["frame"], 3);
+        assert_eq!(frame["replay"]["finished"], true);
+        let _status: serde_json::Value = ws.receive_json().await;
+        
+        ws.send_json(&serde_json::json!({ "type": "step_back" })).await;
+        let frame: serde_json::Value = ws.receive_json().await;
+        assert_eq!(frame["replay"]["frame"], 2);
+        let _status: serde_json::Value = ws.receive_json().await;
+        
+        ws.send_json(&serde_json::json!({ "type": "seek", "turn": 0 })).await;
+        let frame: serde_json::Value = ws.receive_json().await;
+        assert_eq!(frame["replay"]["frame"], 0);
+        assert_eq!(frame["state"], initial["state"]);
+    }
+    
+    #[tokio::test]
+    async fn test_replay_speed_bounds() {
+        let state = server::state::AppState::new();
+        let match_id = finished_match(&state, 3).await;
+        let server = ws_server(state);
+        
+        let mut ws = server
+            .get_websocket(&format!("/ws/replay?match_id={}&speed=100", match_id))
+            .await
+            .into_websocket()
+            .await;
+        let error: serde_json::Value = ws.receive_json().await;
+        assert_eq!(error["error"]["code"], "invalid_request");
+        
+        let mut ws = server
+            .get_websocket(&format!("/ws/replay?match_id={}&speed=0.1", match_id))
+            .await
+            .into_websocket()
+            .await;
+        let _initial: serde_json::Value = ws.receive_json().await;
+        
+        for speed in [0.01, 16.5] {
+            ws.send_json(&serde_json::json!({ "type": "speed", "speed": speed })).await;
+            let error: serde_json::Value = ws.receive_json().await;
+            assert_eq!(error["type"], "error");
+            assert_eq!(error["error"]["code"], "invalid_request");
+        }
+        
+        ws.send_json(&serde_json::json!({ "type": "speed", "speed": 16.0 })).await;
+        let status: serde_json::Value = ws.receive_json().await;
+        assert_eq!(status["type"], "replay_status");
+        assert_eq!(status["replay"]["speed"], 16.0);
+    }
+    
+    #[tokio::test]
+    async fn test_replay_pause_and_resume() {
+        let state = server::state::AppState::new();
+        let match_id = finished_match(&state, 3).await;
+        let server = ws_server(state);
+        
+        // 0.1 s por estado
+        let mut ws = server
+            .get_websocket(&format!("/ws/replay?match_id={}&speed=10", match_id))
+            .await
+            .into_websocket()
+            .await;
+        let _initial: serde_json::Value = ws.receive_json().await;
+        
+        ws.send_json(&serde_json::json!({ "type": "pause" })).await;
+        let paused = loop {
+            let message: serde_json::Value = ws.receive_json().await;
+            if message["type"] == "replay_status" {
+                break message;
+            }
+        };
+        assert_eq!(paused["replay"]["paused"], true);
+        let frame = paused["replay"]["frame"].as_u64().unwrap();
+        assert!(frame < 3);
+        
+        // Pausado, nada chega; o próximo status mostra o mesmo estado
+        tokio::time::sleep(std::time::Duration::from_millis(350)).await;
+        ws.send_json(&serde_json::json!({ "type": "speed", "speed": 10.0 })).await;
+        let status: serde_json::Value = ws.receive_json().await;
+        assert_eq!(status["type"], "replay_status");
+        assert_eq!(status["replay"]["frame"], frame);
+        
+        ws.send_json(&serde_json::json!({ "type": "resume" })).await;
+        let status: serde_json::Value = ws.receive_json().await;
+        assert_eq!(status["replay"]["paused"], false);
+        let next: serde_json::Value = ws.receive_json().await;
+        assert_eq!(next["type"], "state_update");
+        assert_eq!(next["replay"]["frame"], frame + 1);
+    }
+    
+    #[tokio::test]
+    async fn test_websocket_missed_pings_disconnect() {
+        let mut state = server::state::AppState::new();
+        state.heartbeat = server::websocket::HeartbeatConfig {
+            interval: std::time::Duration::from_millis(50),
+            max_missed: 2,
+        };
+        let server = ws_server(state);
+        
+        let mut ws = server.get_websocket("/ws/lobby").await.into_websocket().await;
+        let snapshot: serde_json::Value = ws.receive_json().await;
+        assert_eq!(snapshot["type"], "lobby_snapshot");
+        
+        // Sem ler o socket, nenhum pong é enviado
+        tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+        
+        loop {
+            match ws.receive_message().await {
+                axum_test::WsMessage::Ping(_) => continue,
+                axum_test::WsMessage::Close(_) => break,
+                other => panic!("mensagem inesperada: {:?}", other),
+            }
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_openapi_document() {
+        let app = create_test_app().await.merge(server::openapi::openapi_routes());
+        let server = TestServer::new(app).unwrap();
+        
+        let response = server.get("/openapi.json").await;
+        
+        assert_eq!(response.status_code(), StatusCode::OK);
+        
+        let json: serde_json::Value = response.json();
+        assert!(json["openapi"].as_str().unwrap().starts_with("3."));
+        assert!(json["components"]["schemas"]["GameState"].is_object());
+        assert!(json["components"]["schemas"]["Action"].is_object());
+    }
+    
+    #[tokio::test]
+    async fn test_all_routes_documented() {
+        use utoipa::OpenApi;
+        
+        let doc = server::openapi::ApiDoc::openapi();
+        let state = server::state::AppState::new();
+        let server = full_server(state);
+        let root: serde_json::Value = server.get("/").await.json();
+        let listed = root["endpoints"].as_object().unwrap();
+        
+        // Toda rota registrada em qualquer módulo aparece no OpenAPI ou, se
+        // for WebSocket, na listagem de GET /
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let source = std::fs::read_to_string(entry.unwrap().path()).unwrap();
+            for line in source.lines().filter(|l| l.trim_start().starts_with(".route(\"")) {
+                let path = line.split('"').nth(1).unwrap();
+                if path.starts_with("/ws") {
+                    let documented = listed.keys().any(|k| {
+                        k.strip_prefix("WS ").is_some_and(|e| e.split('?').next() == Some(path))
+                    });
+                    assert!(documented, "WebSocket {} não listado em GET /", path);
+                } else {
+                    assert!(
+                        doc.paths.paths.contains_key(path),
+                        "Rota {} não documentada no OpenAPI",
+                        path
+                    );
+                }
+            }
+        }
+        
+        // E todo endpoint listado responde no app montado: o fallback do
+        // router devolve 404 sem corpo, e método errado devolve 405
+        for endpoint in listed.keys() {
+            let (method, path) = endpoint.split_once(' ').unwrap();
+            let path = path.split('?').next().unwrap().replace("{id}", "inexistente");
+            let response = match method {
+                "GET" | "WS" => server.get(&path).await,
+                "POST" => server.post(&path).await,
+                "PUT" => server.put(&path).await,
+                "DELETE" => server.delete(&path).await,
+                _ => panic!("Método inesperado em {}", endpoint),
+            };
+            let status = response.status_code();
+            assert_ne!(status, StatusCode::METHOD_NOT_ALLOWED, "{} não roteado", endpoint);
+            assert!(
+                status != StatusCode::NOT_FOUND || !response.as_bytes().is_empty(),
+                "{} não roteado",
+                endpoint
+            );
+        }
+    }
+    
     async fn create_test_app() -> Router {
-        let state = crate::state::AppState::new();
-        crate::routes::create_routes(state)
+        let state = server::state::AppState::new();
+        server::routes::create_routes(state)
+    }
+    
+    /// Servidor HTTP de verdade com as rotas WebSocket
+    fn ws_server(state: server::state::AppState) -> TestServer {
+        TestServer::builder()
+            .http_transport()
+            .build(server::websocket::websocket_routes(state))
+            .unwrap()
+    }
+    
+    /// Partida terminada com `turns` passagens de vez gravadas, direto no estado
+    async fn finished_match(state: &server::state::AppState, turns: usize) -> String {
+        let mut match_data = server::state::Match::new("rep1".to_string(), "rep2".to_string());
+        for i in 0..turns {
+            match_data.actions.push(server::state::RecordedAction {
+                player_id: if i % 2 == 0 { "rep1" } else { "rep2" }.to_string(),
+                action: Action::EndTurn,
+            });
+        }
+        match_data.state.phase = tatic_lib::Phase::GameOver {
+            winner: Some("rep1".to_string()),
+        };
+        let match_id = match_data.id.clone();
+        let handle = server::match_actor::MatchHandle::spawn(match_data, 16, state.match_events.clone());
+        state.matches.write().await.insert(match_id.clone(), handle.clone());
+        handle.announce().await;
+        match_id
+    }
+    
+    /// HTTP e WebSocket no mesmo servidor, como em `main`
+    fn full_server(state: server::state::AppState) -> TestServer {
+        let app = server::routes::create_routes(state.clone())
+            .merge(server::websocket::websocket_routes(state));
+        TestServer::builder().http_transport().build(app).unwrap()
+    }
+    
+    /// Registra bot com o token de administrador `segredo` e devolve o token do bot
+    async fn register_bot(server: &TestServer, name: &str) -> String {
+        let response = server
+            .post("/admin/bots")
+            .add_header("Authorization", "Bearer segredo")
+            .json(&serde_json::json!({ "name": name }))
+            .await;
+        response.json::<serde_json::Value>()["data"]["token"].as_str().unwrap().to_string()
+    }
+    
+    /// Espera a vez passar para `player` (ou desiste após ~2s) e devolve de quem é a vez
+    async fn wait_for_turn(server: &TestServer, match_id: &str, player: &str) -> String {
+        let mut turn = String::new();
+        for _ in 0..40 {
+            let response = server.get(&format!("/state?match_id={}", match_id)).await;
+            turn = response.json::<serde_json::Value>()["data"]["turn"].as_str().unwrap_or_default().to_string();
+            if turn == player {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+        turn
+    }
+    
+    /// App com as requisições vindo de `ip`, como atrás de `serve`
+    fn app_from(state: server::state::AppState, ip: [u8; 4]) -> Router {
+        let addr = std::net::SocketAddr::from((ip, 40000));
+        server::routes::create_routes(state)
+            .layer(axum::Extension(axum::extract::ConnectInfo(addr)))
     }
 }